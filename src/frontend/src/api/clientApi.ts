@@ -1,30 +1,80 @@
 import { ApiResponse } from '@calimero-network/calimero-client';
 
-export interface Message {
-  id: string;
-  proposal_id: string;
-  author: string;
-  text: string;
-  created_at: string;
-}
+import type {
+  AllowedUses,
+  ApproveProposalRequest,
+  CancelProposalRequest,
+  CreateNewProposalRequest,
+  CreateProposalRequest,
+  DeleteProposalMessageRequest,
+  EditProposalMessageRequest,
+  EntryKind,
+  Event,
+  GetProposalMessagesRequest,
+  LedgerEntry,
+  License,
+  LicenseKind,
+  ListingFilter,
+  ListingKind,
+  ListingPage,
+  ListingSort,
+  ListingSummary,
+  Message,
+  MessagePage,
+  Order,
+  OrderItem,
+  OrderStatus,
+  Plan,
+  Price,
+  ProposalId,
+  Reply,
+  Review,
+  SendProposalMessageRequest,
+  Statement,
+  Subscription,
+} from './generated/getem-types';
 
-export interface GetProposalMessagesRequest {
-  // proposalId: String;
-  proposal_id: string;
-}
+// The types the app sends and receives, and its events, are generated
+// from the app's Rust types (src/types), so they cannot drift from the
+// method signatures.
+export type {
+  AllowedUses,
+  ApproveProposalRequest,
+  CancelProposalRequest,
+  CreateNewProposalRequest,
+  CreateProposalRequest,
+  DeleteProposalMessageRequest,
+  EditProposalMessageRequest,
+  EntryKind,
+  Event,
+  GetProposalMessagesRequest,
+  LedgerEntry,
+  License,
+  LicenseKind,
+  ListingFilter,
+  ListingKind,
+  ListingSort,
+  ListingSummary,
+  Message,
+  Order,
+  OrderItem,
+  OrderStatus,
+  Plan,
+  Price,
+  ProposalId,
+  Reply,
+  Review,
+  SendProposalMessageRequest,
+  Statement,
+  Subscription,
+};
 
-export interface GetProposalMessagesResponse {
-  messages: Message[];
-}
+export type GetProposalMessagesResponse = MessagePage;
 
-export interface SendProposalMessageRequest {
-  // proposalId: String;
-  proposal_id: string;
+export interface SendProposalMessageResponse {
   message: Message;
 }
 
-export interface SendProposalMessageResponse {}
-
 export enum ProposalActionType {
   ExternalFunctionCall = 'ExternalFunctionCall',
   Transfer = 'Transfer',
@@ -55,38 +105,83 @@ export interface TransferAction {
   amount: string;
 }
 
-export interface CreateProposalRequest {
-  action_type: string;
-  params: {
-    receiver_id?: string;
-    method_name?: string;
-    args?: string;
-    deposit?: string;
-    gas?: string;
-    amount?: string;
-    num_approvals?: number;
-    active_proposals_limit?: number;
-    key?: string;
-    value?: string;
-    proposal_id?: string;
-  };
+// `create_new_proposal` returns the new proposal's id.
+export type CreateProposalResponse = ProposalId;
+
+export interface ApproveProposalResponse {}
+
+export interface ListListingsRequest {
+  filter?: ListingFilter;
+  sort?: ListingSort;
+  // The `next_cursor` of the previous page.
+  cursor?: string;
+  offset?: number;
+  limit?: number;
+}
+
+export type ListListingsResponse = ListingPage;
+
+export interface OpenOrderRequest {
+  listing: string;
 }
 
-export interface CreateProposalResponse {
-  proposal_id: string;
+export interface ListPlansRequest {
+  seller?: string;
 }
 
-export interface ApproveProposalRequest {
-  proposal_id: string;
+export interface SubscriptionRequest {
+  plan_id: number;
 }
 
-export interface ApproveProposalResponse {}
+export interface DepositCreditRequest {
+  // Token amount as a decimal string.
+  amount: string;
+}
+
+export type WithdrawCreditRequest = DepositCreditRequest;
+
+export interface GetStatementRequest {
+  // The `next` of the previous page.
+  from?: number;
+  limit?: number;
+}
+
+export interface SubmitReviewRequest {
+  name: string;
+  rating: number;
+  text: string;
+}
+
+export interface ReplyToReviewRequest {
+  name: string;
+  reviewer: string;
+  text: string;
+}
+
+export interface ListReviewsRequest {
+  name: string;
+}
 
 export enum ClientMethod {
   GET_PROPOSAL_MESSAGES = 'get_proposal_messages',
   SEND_PROPOSAL_MESSAGE = 'send_proposal_messages',
+  EDIT_PROPOSAL_MESSAGE = 'edit_proposal_message',
+  DELETE_PROPOSAL_MESSAGE = 'delete_proposal_message',
   CREATE_PROPOSAL = 'create_new_proposal',
   APPROVE_PROPOSAL = 'approve_proposal',
+  CANCEL_PROPOSAL = 'cancel_proposal',
+  LIST_LISTINGS = 'list_listings',
+  OPEN_ORDER = 'open_order',
+  LIST_PLANS = 'list_plans',
+  SUBSCRIBE = 'subscribe',
+  RENEW_SUBSCRIPTION = 'renew_subscription',
+  LIST_SUBSCRIPTIONS = 'list_subscriptions',
+  DEPOSIT_CREDIT = 'deposit_credit',
+  WITHDRAW_CREDIT = 'withdraw_credit',
+  GET_STATEMENT = 'get_statement',
+  SUBMIT_REVIEW = 'submit_review',
+  REPLY_TO_REVIEW = 'reply_to_review',
+  LIST_REVIEWS = 'list_reviews',
 }
 
 export interface ClientApi {
@@ -97,6 +192,12 @@ export interface ClientApi {
   sendProposalMessage(
     sendMessageRequest: SendProposalMessageRequest,
   ): ApiResponse<SendProposalMessageResponse>;
+  editProposalMessage(
+    request: EditProposalMessageRequest,
+  ): ApiResponse<SendProposalMessageResponse>;
+  deleteProposalMessage(
+    request: DeleteProposalMessageRequest,
+  ): ApiResponse<SendProposalMessageResponse>;
   createProposal(
     request: CreateProposalRequest,
   ): ApiResponse<CreateProposalResponse>;
@@ -104,4 +205,16 @@ export interface ClientApi {
     request: ApproveProposalRequest,
   ): ApiResponse<ApproveProposalResponse>;
   deleteProposal(proposalId: string): ApiResponse<void>;
+  listListings(request: ListListingsRequest): ApiResponse<ListListingsResponse>;
+  openOrder(request: OpenOrderRequest): ApiResponse<Order>;
+  listPlans(request: ListPlansRequest): ApiResponse<Plan[]>;
+  subscribe(request: SubscriptionRequest): ApiResponse<Order>;
+  renewSubscription(request: SubscriptionRequest): ApiResponse<Order>;
+  listSubscriptions(): ApiResponse<Subscription[]>;
+  depositCredit(request: DepositCreditRequest): ApiResponse<Order>;
+  withdrawCredit(request: WithdrawCreditRequest): ApiResponse<Order>;
+  getStatement(request: GetStatementRequest): ApiResponse<Statement>;
+  submitReview(request: SubmitReviewRequest): ApiResponse<Review>;
+  replyToReview(request: ReplyToReviewRequest): ApiResponse<Review>;
+  listReviews(request: ListReviewsRequest): ApiResponse<Review[]>;
 }
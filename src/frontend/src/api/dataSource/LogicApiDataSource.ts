@@ -10,14 +10,35 @@ import {
 import {
   ApproveProposalRequest,
   ApproveProposalResponse,
+  CancelProposalRequest,
   ClientApi,
   ClientMethod,
+  CreateNewProposalRequest,
   CreateProposalRequest,
   CreateProposalResponse,
+  DeleteProposalMessageRequest,
+  DepositCreditRequest,
+  EditProposalMessageRequest,
   GetProposalMessagesRequest,
   GetProposalMessagesResponse,
+  GetStatementRequest,
+  ListListingsRequest,
+  ListListingsResponse,
+  ListPlansRequest,
+  ListReviewsRequest,
+  Message,
+  OpenOrderRequest,
+  Order,
+  Plan,
+  ReplyToReviewRequest,
+  Review,
   SendProposalMessageRequest,
   SendProposalMessageResponse,
+  Statement,
+  SubmitReviewRequest,
+  Subscription,
+  SubscriptionRequest,
+  WithdrawCreditRequest,
 } from '../../api/clientApi';
 import { getContextId, getNodeUrl } from '../../utils/node';
 import {
@@ -81,10 +102,10 @@ export class LogicApiDataSource implements ClientApi {
 
     console.log('Creating proposal with request:', request);
 
-    const params: RpcQueryParams<typeof request> = {
+    const params: RpcQueryParams<CreateNewProposalRequest> = {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.CREATE_PROPOSAL,
-      argsJson: request,
+      argsJson: { request },
       executorPublicKey: jwtObject.executor_public_key,
     };
 
@@ -92,7 +113,7 @@ export class LogicApiDataSource implements ClientApi {
 
     try {
       const response = await getJsonRpcClient().execute<
-        typeof request,
+        CreateNewProposalRequest,
         CreateProposalResponse
       >(params, config);
 
@@ -195,6 +216,7 @@ export class LogicApiDataSource implements ClientApi {
 
     const getProposalsResponse: GetProposalMessagesResponse = {
       messages: response?.result?.output?.messages,
+      next: response?.result?.output?.next ?? null,
     } as GetProposalMessagesResponse;
 
     return {
@@ -231,21 +253,333 @@ export class LogicApiDataSource implements ClientApi {
     }
 
     return {
-      data: {},
+      data: { message: response?.result?.output as Message },
       error: null,
     };
   }
 
+  // Only the author of a message may edit or delete it.
+  async editProposalMessage(
+    request: EditProposalMessageRequest,
+  ): ApiResponse<SendProposalMessageResponse> {
+    return this.changeProposalMessage(
+      ClientMethod.EDIT_PROPOSAL_MESSAGE,
+      request,
+    );
+  }
+
+  async deleteProposalMessage(
+    request: DeleteProposalMessageRequest,
+  ): ApiResponse<SendProposalMessageResponse> {
+    return this.changeProposalMessage(
+      ClientMethod.DELETE_PROPOSAL_MESSAGE,
+      request,
+    );
+  }
+
+  private async changeProposalMessage<
+    T extends EditProposalMessageRequest | DeleteProposalMessageRequest,
+  >(method: ClientMethod, request: T): ApiResponse<SendProposalMessageResponse> {
+    const { jwtObject, config, error } = getConfigAndJwt();
+    if (error) {
+      return { error };
+    }
+
+    const response = await getJsonRpcClient().execute<T, Message>(
+      {
+        contextId: jwtObject?.context_id ?? getContextId(),
+        method,
+        argsJson: request,
+        executorPublicKey: jwtObject.executor_public_key,
+      },
+      config,
+    );
+    if (response?.error) {
+      return await this.handleError(response.error, {}, () =>
+        this.changeProposalMessage(method, request),
+      );
+    }
+
+    return {
+      data: { message: response?.result?.output as Message },
+      error: null,
+    };
+  }
+
+  // Only the creator of a pending proposal may cancel it.
   async deleteProposal(proposalId: string): ApiResponse<void> {
-    // TODO: Backend API endpoint not implemented yet
-    console.log('deleteProposal', proposalId);
-    console.warn('deleteProposal: Backend API endpoint not implemented yet');
+    const { jwtObject, config, error } = getConfigAndJwt();
+    if (error) {
+      return { error };
+    }
+
+    const params: RpcQueryParams<CancelProposalRequest> = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.CANCEL_PROPOSAL,
+      argsJson: { proposal_id: proposalId },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    const response = await getJsonRpcClient().execute<
+      CancelProposalRequest,
+      unknown
+    >(params, config);
+
+    if (response?.error) {
+      return await this.handleError(
+        response.error,
+        proposalId,
+        this.deleteProposal,
+      );
+    }
+
     return {
       data: undefined,
       error: null,
     };
   }
 
+  async listListings(
+    request: ListListingsRequest,
+  ): ApiResponse<ListListingsResponse> {
+    const { jwtObject, config, error } = getConfigAndJwt();
+    if (error) {
+      return { error };
+    }
+
+    const params: RpcQueryParams<ListListingsRequest> = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.LIST_LISTINGS,
+      argsJson: request,
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    const response = await getJsonRpcClient().query<
+      ListListingsRequest,
+      ListListingsResponse
+    >(params, config);
+
+    if (response?.error) {
+      return await this.handleError(response.error, request, this.listListings);
+    }
+
+    return {
+      data: {
+        listings: response?.result?.output?.listings ?? [],
+        next_cursor: response?.result?.output?.next_cursor ?? null,
+      },
+      error: null,
+    };
+  }
+
+  async openOrder(request: OpenOrderRequest): ApiResponse<Order> {
+    return this.placeOrder(ClientMethod.OPEN_ORDER, request);
+  }
+
+  // Subscribing and renewing both open an order for one period of the
+  // plan; access follows once its Transfer proposal executes.
+  async subscribe(request: SubscriptionRequest): ApiResponse<Order> {
+    return this.placeOrder(ClientMethod.SUBSCRIBE, request);
+  }
+
+  async renewSubscription(request: SubscriptionRequest): ApiResponse<Order> {
+    return this.placeOrder(ClientMethod.RENEW_SUBSCRIPTION, request);
+  }
+
+  // Credit is booked once the deposit's proposal executes; the money stays
+  // in the proxy's treasury, which pays withdrawals.
+  async depositCredit(request: DepositCreditRequest): ApiResponse<Order> {
+    return this.placeOrder(ClientMethod.DEPOSIT_CREDIT, request);
+  }
+
+  // The credit is taken at once and paid out to the caller's payout
+  // account once the withdrawal's Transfer proposal executes.
+  async withdrawCredit(request: WithdrawCreditRequest): ApiResponse<Order> {
+    return this.placeOrder(ClientMethod.WITHDRAW_CREDIT, request);
+  }
+
+  private async placeOrder<
+    T extends OpenOrderRequest | SubscriptionRequest | DepositCreditRequest,
+  >(
+    method: ClientMethod,
+    request: T,
+  ): ApiResponse<Order> {
+    const { jwtObject, config, error } = getConfigAndJwt();
+    if (error) {
+      return { error };
+    }
+
+    const response = await getJsonRpcClient().execute<T, Order>(
+      {
+        contextId: jwtObject?.context_id ?? getContextId(),
+        method,
+        argsJson: request,
+        executorPublicKey: jwtObject.executor_public_key,
+      },
+      config,
+    );
+    if (response?.error) {
+      return await this.handleError(response.error, request, () =>
+        this.placeOrder(method, request),
+      );
+    }
+
+    return {
+      data: response?.result?.output as Order,
+      error: null,
+    };
+  }
+
+  async listPlans(request: ListPlansRequest): ApiResponse<Plan[]> {
+    const { jwtObject, config, error } = getConfigAndJwt();
+    if (error) {
+      return { error };
+    }
+
+    const params: RpcQueryParams<ListPlansRequest> = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.LIST_PLANS,
+      argsJson: request,
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    const response = await getJsonRpcClient().query<ListPlansRequest, Plan[]>(
+      params,
+      config,
+    );
+    if (response?.error) {
+      return await this.handleError(response.error, request, this.listPlans);
+    }
+
+    return {
+      data: response?.result?.output ?? [],
+      error: null,
+    };
+  }
+
+  async getStatement(request: GetStatementRequest): ApiResponse<Statement> {
+    const { jwtObject, config, error } = getConfigAndJwt();
+    if (error) {
+      return { error };
+    }
+
+    const params: RpcQueryParams<GetStatementRequest> = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.GET_STATEMENT,
+      argsJson: request,
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    const response = await getJsonRpcClient().query<
+      GetStatementRequest,
+      Statement
+    >(params, config);
+    if (response?.error) {
+      return await this.handleError(response.error, request, this.getStatement);
+    }
+
+    return {
+      data: response?.result?.output as Statement,
+      error: null,
+    };
+  }
+
+  // Only callers who bought or were granted the listing may review it.
+  async submitReview(request: SubmitReviewRequest): ApiResponse<Review> {
+    return this.changeReview(ClientMethod.SUBMIT_REVIEW, request);
+  }
+
+  async replyToReview(request: ReplyToReviewRequest): ApiResponse<Review> {
+    return this.changeReview(ClientMethod.REPLY_TO_REVIEW, request);
+  }
+
+  private async changeReview<
+    T extends SubmitReviewRequest | ReplyToReviewRequest,
+  >(method: ClientMethod, request: T): ApiResponse<Review> {
+    const { jwtObject, config, error } = getConfigAndJwt();
+    if (error) {
+      return { error };
+    }
+
+    const response = await getJsonRpcClient().execute<T, Review>(
+      {
+        contextId: jwtObject?.context_id ?? getContextId(),
+        method,
+        argsJson: request,
+        executorPublicKey: jwtObject.executor_public_key,
+      },
+      config,
+    );
+    if (response?.error) {
+      return await this.handleError(response.error, request, () =>
+        this.changeReview(method, request),
+      );
+    }
+
+    return {
+      data: response?.result?.output as Review,
+      error: null,
+    };
+  }
+
+  async listReviews(request: ListReviewsRequest): ApiResponse<Review[]> {
+    const { jwtObject, config, error } = getConfigAndJwt();
+    if (error) {
+      return { error };
+    }
+
+    const params: RpcQueryParams<ListReviewsRequest> = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.LIST_REVIEWS,
+      argsJson: request,
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    const response = await getJsonRpcClient().query<
+      ListReviewsRequest,
+      Review[]
+    >(params, config);
+    if (response?.error) {
+      return await this.handleError(response.error, request, this.listReviews);
+    }
+
+    return {
+      data: response?.result?.output ?? [],
+      error: null,
+    };
+  }
+
+  async listSubscriptions(): ApiResponse<Subscription[]> {
+    const { jwtObject, config, error } = getConfigAndJwt();
+    if (error) {
+      return { error };
+    }
+
+    const params: RpcQueryParams<Record<string, never>> = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.LIST_SUBSCRIPTIONS,
+      argsJson: {},
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    const response = await getJsonRpcClient().query<
+      Record<string, never>,
+      Subscription[]
+    >(params, config);
+    if (response?.error) {
+      return await this.handleError(
+        response.error,
+        {},
+        this.listSubscriptions,
+      );
+    }
+
+    return {
+      data: response?.result?.output ?? [],
+      error: null,
+    };
+  }
+
   // TODO: Properly type these parameters when the API contract is finalized
   /* eslint-disable @typescript-eslint/no-explicit-any */
   private async handleError(
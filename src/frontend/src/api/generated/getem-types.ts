@@ -0,0 +1,227 @@
+// Generated from src/types by `cargo run --features ts --bin generate-ts`.
+// Do not edit; change the Rust types and regenerate instead.
+
+/**
+ * The context proxy's id of a proposal, laid out in Borsh like the
+ * runtime's own id. Clients see it as the base58 string the admin API
+ * shows; the byte array older clients sent is accepted as well.
+ */
+export type ProposalId = string;
+
+/**
+ * A token amount. Accepts a JSON number or a decimal string, and is always
+ * serialized as a string so JavaScript clients don't lose precision.
+ */
+export type U128 = string;
+
+/**
+ * A message in a proposal's discussion thread. Everything but the text is
+ * filled in by the app.
+ */
+export type Message = { id: number, proposal_id: ProposalId, author: string, text: string, created_at: number, edited_at: number | null, deleted: boolean, };
+
+/**
+ * A slice of a thread, oldest first.
+ */
+export type MessagePage = { messages: Array<Message>, 
+/**
+ * Pass as `from` to fetch the next page; `None` on the last one.
+ */
+next: number | null, };
+
+/**
+ * A proposal as sent by clients: `{ "action_type": "...", "params": { ... } }`.
+ */
+export type CreateProposalRequest = { "action_type": "ExternalFunctionCall", "params": { receiver_id: string, method_name: string, args: string, deposit: U128, } } | { "action_type": "Transfer", "params": { receiver_id: string, amount: U128, } } | { "action_type": "SetContextValue", "params": { key: string, value: string, } } | { "action_type": "SetNumApprovals", "params": { num_approvals: number, } } | { "action_type": "SetActiveProposalsLimit", "params": { active_proposals_limit: number, } } | { "action_type": "DeleteProposal", "params": { 
+/**
+ * Hex, unlike everywhere else.
+ */
+proposal_id: string, } };
+
+export type ProposalStatus = "Pending" | "Executed" | "Deleted" | "Cancelled";
+
+/**
+ * What the app knows about a proposal it sent to the context proxy.
+ */
+export type ProposalRecord = { id: ProposalId, creator: string, action: CreateProposalRequest, summary: string, created_at: number, approvers: Array<string>, status: ProposalStatus, };
+
+export type DistanceMetric = "Cosine" | "DotProduct" | "Euclidean";
+
+export type ChunkMetadata = { title: string | null, label: string | null, text_excerpt: string | null, attributes: { [key in string]?: string }, };
+
+export type EmbeddingChunk = { chunk_id: string, metadata: ChunkMetadata, vector: Array<number>, 
+/**
+ * For encrypted datasets: the chunk (metadata and vector) sealed with
+ * the dataset's content key. `metadata` and `vector` are then empty.
+ */
+sealed: string | null, };
+
+/**
+ * What `list_datasets`/`get_dataset` return: everything but the vectors.
+ */
+export type DatasetInfo = { name: string, owner: string, model: string, dimension: number, metric: DistanceMetric, chunk_count: number, encrypted: boolean, };
+
+/**
+ * `create_new_proposal`
+ */
+export type CreateNewProposalRequest = { request: CreateProposalRequest, };
+
+/**
+ * `approve_proposal`
+ */
+export type ApproveProposalRequest = { proposal_id: ProposalId, };
+
+/**
+ * `sync_num_approvals`: the threshold read from the context proxy.
+ */
+export type SyncNumApprovalsRequest = { num_approvals: number, };
+
+/**
+ * `cancel_proposal`
+ */
+export type CancelProposalRequest = { proposal_id: ProposalId, };
+
+/**
+ * `get_proposal_messages`
+ */
+export type GetProposalMessagesRequest = { proposal_id: ProposalId, 
+/**
+ * Message id the page starts at, the `next` of the previous page.
+ */
+from?: number, limit?: number, };
+
+/**
+ * `send_proposal_messages`
+ */
+export type SendProposalMessageRequest = { proposal_id: ProposalId, text: string, };
+
+/**
+ * `edit_proposal_message`
+ */
+export type EditProposalMessageRequest = { proposal_id: ProposalId, message_id: number, text: string, };
+
+/**
+ * `delete_proposal_message`
+ */
+export type DeleteProposalMessageRequest = { proposal_id: ProposalId, message_id: number, };
+
+/**
+ * What a listing costs. The purchase is paid through the context proxy,
+ * which only transfers the chain's native token, so `token` must be
+ * `NATIVE_TOKEN`.
+ */
+export type Price = { amount: U128, token: string, };
+
+export type LicenseKind = "Personal" | "Research" | "Commercial" | "OpenSource";
+
+/**
+ * What a buyer may do with the content beyond using it themselves.
+ */
+export type AllowedUses = { commercial: boolean, derivatives: boolean, redistribution: boolean, model_training: boolean, };
+
+/**
+ * The terms a listing is sold under. Orders keep a copy, so changing a
+ * listing's license does not change what earlier buyers agreed to.
+ */
+export type License = { kind: LicenseKind, allowed_uses: AllowedUses, };
+
+export type ListingKind = "File" | "Dataset";
+
+/**
+ * One row of the catalog. File-only and dataset-only fields are `None`
+ * for the other kind.
+ */
+export type ListingSummary = { name: string, kind: ListingKind, owner: string, size: number | null, chunk_count: number | null, model: string | null, dimension: number | null, price: Price | null, license: License, 
+/**
+ * Credit charged per search for buyers without access. Datasets only.
+ */
+query_price: U128 | null, 
+/**
+ * Average review rating, `None` until the first review.
+ */
+rating: number | null, review_count: number, tags: Array<string>, encrypted: boolean, created_at: number, };
+
+/**
+ * Every field that is set must match. `model` and `dimension` only match
+ * datasets.
+ */
+export type ListingFilter = { owner?: string, tag?: string, model?: string, dimension?: number, };
+
+export type ListingSort = "Newest" | "Oldest" | "Name" | "PriceLowToHigh" | "PriceHighToLow";
+
+export type ListingPage = { listings: Array<ListingSummary>, 
+/**
+ * Pass back as `cursor` for the next page; `None` on the last one.
+ */
+next_cursor: string | null, };
+
+/**
+ * What an order pays for.
+ */
+export type OrderItem = { "Listing": string } | { "Subscription": number } | "CreditDeposit" | "CreditWithdrawal";
+
+export type OrderStatus = "Pending" | "Paid" | "Cancelled" | "Expired";
+
+export type Order = { id: number, item: OrderItem, buyer: string, seller: string, 
+/**
+ * The listing's price when the order was opened; `fee` of it goes to
+ * the marketplace, the rest to the seller.
+ */
+amount: U128, token: string, fee: U128, license: License, proposal_id: ProposalId, status: OrderStatus, created_at: number, expires_at: number, };
+
+/**
+ * A seller's offer of access to a set of their datasets for one period
+ * (nanoseconds, matching `env::time_now`) at a time.
+ */
+export type Plan = { id: number, seller: string, name: string, period: number, price: Price, datasets: Array<string>, license: License, 
+/**
+ * Retired plans take no new subscribers or renewals; windows already
+ * paid for still run out.
+ */
+retired: boolean, created_at: number, };
+
+/**
+ * A subscriber's paid window on a plan. It only ever grows: a renewal
+ * adds one period to it.
+ */
+export type Subscription = { plan_id: number, subscriber: string, 
+/**
+ * Start of the current unbroken window.
+ */
+started_at: number, expires_at: number, renewals: number, 
+/**
+ * Set once `expire_subscriptions` has reported the lapse, so it is
+ * reported only once.
+ */
+lapsed: boolean, };
+
+export type EntryKind = { "Deposit": { order_id: number, } } | { "QueryCharge": { dataset: string, seller: string, } } | { "QueryIncome": { dataset: string, buyer: string, } } | { "Withdrawal": { order_id: number, } } | { "WithdrawalReturned": { order_id: number, } };
+
+/**
+ * One movement of credit. `balance` is the account's balance right after
+ * it.
+ */
+export type LedgerEntry = { id: number, kind: EntryKind, amount: U128, balance: U128, created_at: number, };
+
+/**
+ * A slice of an account's entries, oldest first.
+ */
+export type Statement = { balance: U128, entries: Array<LedgerEntry>, 
+/**
+ * Pass as `from` to fetch the next page; `None` on the last one.
+ */
+next: number | null, };
+
+export type Reply = { text: string, created_at: number, };
+
+/**
+ * A buyer's review of a listing. Each buyer reviews a listing once, and
+ * its owner may answer once.
+ */
+export type Review = { listing: string, reviewer: string, rating: number, text: string, created_at: number, reply: Reply | null, };
+
+/**
+ * An event the app emitted. The node reports each one as its
+ * variant name, `kind`, and the JSON of its fields, `data`.
+ */
+export type Event = { "kind": "FileUploaded", "data": { name: string, owner: string, sha256: string, } } | { "kind": "FileDownloaded", "data": { name: string, downloader: string, } } | { "kind": "FileDeleted", "data": { name: string, } } | { "kind": "UploadStarted", "data": { id: number, name: string, } } | { "kind": "UploadAborted", "data": { id: number, } } | { "kind": "UploadExpired", "data": { id: number, } } | { "kind": "DatasetUploaded", "data": { name: string, owner: string, chunk_count: number, } } | { "kind": "DatasetChunksAppended", "data": { name: string, chunk_count: number, } } | { "kind": "DatasetDeleted", "data": { name: string, } } | { "kind": "ListingUpdated", "data": { name: string, } } | { "kind": "ProposalCreated", "data": { id: ProposalId, } } | { "kind": "ApprovedProposal", "data": { id: ProposalId, } } | { "kind": "ProposalExecuted", "data": { id: ProposalId, } } | { "kind": "ProposalCancelled", "data": { id: ProposalId, } } | { "kind": "OrderOpened", "data": { id: number, listing: string, buyer: string, } } | { "kind": "OrderPaid", "data": { id: number, } } | { "kind": "OrderCancelled", "data": { id: number, } } | { "kind": "OrderExpired", "data": { id: number, } } | { "kind": "PlanCreated", "data": { id: number, seller: string, } } | { "kind": "PlanRetired", "data": { id: number, } } | { "kind": "SubscriptionOrderOpened", "data": { id: number, plan_id: number, buyer: string, } } | { "kind": "SubscriptionStarted", "data": { plan_id: number, subscriber: string, expires_at: number, } } | { "kind": "SubscriptionRenewed", "data": { plan_id: number, subscriber: string, expires_at: number, } } | { "kind": "SubscriptionExpired", "data": { plan_id: number, subscriber: string, } } | { "kind": "CreditDeposited", "data": { account: string, amount: U128, } } | { "kind": "CreditWithdrawn", "data": { account: string, amount: U128, } } | { "kind": "QueryCharged", "data": { dataset: string, buyer: string, amount: U128, } } | { "kind": "ReviewSubmitted", "data": { name: string, reviewer: string, rating: number, } } | { "kind": "ReviewReplied", "data": { name: string, reviewer: string, } } | { "kind": "AccessGranted", "data": { name: string, reader: string, } } | { "kind": "AccessRevoked", "data": { name: string, reader: string, } } | { "kind": "KeyWrapped", "data": { name: string, reader: string, } } | { "kind": "KeyRemoved", "data": { name: string, reader: string, } };
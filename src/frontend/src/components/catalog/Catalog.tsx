@@ -0,0 +1,163 @@
+import { useEffect, useState } from 'react';
+import { LogicApiDataSource } from '../../api/dataSource/LogicApiDataSource';
+import { ListingSort, ListingSummary } from '../../api/clientApi';
+
+const PAGE_SIZE = 10;
+
+const SORTS: ListingSort[] = [
+  'Newest',
+  'Oldest',
+  'Name',
+  'PriceLowToHigh',
+  'PriceHighToLow',
+];
+
+export default function Catalog() {
+  const [sort, setSort] = useState<ListingSort>('Newest');
+  const [tag, setTag] = useState('');
+  const [listings, setListings] = useState<ListingSummary[]>([]);
+  const [nextCursor, setNextCursor] = useState<string | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  // Pages are fetched by cursor, so listings added while browsing never
+  // show up twice.
+  async function fetchPage(cursor: string | null) {
+    setLoading(true);
+    const result = await new LogicApiDataSource().listListings({
+      filter: tag ? { tag } : undefined,
+      sort,
+      cursor: cursor ?? undefined,
+      limit: PAGE_SIZE,
+    });
+    setLoading(false);
+
+    if (result?.error || !result.data) {
+      console.error('Error:', result?.error);
+      window.alert(`${result?.error?.message}`);
+      return;
+    }
+    const page = result.data;
+    setListings((previous) =>
+      cursor ? [...previous, ...page.listings] : page.listings,
+    );
+    setNextCursor(page.next_cursor);
+  }
+
+  // The purchase is a Transfer proposal; access follows once it has been
+  // approved.
+  async function buy(listing: ListingSummary) {
+    const result = await new LogicApiDataSource().openOrder({
+      listing: listing.name,
+    });
+    if (result?.error || !result.data) {
+      console.error('Error:', result?.error);
+      window.alert(`${result?.error?.message}`);
+      return;
+    }
+    const order = result.data;
+    window.alert(
+      `Order ${order.id} opened under the ${order.license.kind} license; it completes once proposal ${order.proposal_id} is approved.`,
+    );
+  }
+
+  useEffect(() => {
+    fetchPage(null);
+    // Refetch from the start whenever the query changes.
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [sort, tag]);
+
+  return (
+    <div className="text-white text-xs px-4 mb-4 flex flex-col items-center">
+      <div className="text-base">Catalog</div>
+      <div className="flex gap-2 items-center">
+        <select
+          value={sort}
+          onChange={(e) => setSort(e.target.value as ListingSort)}
+          className="text-white px-4 py-1 m-1 rounded-lg text-base bg-[#5dbb63] cursor-pointer border-none outline-none"
+        >
+          {SORTS.map((option) => (
+            <option key={option} value={option}>
+              {option}
+            </option>
+          ))}
+        </select>
+        <input
+          value={tag}
+          onChange={(e) => setTag(e.target.value.trim())}
+          placeholder="Filter by tag"
+          className="px-2 py-1 rounded-lg text-black"
+        />
+      </div>
+      {listings.length > 0 ? (
+        <table className="border-collapse">
+          <thead>
+            <tr>
+              <th className="p-2">Name</th>
+              <th className="p-2">Owner</th>
+              <th className="p-2">Size / chunks</th>
+              <th className="p-2">Price</th>
+              <th className="p-2">License</th>
+              <th className="p-2">Rating</th>
+              <th className="p-2">Created</th>
+              <th className="p-2"></th>
+            </tr>
+          </thead>
+          <tbody>
+            {listings.map((listing) => (
+              <tr key={listing.name}>
+                <td className="p-2 max-w-[200px] break-words">
+                  {listing.name}
+                </td>
+                <td className="p-2 max-w-[200px] break-words">
+                  {listing.owner}
+                </td>
+                <td className="p-2">
+                  {listing.kind === 'File'
+                    ? `${listing.size} bytes`
+                    : `${listing.chunk_count} chunks`}
+                </td>
+                <td className="p-2">
+                  {listing.price
+                    ? `${listing.price.amount} ${listing.price.token}`
+                    : '-'}
+                </td>
+                <td className="p-2">{listing.license.kind}</td>
+                <td className="p-2">
+                  {listing.rating !== null
+                    ? `${listing.rating.toFixed(1)} (${listing.review_count})`
+                    : '-'}
+                </td>
+                <td className="p-2">
+                  {listing.created_at
+                    ? new Date(listing.created_at / 1_000_000).toLocaleString()
+                    : '-'}
+                </td>
+                <td className="p-2">
+                  {listing.price && (
+                    <button
+                      onClick={() => buy(listing)}
+                      className="text-white px-2 py-1 rounded-lg bg-[#5dbb63] cursor-pointer border-none outline-none"
+                    >
+                      Buy
+                    </button>
+                  )}
+                </td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      ) : (
+        <div>No listings</div>
+      )}
+      {nextCursor && (
+        <button
+          onClick={() => fetchPage(nextCursor)}
+          disabled={loading}
+          className="text-white px-4 py-1 m-1 rounded-lg text-base bg-[#5dbb63] cursor-pointer flex justify-center border-none outline-none disabled:opacity-50"
+        >
+          {loading ? 'Loading...' : 'Load more'}
+        </button>
+      )}
+    </div>
+  );
+}
@@ -37,6 +37,7 @@ import CreateProposalPopup, {
   ProposalData,
 } from '../components/proposals/CreateProposalPopup';
 import Actions from '../components/Actions';
+import Catalog from '../components/catalog/Catalog';
 
 export default function HomePage() {
   const navigate = useNavigate();
@@ -79,13 +80,7 @@ export default function HomePage() {
   async function sendProposalMessage(request: SendProposalMessageRequest) {
     const params: SendProposalMessageRequest = {
       proposal_id: request.proposal_id,
-      message: {
-        id: request.message.id,
-        proposal_id: request.proposal_id,
-        author: request.message.author,
-        text: request.message.text,
-        created_at: new Date().toISOString(),
-      },
+      text: request.text,
     };
     const result: ResponseData<SendProposalMessageResponse> =
       await new LogicApiDataSource().sendProposalMessage(params);
@@ -452,6 +447,8 @@ export default function HomePage() {
         </div>
       </div>
 
+      <Catalog />
+
       <div className="text-white">Proposals</div>
 
       <button
@@ -539,14 +536,8 @@ export default function HomePage() {
                 onClick={() => {
                   sendProposalMessage({
                     proposal_id: selectedProposal.id,
-                    message: {
-                      id: 'test' + Math.random(),
-                      proposal_id: selectedProposal.id,
-                      author: getJWTObject()?.executor_public_key,
-                      text: 'test' + Math.random(),
-                      created_at: new Date().toISOString(),
-                    },
-                  } as SendProposalMessageRequest);
+                    text: 'test' + Math.random(),
+                  });
                 }}
                 className="text-white px-4 py-1 m-1 rounded-lg text-base bg-[#5dbb63] cursor-pointer flex justify-center border-none outline-none"
               >
@@ -6,8 +6,14 @@ TARGET="${CARGO_TARGET_DIR:-target}"
 
 rustup target add wasm32-unknown-unknown
 
+# FEATURES="proposals" builds only those method groups; all by default.
+FEATURE_ARGS=()
+if [ -n "${FEATURES:-}" ]; then
+  FEATURE_ARGS=(--no-default-features --features "$FEATURES")
+fi
+
 echo "Building the project..."
-cargo build --target wasm32-unknown-unknown --profile app-release
+cargo build --target wasm32-unknown-unknown --profile app-release "${FEATURE_ARGS[@]}"
 
 mkdir -p res
 
@@ -0,0 +1,31 @@
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use calimero_sdk::serde::Serialize;
+
+// ---------------- Access Control Types ----------------
+
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct AccessGrant {
+    pub reader: String,
+    pub granted_by: String,
+    pub granted_at: u64,
+    /// Node time (nanoseconds) after which the grant stops working.
+    pub expires_at: Option<u64>,
+}
+
+#[cfg(feature = "files")]
+impl AccessGrant {
+    pub fn is_valid_at(&self, now: u64) -> bool {
+        self.expires_at.map_or(true, |expires_at| now < expires_at)
+    }
+}
+
+/// What `list_grants` returns for a listing.
+#[cfg(feature = "files")]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct AccessList {
+    pub owner: String,
+    pub grants: Vec<AccessGrant>,
+}
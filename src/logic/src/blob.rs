@@ -0,0 +1,49 @@
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "files")]
+use calimero_sdk::serde::Serialize;
+use calimero_storage::collections::Vector;
+#[cfg(feature = "files")]
+use sha2::{Digest, Sha256};
+
+// ---------------- Content Types ----------------
+
+/// File content, stored once per SHA-256 digest no matter how many files
+/// point at it.
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+pub struct Blob {
+    pub size: u64,
+    pub chunks: Vector<String>,
+    pub refs: u32,
+}
+
+#[cfg(feature = "files")]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct FileInfo {
+    pub name: String,
+    pub owner: String,
+    pub size: u64,
+    pub sha256: String,
+    /// The content is sealed with a key only readers with a wrapped key
+    /// can recover; the digest is over the sealed content.
+    pub encrypted: bool,
+}
+
+#[cfg(feature = "files")]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct FileDownload {
+    pub content: String,
+    pub sha256: String,
+}
+
+/// Hex SHA-256 over the concatenation of `pieces`.
+#[cfg(feature = "files")]
+pub fn digest_pieces<'a>(pieces: impl IntoIterator<Item = &'a str>) -> String {
+    let mut hasher = Sha256::new();
+    for piece in pieces {
+        hasher.update(piece.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
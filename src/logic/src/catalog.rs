@@ -0,0 +1,67 @@
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "marketplace")]
+pub use getem_types::catalog::{
+    ListingFilter, ListingKind, ListingPage, ListingSort, ListingSummary, SortKey,
+};
+
+use crate::terms::{License, Price};
+
+// ---------------- Catalog Types ----------------
+
+#[cfg(feature = "marketplace")]
+pub const MAX_TAGS: usize = 16;
+#[cfg(feature = "marketplace")]
+pub const MAX_TAG_BYTES: usize = 32;
+
+/// Catalog data kept for every file and dataset. `seq` counts up with every
+/// new listing and is never reused, so it orders listings by age even when
+/// several share a timestamp.
+#[derive(Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+pub struct ListingMeta {
+    pub seq: u64,
+    pub created_at: u64,
+    pub tags: Vec<String>,
+    pub price: Option<Price>,
+    pub license: License,
+}
+
+/// The position of the last listing of a page. Unlike an offset it stays
+/// valid when listings are added or removed before it.
+#[cfg(feature = "marketplace")]
+pub fn encode_cursor((rank, name): &SortKey) -> String {
+    format!("{}:{}", rank, name)
+}
+
+#[cfg(feature = "marketplace")]
+pub fn decode_cursor(cursor: &str) -> Option<SortKey> {
+    let (rank, name) = cursor.split_once(':')?;
+    Some((rank.parse().ok()?, name.to_owned()))
+}
+
+/// Lowercases and trims tags and drops duplicates, keeping the first
+/// occurrence.
+#[cfg(feature = "marketplace")]
+pub fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() {
+            return Err("tags must not be empty".to_owned());
+        }
+        if tag.len() > MAX_TAG_BYTES {
+            return Err(format!(
+                "tag {} is longer than {} bytes",
+                tag, MAX_TAG_BYTES
+            ));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    if normalized.len() > MAX_TAGS {
+        return Err(format!("a listing can have at most {} tags", MAX_TAGS));
+    }
+    Ok(normalized)
+}
@@ -0,0 +1,102 @@
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use calimero_storage::collections::{UnorderedMap, Vector};
+#[cfg(feature = "files")]
+pub use getem_types::dataset::{ChunkMetadata, DatasetInfo};
+pub use getem_types::dataset::{DistanceMetric, EmbeddingChunk};
+
+// ---------------- Dataset Types ----------------
+
+/// Stored form of a dataset. Chunks live in their own collection so that
+/// listing datasets never has to load the vectors.
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+pub struct EmbeddingDataset {
+    pub name: String,
+    pub owner: String,
+    pub model: String,
+    pub dimension: u32,
+    pub metric: DistanceMetric,
+    pub chunk_count: u64,
+    pub encrypted: bool,
+    pub chunks: Vector<EmbeddingChunk>,
+    /// Position of every chunk by id; ids are unique within a dataset.
+    pub chunk_ids: UnorderedMap<String, u64>,
+}
+
+#[cfg(feature = "files")]
+impl EmbeddingDataset {
+    pub fn new(
+        name: String,
+        owner: String,
+        model: String,
+        dimension: u32,
+        metric: DistanceMetric,
+        encrypted: bool,
+    ) -> Self {
+        EmbeddingDataset {
+            name,
+            owner,
+            model,
+            dimension,
+            metric,
+            chunk_count: 0,
+            encrypted,
+            chunks: Vector::new(),
+            chunk_ids: UnorderedMap::new(),
+        }
+    }
+
+    pub fn info(&self) -> DatasetInfo {
+        DatasetInfo {
+            name: self.name.clone(),
+            owner: self.owner.clone(),
+            model: self.model.clone(),
+            dimension: self.dimension,
+            metric: self.metric,
+            chunk_count: self.chunk_count,
+            encrypted: self.encrypted,
+        }
+    }
+
+    pub fn check_chunk(&self, chunk: &EmbeddingChunk) -> Result<(), String> {
+        if chunk.chunk_id.is_empty() {
+            return Err("chunk_id must not be empty".to_owned());
+        }
+        if self.encrypted {
+            return match &chunk.sealed {
+                Some(sealed)
+                    if !sealed.is_empty()
+                        && chunk.vector.is_empty()
+                        && chunk.metadata == ChunkMetadata::default() =>
+                {
+                    Ok(())
+                }
+                _ => Err(format!(
+                    "chunk {} of an encrypted dataset must carry only sealed content",
+                    chunk.chunk_id
+                )),
+            };
+        }
+        if chunk.sealed.is_some() {
+            return Err(format!(
+                "chunk {} is sealed but the dataset is not encrypted",
+                chunk.chunk_id
+            ));
+        }
+        if chunk.vector.len() != self.dimension as usize {
+            return Err(format!(
+                "chunk {} has dimension {}, expected {}",
+                chunk.chunk_id,
+                chunk.vector.len(),
+                self.dimension
+            ));
+        }
+        if chunk.vector.iter().any(|value| !value.is_finite()) {
+            return Err(format!(
+                "chunk {} contains non-finite values",
+                chunk.chunk_id
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use calimero_sdk::serde::{Deserialize, Serialize};
+
+// ---------------- Envelope Types ----------------
+
+/// How content keys are wrapped: an ephemeral X25519 key agreement with the
+/// reader's identity key (converted from ed25519), HKDF-SHA256, then
+/// ChaCha20-Poly1305. The `getem-envelope` crate implements the client side.
+#[cfg(feature = "files")]
+pub const ENVELOPE_ALGORITHM: &str = "x25519-hkdf-sha256-chacha20poly1305";
+
+#[cfg(feature = "files")]
+const PUBLIC_KEY_BYTES: usize = 32;
+#[cfg(feature = "files")]
+const NONCE_BYTES: usize = 12;
+// A 32-byte content key plus the 16-byte Poly1305 tag.
+#[cfg(feature = "files")]
+const SEALED_KEY_BYTES: usize = 48;
+
+/// A listing's content key, encrypted for one reader. All fields are hex.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct SealedKey {
+    pub ephemeral_public_key: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct WrappedKey {
+    pub reader: String,
+    pub algorithm: String,
+    pub key: SealedKey,
+    pub wrapped_by: String,
+    pub wrapped_at: u64,
+}
+
+#[cfg(feature = "files")]
+impl SealedKey {
+    /// The node never sees the content key, so all it can check is that
+    /// the envelope has the right shape.
+    pub fn check(&self) -> Result<(), String> {
+        check_hex(
+            "ephemeral_public_key",
+            &self.ephemeral_public_key,
+            PUBLIC_KEY_BYTES,
+        )?;
+        check_hex("nonce", &self.nonce, NONCE_BYTES)?;
+        check_hex("ciphertext", &self.ciphertext, SEALED_KEY_BYTES)
+    }
+}
+
+#[cfg(feature = "files")]
+fn check_hex(field: &str, value: &str, len: usize) -> Result<(), String> {
+    match hex::decode(value) {
+        Ok(bytes) if bytes.len() == len => Ok(()),
+        _ => Err(format!("{} must be {} bytes of hex", field, len)),
+    }
+}
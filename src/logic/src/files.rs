@@ -0,0 +1,720 @@
+//! Methods of the `files` feature: file exchange, datasets, search, and
+//! per-listing access control and encryption keys.
+
+use super::*;
+
+#[app::logic]
+impl FileExchangeState {
+    // ===== File Exchange Functions =====
+
+    /// Stores a file and returns the SHA-256 digest of its content.
+    /// Content that is already stored is not stored a second time. Pass
+    /// `encrypted` when `content` is sealed client-side; readers then need
+    /// a wrapped key to make sense of it.
+    pub fn upload_file(
+        &mut self,
+        name: String,
+        content: String,
+        encrypted: Option<bool>,
+    ) -> Result<String, FileExchangeError> {
+        self.check_migrated()?;
+        if self.listing_exists(&name)? {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+
+        let sha256 = self.store_content(&content)?;
+        self.add_file(
+            name,
+            content.len() as u64,
+            sha256,
+            encrypted.unwrap_or(false),
+        )
+    }
+
+    /// Returns the whole file together with its digest, so the buyer can
+    /// check it against the listing.
+    pub fn download_file(&self, name: String) -> Result<FileDownload, FileExchangeError> {
+        self.check_access(&name, &caller())?;
+
+        let file = self.file(&name)?;
+        let content = self
+            .content(&file.sha256)?
+            .chunks
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .collect();
+
+        host::emit(Event::FileDownloaded {
+            name,
+            downloader: caller(),
+        });
+
+        Ok(FileDownload {
+            content,
+            sha256: file.sha256,
+        })
+    }
+
+    pub fn get_file_info(&self, name: String) -> Result<FileInfo, FileExchangeError> {
+        self.file(&name).map(|file| file.info())
+    }
+
+    /// Returns up to `length` bytes of a file starting at `offset`, for
+    /// files too large to fetch in one call. Continue from `next_offset`
+    /// until `eof` is set.
+    pub fn download_range(
+        &self,
+        name: String,
+        offset: u64,
+        length: u64,
+    ) -> Result<ContentRange, FileExchangeError> {
+        self.check_access(&name, &caller())?;
+
+        let file = self.file(&name)?;
+        if offset > file.size {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+
+        let chunks = self
+            .content(&file.sha256)?
+            .chunks
+            .entries()
+            .map_err(FileExchangeError::storage)?;
+        let (data, next_offset) =
+            read_range(chunks, offset, length).ok_or(FileExchangeError::InvalidOperation)?;
+
+        if offset == 0 {
+            host::emit(Event::FileDownloaded {
+                name: name.clone(),
+                downloader: caller(),
+            });
+        }
+
+        Ok(ContentRange {
+            name,
+            offset,
+            data,
+            next_offset,
+            total_size: file.size,
+            eof: next_offset >= file.size,
+            sha256: file.sha256,
+        })
+    }
+
+    /// Starts a chunked upload for a file too large for `upload_file`.
+    /// The name is only taken when the upload is finalized, so several
+    /// uploads may aim for the same name and the first to finish wins.
+    pub fn begin_upload(
+        &mut self,
+        name: String,
+        encrypted: Option<bool>,
+    ) -> Result<UploadStatus, FileExchangeError> {
+        self.check_migrated()?;
+        if self.listing_exists(&name)? {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+
+        let now = host::time_now();
+        let session = UploadSession {
+            id: self.next_upload_id,
+            name: name.clone(),
+            owner: caller(),
+            encrypted: encrypted.unwrap_or(false),
+            chunks: Vector::new(),
+            chunk_hashes: Vector::new(),
+            next_index: 0,
+            received_bytes: 0,
+            started_at: now,
+            expires_at: now + UPLOAD_TTL,
+        };
+        self.next_upload_id += 1;
+
+        let status = session.status();
+        self.uploads
+            .insert(session.id, session)
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::UploadStarted {
+            id: status.id,
+            name,
+        });
+        Ok(status)
+    }
+
+    /// Appends chunk `index` to an upload. `sha256` is the hex digest of
+    /// `data`. Re-sending a chunk that was already stored is accepted, so a
+    /// client can retry a call whose response it never saw.
+    pub fn append_upload_chunk(
+        &mut self,
+        upload_id: UploadId,
+        index: u64,
+        data: String,
+        sha256: String,
+    ) -> Result<UploadStatus, FileExchangeError> {
+        self.check_migrated()?;
+        let mut session = self.upload_session(upload_id)?;
+
+        if data.len() > MAX_CHUNK_BYTES {
+            return Err(FileExchangeError::ChunkTooLarge);
+        }
+        let digest = sha256_hex(data.as_bytes());
+        if !digest.eq_ignore_ascii_case(&sha256) {
+            return Err(FileExchangeError::ChecksumMismatch);
+        }
+
+        if index < session.next_index {
+            let stored = session
+                .chunk_hashes
+                .get(index as usize)
+                .map_err(FileExchangeError::storage)?;
+            if stored.as_deref() != Some(digest.as_str()) {
+                return Err(FileExchangeError::ChecksumMismatch);
+            }
+            return Ok(session.status());
+        }
+        if index > session.next_index {
+            return Err(FileExchangeError::OutOfOrderChunk {
+                expected: session.next_index,
+            });
+        }
+
+        session.received_bytes += data.len() as u64;
+        session
+            .chunks
+            .push(data)
+            .map_err(FileExchangeError::storage)?;
+        session
+            .chunk_hashes
+            .push(digest)
+            .map_err(FileExchangeError::storage)?;
+        session.next_index += 1;
+        session.expires_at = host::time_now() + UPLOAD_TTL;
+
+        let status = session.status();
+        self.uploads
+            .insert(upload_id, session)
+            .map_err(FileExchangeError::storage)?;
+        Ok(status)
+    }
+
+    pub fn upload_status(&self, upload_id: UploadId) -> Result<UploadStatus, FileExchangeError> {
+        self.upload_session(upload_id)
+            .map(|session| session.status())
+    }
+
+    /// Turns a finished upload into a file and returns its digest. If
+    /// `sha256` is given, the assembled content must match it.
+    pub fn finalize_upload(
+        &mut self,
+        upload_id: UploadId,
+        sha256: Option<String>,
+    ) -> Result<String, FileExchangeError> {
+        self.check_migrated()?;
+        let mut session = self.upload_session(upload_id)?;
+
+        if self.listing_exists(&session.name)? {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+
+        let pieces: Vec<String> = session
+            .chunks
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .collect();
+        let digest = digest_pieces(pieces.iter().map(String::as_str));
+        if let Some(expected) = sha256 {
+            if !digest.eq_ignore_ascii_case(&expected) {
+                return Err(FileExchangeError::ChecksumMismatch);
+            }
+        }
+
+        self.uploads
+            .remove(&upload_id)
+            .map_err(FileExchangeError::storage)?;
+        session
+            .chunk_hashes
+            .clear()
+            .map_err(FileExchangeError::storage)?;
+
+        let mut blob = match self.blob(&digest)? {
+            Some(blob) => {
+                session.chunks.clear().map_err(FileExchangeError::storage)?;
+                blob
+            }
+            None => Blob {
+                size: session.received_bytes,
+                chunks: session.chunks,
+                refs: 0,
+            },
+        };
+        blob.refs += 1;
+        self.blobs
+            .insert(digest.clone(), blob)
+            .map_err(FileExchangeError::storage)?;
+
+        self.add_file(
+            session.name,
+            session.received_bytes,
+            digest,
+            session.encrypted,
+        )
+    }
+
+    pub fn abort_upload(&mut self, upload_id: UploadId) -> Result<(), FileExchangeError> {
+        self.check_migrated()?;
+        let session = self.upload_session(upload_id)?;
+
+        self.drop_upload(session)?;
+        host::emit(Event::UploadAborted { id: upload_id });
+        Ok(())
+    }
+
+    /// Drops every upload that has gone `UPLOAD_TTL` without a chunk, so
+    /// abandoned ones do not hold their chunks forever. Anyone may call
+    /// it. Returns the ids of the dropped uploads.
+    pub fn expire_uploads(&mut self) -> Result<Vec<UploadId>, FileExchangeError> {
+        self.check_migrated()?;
+        let now = host::time_now();
+        let expired: Vec<UploadSession> = self
+            .uploads
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .map(|(_, session)| session)
+            .filter(|session| session.is_expired_at(now))
+            .collect();
+
+        let mut ids = Vec::with_capacity(expired.len());
+        for session in expired {
+            let id = session.id;
+            self.drop_upload(session)?;
+            host::emit(Event::UploadExpired { id });
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    pub fn delete_file(&mut self, name: String) -> Result<(), FileExchangeError> {
+        self.check_migrated()?;
+        let file = self.file(&name)?;
+
+        if file.owner != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+
+        self.release_blob(&file.sha256)?;
+        self.files
+            .remove(&name)
+            .map_err(FileExchangeError::storage)?;
+        self.drop_listing(&name)?;
+        self.drop_grants(&name)?;
+        self.drop_wrapped_keys(&name)?;
+
+        host::emit(Event::FileDeleted { name });
+        Ok(())
+    }
+
+    pub fn list_files(&self) -> Result<Vec<String>, FileExchangeError> {
+        self.files
+            .entries()
+            .map_err(FileExchangeError::storage)
+            .map(|entries| entries.map(|(key, _)| key).collect())
+    }
+
+    // ===== Dataset Functions =====
+
+    /// Creates a dataset. Chunks of an `encrypted` dataset carry only
+    /// sealed content, so the node cannot search them.
+    pub fn upload_dataset(
+        &mut self,
+        name: String,
+        model: String,
+        dimension: u32,
+        metric: DistanceMetric,
+        chunks: Vec<EmbeddingChunk>,
+        encrypted: Option<bool>,
+    ) -> Result<DatasetInfo, FileExchangeError> {
+        self.check_migrated()?;
+        let owner = caller();
+
+        if self.listing_exists(&name)? {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+        if dimension == 0 {
+            return Err(FileExchangeError::InvalidDataset(
+                "dimension must be greater than zero".to_owned(),
+            ));
+        }
+
+        let mut dataset = EmbeddingDataset::new(
+            name.clone(),
+            owner.clone(),
+            model,
+            dimension,
+            metric,
+            encrypted.unwrap_or(false),
+        );
+        Self::push_chunks(&mut dataset, chunks)?;
+        let info = dataset.info();
+
+        self.datasets
+            .insert(name.clone(), dataset)
+            .map_err(FileExchangeError::storage)?;
+        self.add_listing(&name)?;
+
+        host::emit(Event::DatasetUploaded {
+            name,
+            owner,
+            chunk_count: info.chunk_count,
+        });
+        Ok(info)
+    }
+
+    pub fn append_dataset_chunks(
+        &mut self,
+        name: String,
+        chunks: Vec<EmbeddingChunk>,
+    ) -> Result<DatasetInfo, FileExchangeError> {
+        self.check_migrated()?;
+        let mut dataset = self.dataset(&name)?;
+
+        if dataset.owner != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+
+        Self::push_chunks(&mut dataset, chunks)?;
+        let info = dataset.info();
+
+        self.datasets
+            .insert(name.clone(), dataset)
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::DatasetChunksAppended {
+            name,
+            chunk_count: info.chunk_count,
+        });
+        Ok(info)
+    }
+
+    pub fn list_datasets(&self) -> Result<Vec<DatasetInfo>, FileExchangeError> {
+        self.datasets
+            .entries()
+            .map_err(FileExchangeError::storage)
+            .map(|entries| entries.map(|(_, dataset)| dataset.info()).collect())
+    }
+
+    pub fn get_dataset(&self, name: String) -> Result<DatasetInfo, FileExchangeError> {
+        self.dataset(&name).map(|dataset| dataset.info())
+    }
+
+    pub fn get_dataset_chunks(
+        &self,
+        name: String,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<EmbeddingChunk>, FileExchangeError> {
+        self.check_access(&name, &caller())?;
+
+        let dataset = self.dataset(&name)?;
+
+        let chunks = dataset
+            .chunks
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect();
+
+        Ok(chunks)
+    }
+
+    pub fn delete_dataset(&mut self, name: String) -> Result<(), FileExchangeError> {
+        self.check_migrated()?;
+        let mut dataset = self.dataset(&name)?;
+
+        if dataset.owner != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+
+        dataset.chunks.clear().map_err(FileExchangeError::storage)?;
+        dataset
+            .chunk_ids
+            .clear()
+            .map_err(FileExchangeError::storage)?;
+        self.datasets
+            .remove(&name)
+            .map_err(FileExchangeError::storage)?;
+        self.drop_listing(&name)?;
+        self.drop_grants(&name)?;
+        self.drop_wrapped_keys(&name)?;
+        self.query_prices
+            .remove(&name)
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::DatasetDeleted { name });
+        Ok(())
+    }
+
+    // ===== Search Functions =====
+
+    /// Runs a top-k similarity search on the node, so buyers can query a
+    /// dataset without downloading its vectors. Defaults to the dataset's
+    /// own metric.
+    pub fn search(
+        &mut self,
+        dataset: String,
+        query_vector: Vec<f32>,
+        k: u32,
+        metric: Option<DistanceMetric>,
+    ) -> Result<Vec<SearchHit>, FileExchangeError> {
+        self.check_migrated()?;
+        let reader = caller();
+        let charge = match self.check_access(&dataset, &reader) {
+            Ok(()) => None,
+            Err(FileExchangeError::Unauthorized) => Some(
+                self.query_price(&dataset)?
+                    .ok_or(FileExchangeError::Unauthorized)?,
+            ),
+            Err(err) => return Err(err),
+        };
+
+        let dataset = self.dataset(&dataset)?;
+
+        if dataset.encrypted {
+            return Err(FileExchangeError::EncryptedListing);
+        }
+        if query_vector.len() != dataset.dimension as usize {
+            return Err(FileExchangeError::InvalidDataset(format!(
+                "query has dimension {}, expected {}",
+                query_vector.len(),
+                dataset.dimension
+            )));
+        }
+        if query_vector.iter().any(|value| !value.is_finite()) {
+            return Err(FileExchangeError::InvalidDataset(
+                "query contains non-finite values".to_owned(),
+            ));
+        }
+        if k == 0 || k > MAX_SEARCH_K {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+        if let Some(price) = charge {
+            self.charge_query(&dataset, reader, price)?;
+        }
+
+        let mut top = TopK::new(k, metric.unwrap_or(dataset.metric));
+        for chunk in dataset
+            .chunks
+            .entries()
+            .map_err(FileExchangeError::storage)?
+        {
+            top.offer(&query_vector, chunk);
+        }
+
+        Ok(top.into_hits())
+    }
+
+    // ===== Access Control Functions =====
+
+    /// Lets the caller read one of their listings. `expires_at` is node time
+    /// in nanoseconds; `None` grants access indefinitely.
+    pub fn grant_access(
+        &mut self,
+        name: String,
+        reader: String,
+        expires_at: Option<u64>,
+    ) -> Result<AccessGrant, FileExchangeError> {
+        self.check_migrated()?;
+        let owner = caller();
+
+        if self.listing_owner(&name)? != owner {
+            return Err(FileExchangeError::Unauthorized);
+        }
+        if reader == owner {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+
+        self.put_grant(
+            name,
+            AccessGrant {
+                reader,
+                granted_by: owner,
+                granted_at: host::time_now(),
+                expires_at,
+            },
+        )
+    }
+
+    pub fn revoke_access(&mut self, name: String, reader: String) -> Result<(), FileExchangeError> {
+        self.check_migrated()?;
+        if self.listing_owner(&name)? != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+
+        let mut grants = self
+            .grants
+            .get(&name)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::InvalidOperation)?;
+        grants
+            .remove(&reader)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::InvalidOperation)?;
+        self.grants
+            .insert(name.clone(), grants)
+            .map_err(FileExchangeError::storage)?;
+        self.take_wrapped_key(&name, &reader)?;
+
+        host::emit(Event::AccessRevoked { name, reader });
+        Ok(())
+    }
+
+    pub fn list_grants(&self, name: String) -> Result<AccessList, FileExchangeError> {
+        let owner = self.listing_owner(&name)?;
+
+        if owner != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+
+        let grants = match self.grants.get(&name).map_err(FileExchangeError::storage)? {
+            Some(grants) => grants
+                .entries()
+                .map_err(FileExchangeError::storage)?
+                .map(|(_, grant)| grant)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok(AccessList { owner, grants })
+    }
+
+    pub fn has_access(&self, name: String) -> Result<bool, FileExchangeError> {
+        match self.check_access(&name, &caller()) {
+            Ok(()) => Ok(true),
+            Err(FileExchangeError::Unauthorized) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    // ===== Encryption Functions =====
+
+    /// Stores the listing's content key wrapped for `reader`. The owner
+    /// wraps it client-side, so the key itself never reaches context state;
+    /// the reader must already have access.
+    pub fn add_wrapped_key(
+        &mut self,
+        name: String,
+        reader: String,
+        key: SealedKey,
+    ) -> Result<WrappedKey, FileExchangeError> {
+        self.check_migrated()?;
+        let owner = caller();
+
+        if self.listing_owner(&name)? != owner {
+            return Err(FileExchangeError::Unauthorized);
+        }
+        if !self.is_encrypted(&name)? {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+        self.check_access(&name, &reader)?;
+        key.check().map_err(FileExchangeError::InvalidWrappedKey)?;
+
+        let wrapped = WrappedKey {
+            reader: reader.clone(),
+            algorithm: ENVELOPE_ALGORITHM.to_owned(),
+            key,
+            wrapped_by: owner,
+            wrapped_at: host::time_now(),
+        };
+
+        let mut keys = self
+            .wrapped_keys
+            .get(&name)
+            .map_err(FileExchangeError::storage)?
+            .unwrap_or_default();
+        keys.insert(reader.clone(), wrapped.clone())
+            .map_err(FileExchangeError::storage)?;
+        self.wrapped_keys
+            .insert(name.clone(), keys)
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::KeyWrapped { name, reader });
+        Ok(wrapped)
+    }
+
+    /// Removing a key stops the node from handing it out, but a reader who
+    /// already fetched it can still decrypt; re-upload under a new key to
+    /// shut them out for good.
+    pub fn remove_wrapped_key(
+        &mut self,
+        name: String,
+        reader: String,
+    ) -> Result<(), FileExchangeError> {
+        self.check_migrated()?;
+        if self.listing_owner(&name)? != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+
+        self.take_wrapped_key(&name, &reader)?
+            .ok_or(FileExchangeError::WrappedKeyNotFound)?;
+        Ok(())
+    }
+
+    /// The caller's wrapped content key, while they still have access.
+    pub fn get_wrapped_key(&self, name: String) -> Result<WrappedKey, FileExchangeError> {
+        let reader = caller();
+        self.check_access(&name, &reader)?;
+
+        let key = match self
+            .wrapped_keys
+            .get(&name)
+            .map_err(FileExchangeError::storage)?
+        {
+            Some(keys) => keys.get(&reader).map_err(FileExchangeError::storage)?,
+            None => None,
+        };
+        key.ok_or(FileExchangeError::WrappedKeyNotFound)
+    }
+
+    /// Readers of an encrypted listing who have access but no key yet, so
+    /// the owner knows whom to wrap for after a sale.
+    pub fn list_missing_keys(&self, name: String) -> Result<Vec<String>, FileExchangeError> {
+        let owner = self.listing_owner(&name)?;
+
+        if owner != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+        if !self.is_encrypted(&name)? {
+            return Ok(Vec::new());
+        }
+
+        let keys = self
+            .wrapped_keys
+            .get(&name)
+            .map_err(FileExchangeError::storage)?;
+        let has_key = |reader: &str| -> Result<bool, FileExchangeError> {
+            match &keys {
+                Some(keys) => keys
+                    .get(reader)
+                    .map(|key| key.is_some())
+                    .map_err(FileExchangeError::storage),
+                None => Ok(false),
+            }
+        };
+
+        let mut readers = Vec::new();
+        if !has_key(owner.as_str())? {
+            readers.push(owner);
+        }
+
+        let Some(grants) = self.grants.get(&name).map_err(FileExchangeError::storage)? else {
+            return Ok(readers);
+        };
+        let now = host::time_now();
+        for (reader, grant) in grants.entries().map_err(FileExchangeError::storage)? {
+            if grant.is_valid_at(now) && !has_key(reader.as_str())? {
+                readers.push(reader);
+            }
+        }
+        Ok(readers)
+    }
+}
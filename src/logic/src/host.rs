@@ -0,0 +1,105 @@
+//! Everything the app asks of the node runtime. Under `cargo test` these
+//! calls go to `crate::mock` instead, so the logic runs natively.
+
+#[cfg(all(test, feature = "marketplace"))]
+pub use crate::mock::propose_all;
+#[cfg(all(test, feature = "proposals"))]
+pub use crate::mock::{approve, log, propose};
+#[cfg(all(test, any(feature = "files", feature = "proposals")))]
+pub use crate::mock::{emit, executor_id, time_now};
+#[cfg(all(not(test), feature = "marketplace"))]
+pub use runtime::propose_all;
+#[cfg(all(not(test), feature = "proposals"))]
+pub use runtime::{approve, log, propose};
+#[cfg(all(not(test), any(feature = "files", feature = "proposals")))]
+pub use runtime::{emit, executor_id, time_now};
+
+#[cfg(all(not(test), any(feature = "files", feature = "proposals")))]
+mod runtime {
+    use calimero_sdk::app;
+    use calimero_sdk::env;
+    #[cfg(feature = "proposals")]
+    use calimero_sdk::env::ext::{self, AccountId, DraftProposal};
+    #[cfg(feature = "proposals")]
+    use getem_types::{CreateProposalRequest, ProposalId};
+
+    use crate::Event;
+    #[cfg(feature = "proposals")]
+    use crate::FileExchangeState;
+
+    pub fn executor_id() -> [u8; 32] {
+        env::executor_id()
+    }
+
+    pub fn time_now() -> u64 {
+        env::time_now()
+    }
+
+    #[cfg(feature = "proposals")]
+    pub fn log(message: &str) {
+        env::log(message);
+    }
+
+    pub fn emit(event: Event) {
+        app::emit!(event);
+    }
+
+    /// Sends the proposal to the context proxy; the caller's proposal
+    /// counts as their approval.
+    #[cfg(feature = "proposals")]
+    pub fn propose(action: CreateProposalRequest) -> ProposalId {
+        propose_all(vec![action])
+    }
+
+    /// Sends a single proposal carrying all `actions`, which the proxy
+    /// executes together once it is approved.
+    #[cfg(feature = "proposals")]
+    pub fn propose_all(actions: Vec<CreateProposalRequest>) -> ProposalId {
+        let id = actions
+            .into_iter()
+            .fold(FileExchangeState::external().propose(), add_action)
+            .send();
+        ProposalId(id.0)
+    }
+
+    #[cfg(feature = "proposals")]
+    fn add_action(draft: DraftProposal, action: CreateProposalRequest) -> DraftProposal {
+        match action {
+            CreateProposalRequest::ExternalFunctionCall {
+                receiver_id,
+                method_name,
+                args,
+                deposit,
+            } => {
+                env::log(&format!(
+                    "Parsed values: receiver_id={}, method_name={}, args={}, deposit={}",
+                    receiver_id, method_name, args, deposit.0
+                ));
+
+                draft.external_function_call(receiver_id, method_name, args, deposit.0)
+            }
+            CreateProposalRequest::Transfer {
+                receiver_id,
+                amount,
+            } => draft.transfer(AccountId(receiver_id), amount.0),
+            CreateProposalRequest::SetContextValue { key, value } => draft.set_context_value(
+                key.into_bytes().into_boxed_slice(),
+                value.into_bytes().into_boxed_slice(),
+            ),
+            CreateProposalRequest::SetNumApprovals { num_approvals } => {
+                draft.set_num_approvals(num_approvals)
+            }
+            CreateProposalRequest::SetActiveProposalsLimit {
+                active_proposals_limit,
+            } => draft.set_active_proposals_limit(active_proposals_limit),
+            CreateProposalRequest::DeleteProposal { proposal_id } => {
+                draft.delete(ext::ProposalId(proposal_id.0))
+            }
+        }
+    }
+
+    #[cfg(feature = "proposals")]
+    pub fn approve(proposal_id: ProposalId) {
+        FileExchangeState::external().approve(ext::ProposalId(proposal_id.0));
+    }
+}
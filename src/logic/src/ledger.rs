@@ -0,0 +1,12 @@
+pub use getem_types::ledger::LedgerEntry;
+#[cfg(feature = "marketplace")]
+pub use getem_types::ledger::Statement;
+#[cfg(any(feature = "files", feature = "proposals"))]
+pub use getem_types::ledger::{EntryId, EntryKind};
+
+/// A credit deposit's proposal sets `<prefix><order id>` to the buyer and
+/// amount. The money stays in the proxy's treasury, which pays the
+/// withdrawals, so the proposal only records the credit the members
+/// approved.
+#[cfg(feature = "marketplace")]
+pub const CREDIT_DEPOSIT_KEY_PREFIX: &str = "getem.credit_deposit.";
@@ -1,22 +1,122 @@
+#[cfg(feature = "files")]
+use std::collections::HashSet;
+use std::fmt;
+
+#[cfg(feature = "proposals")]
+use calimero_sdk::types::Error;
 use calimero_sdk::{
     app,
     borsh::{BorshDeserialize, BorshSerialize},
-    env,
-    env::ext::{AccountId, ProposalId},
-    serde::{Deserialize, Serialize},
-    types::Error,
+    serde::Serialize,
 };
 use calimero_storage::collections::{UnorderedMap, Vector};
+use getem_types::message::Message;
+#[cfg(feature = "proposals")]
+use getem_types::message::{check_text, MessageError, MessageId, MessagePage};
+#[cfg(feature = "proposals")]
+use getem_types::proposal::{
+    parse_flag, ApprovalError, CreateProposalRequest, ProposalStatus, ARCHIVE_MESSAGES_KEY,
+};
+use getem_types::proposal::{ProposalId, ProposalRecord, U128};
+
+mod acl;
+mod blob;
+mod catalog;
+mod dataset;
+mod envelope;
+#[cfg(feature = "files")]
+mod files;
+mod host;
+mod ledger;
+#[cfg(feature = "marketplace")]
+mod marketplace;
+mod order;
+#[cfg(feature = "proposals")]
+mod proposals;
+mod review;
+mod schema;
+#[cfg(feature = "files")]
+mod search;
+mod subscription;
+mod terms;
+mod upload;
+
+#[cfg(all(test, any(feature = "files", feature = "proposals")))]
+mod mock;
+#[cfg(all(test, any(feature = "files", feature = "proposals")))]
+mod tests;
+
+use acl::AccessGrant;
+#[cfg(feature = "files")]
+use acl::AccessList;
+use blob::Blob;
+#[cfg(feature = "files")]
+use blob::{digest_pieces, FileDownload, FileInfo};
+use catalog::ListingMeta;
+#[cfg(feature = "marketplace")]
+use catalog::{
+    decode_cursor, encode_cursor, normalize_tags, ListingFilter, ListingKind, ListingPage,
+    ListingSort, ListingSummary,
+};
+use dataset::EmbeddingDataset;
+#[cfg(feature = "files")]
+use dataset::{DatasetInfo, DistanceMetric, EmbeddingChunk};
+use envelope::WrappedKey;
+#[cfg(feature = "files")]
+use envelope::{SealedKey, ENVELOPE_ALGORITHM};
+use ledger::LedgerEntry;
+#[cfg(any(feature = "files", feature = "proposals"))]
+use ledger::{EntryId, EntryKind};
+#[cfg(feature = "marketplace")]
+use ledger::{Statement, CREDIT_DEPOSIT_KEY_PREFIX};
+#[cfg(feature = "marketplace")]
+use order::ORDER_TTL;
+use order::{Order, OrderId};
+#[cfg(feature = "proposals")]
+use order::{OrderItem, OrderStatus};
+#[cfg(feature = "marketplace")]
+use review::{check_rating, check_review_text, Reply};
+use review::{RatingTally, Review};
+use schema::PendingMigration;
+#[cfg(any(feature = "files", feature = "proposals"))]
+use schema::MIGRATION_REQUIRED;
+#[cfg(feature = "files")]
+use search::{SearchHit, TopK, MAX_SEARCH_K};
+#[cfg(feature = "marketplace")]
+use subscription::check_plan;
+use subscription::{Plan, PlanId, Subscription};
+#[cfg(feature = "proposals")]
+use terms::{parse_fee_bps, validate_setting, MARKETPLACE_FEE_ACCOUNT_KEY, MARKETPLACE_FEE_KEY};
+#[cfg(feature = "marketplace")]
+use terms::{split, License, MarketplaceFee, Price, NATIVE_TOKEN};
+#[cfg(feature = "files")]
+use upload::{read_range, ContentRange, UploadStatus, MAX_CHUNK_BYTES, UPLOAD_TTL};
+use upload::{sha256_hex, split_content, UploadId, UploadSession};
 
 // ---------------- FileExchange Types ----------------
 
-#[derive(BorshDeserialize, BorshSerialize, Clone, Serialize, Deserialize)]
+/// A named pointer to content in `blobs`, keyed by its SHA-256 digest.
+#[derive(BorshDeserialize, BorshSerialize)]
 #[borsh(crate = "calimero_sdk::borsh")]
-#[serde(crate = "calimero_sdk::serde")]
 pub struct FileEntry {
     name: String,
-    content: String,
     owner: String,
+    size: u64,
+    sha256: String,
+    encrypted: bool,
+}
+
+impl FileEntry {
+    #[cfg(feature = "files")]
+    fn info(&self) -> FileInfo {
+        FileInfo {
+            name: self.name.clone(),
+            owner: self.owner.clone(),
+            size: self.size,
+            sha256: self.sha256.clone(),
+            encrypted: self.encrypted,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -26,271 +126,1333 @@ pub enum FileExchangeError {
     ProposalError(String),
     StorageError(String),
     InvalidOperation,
+    InvalidDataset(String),
+    UploadNotFound,
+    ChecksumMismatch,
+    ChunkTooLarge,
+    OutOfOrderChunk { expected: u64 },
+    OrderNotFound,
+    PayoutAccountMissing,
     Unauthorized,
+    EncryptedListing,
+    WrappedKeyNotFound,
+    InvalidWrappedKey(String),
+    InvalidListing(String),
+    InvalidCursor,
+    ListingNotForSale,
+    PlanNotFound,
+    InvalidPlan(String),
+    PlanRetired,
+    AlreadySubscribed,
+    SubscriptionNotFound,
+    MarketplaceAccountMissing,
+    InsufficientCredit { balance: U128, price: U128 },
+    CreditOverflow,
+    InvalidReview(String),
+    NotAVerifiedBuyer,
+    AlreadyReviewed,
+    ReviewNotFound,
+    AlreadyReplied,
+    UnsupportedToken(String),
+    MigrationRequired(String),
 }
 
-// ---------------- Proposal Types ----------------
-
-#[derive(
-    Clone, Debug, PartialEq, PartialOrd, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
-)]
-#[borsh(crate = "calimero_sdk::borsh")]
-#[serde(crate = "calimero_sdk::serde")]
-pub struct Message {
-    id: String,
-    proposal_id: String,
-    author: String,
-    text: String,
-    created_at: String,
+impl FileExchangeError {
+    fn storage(err: impl ToString) -> Self {
+        FileExchangeError::StorageError(err.to_string())
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(crate = "calimero_sdk::serde")]
-pub struct CreateProposalRequest {
-    pub action_type: String,
-    pub params: serde_json::Value,
+impl fmt::Display for FileExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
 }
 
+impl std::error::Error for FileExchangeError {}
+
 // ---------------- Events ----------------
 
-#[app::event]
-pub enum Event {
-    // File exchange events
-    FileUploaded { name: String, owner: String },
-    FileDownloaded { name: String, downloader: String },
-    FileDeleted { name: String },
-    // Proposal events
-    ProposalCreated { id: ProposalId },
-    ApprovedProposal { id: ProposalId },
+getem_types::define_events! {
+    #[app::event]
 }
 
 // ---------------- Main State ----------------
 
+/// Serialized with a version header, see `schema`. New fields must be added
+/// there as well.
 #[app::state(emits = Event)]
-#[derive(BorshDeserialize, BorshSerialize)]
-#[borsh(crate = "calimero_sdk::borsh")]
 pub struct FileExchangeState {
     // FileExchange storage (using std::collections::HashMap)
     files: UnorderedMap<String, FileEntry>,
+    // File content by SHA-256 digest, shared between identical uploads
+    blobs: UnorderedMap<String, Blob>,
+    // Chunked uploads that have not been finalized yet
+    uploads: UnorderedMap<UploadId, UploadSession>,
+    next_upload_id: UploadId,
+    // Embedding datasets, sharing the listing namespace with `files`
+    datasets: UnorderedMap<String, EmbeddingDataset>,
+    // Catalog data (age, tags, price) of every file and dataset
+    listings: UnorderedMap<String, ListingMeta>,
+    next_listing_seq: u64,
     // Proposal-related storage (using calimero_storage collections)
     proposal_messages: UnorderedMap<ProposalId, Vector<Message>>,
+    proposals: UnorderedMap<ProposalId, ProposalRecord>,
+    num_approvals: u32,
+    // The threshold a client last read from the proxy; nothing settles
+    // while it differs from `num_approvals`
+    proxy_num_approvals: Option<u32>,
+    // Threads of finished proposals, kept only while `archive_messages` is on
+    archived_messages: UnorderedMap<ProposalId, Vector<Message>>,
+    archive_messages: bool,
+    // Purchases: orders and the Transfer proposals paying for them
+    orders: UnorderedMap<OrderId, Order>,
+    order_proposals: UnorderedMap<ProposalId, OrderId>,
+    next_order_id: OrderId,
+    payout_accounts: UnorderedMap<String, String>,
+    // Marketplace fee, set through SetContextValue proposals
+    fee_bps: u32,
+    fee_account: Option<String>,
+    // Subscription plans and, per subscriber, their windows on each plan
+    plans: UnorderedMap<PlanId, Plan>,
+    next_plan_id: PlanId,
+    subscriptions: UnorderedMap<String, UnorderedMap<PlanId, Subscription>>,
+    // Pay-per-query: price of one search per dataset, and every identity's
+    // credit with the statement behind it
+    query_prices: UnorderedMap<String, U128>,
+    credit_balances: UnorderedMap<String, U128>,
+    ledger: UnorderedMap<String, Vector<LedgerEntry>>,
+    // Reviews: listing name -> reviewer -> review, and each listing's totals
+    reviews: UnorderedMap<String, UnorderedMap<String, Review>>,
+    ratings: UnorderedMap<String, RatingTally>,
+    // Per-listing access control: listing name -> reader -> grant
+    grants: UnorderedMap<String, UnorderedMap<String, AccessGrant>>,
+    // Content keys of encrypted listings: listing name -> reader -> key
+    wrapped_keys: UnorderedMap<String, UnorderedMap<String, WrappedKey>>,
+    // Not stored: parts of an older layout left for `migrate` to convert
+    migration: Option<PendingMigration>,
 }
 
 // ---------------- Logic Implementation ----------------
 
+// The exported methods are in `files`, `marketplace` and `proposals`, one
+// cargo feature each.
+
 #[app::logic]
 impl FileExchangeState {
+    /// `num_approvals` is the number of approvals the context proxy
+    /// requires before it executes a proposal, passed in the context's
+    /// init parameters, e.g. `--params '{"num_approvals": 3}'`. It must
+    /// match the proxy: see `update_proposal_status`.
     #[app::init]
-    pub fn init() -> FileExchangeState {
+    pub fn init(num_approvals: u32) -> FileExchangeState {
         FileExchangeState {
             files: UnorderedMap::new(),
+            blobs: UnorderedMap::new(),
+            uploads: UnorderedMap::new(),
+            next_upload_id: 0,
+            datasets: UnorderedMap::new(),
+            listings: UnorderedMap::new(),
+            next_listing_seq: 0,
             proposal_messages: UnorderedMap::new(),
+            proposals: UnorderedMap::new(),
+            num_approvals,
+            proxy_num_approvals: None,
+            archived_messages: UnorderedMap::new(),
+            archive_messages: false,
+            orders: UnorderedMap::new(),
+            order_proposals: UnorderedMap::new(),
+            next_order_id: 0,
+            payout_accounts: UnorderedMap::new(),
+            fee_bps: 0,
+            fee_account: None,
+            plans: UnorderedMap::new(),
+            next_plan_id: 0,
+            subscriptions: UnorderedMap::new(),
+            query_prices: UnorderedMap::new(),
+            credit_balances: UnorderedMap::new(),
+            ledger: UnorderedMap::new(),
+            reviews: UnorderedMap::new(),
+            ratings: UnorderedMap::new(),
+            grants: UnorderedMap::new(),
+            wrapped_keys: UnorderedMap::new(),
+            migration: None,
         }
     }
 
-    // ===== File Exchange Functions =====
+    /// Converts a state stored by an older version of the app to the
+    /// current layout and writes it back. Until this has run, the parts
+    /// stored in an older format read as empty and calls that change the
+    /// state fail with `MigrationRequired`. Returns whether there was
+    /// anything to convert.
+    pub fn migrate(&mut self) -> Result<bool, FileExchangeError> {
+        self.run_migration().map_err(FileExchangeError::storage)
+    }
+}
 
-    pub fn upload_file(
-        &mut self,
-        name: String,
-        content: String,
-        owner: String,
-    ) -> Result<(), FileExchangeError> {
-        if self.files.get(&name).map_err(|e| FileExchangeError::StorageError(e.to_string()))?.is_some() {
-            return Err(FileExchangeError::InvalidOperation);
+// ---------------- Helpers ----------------
+
+/// The identity executing the current call, as the base58 public key that
+/// clients see in their JWT. Never trust an identity passed as an argument.
+#[cfg(any(feature = "files", feature = "proposals"))]
+fn caller() -> String {
+    bs58::encode(host::executor_id()).into_string()
+}
+
+#[cfg(feature = "marketplace")]
+fn file_summary(file: &FileEntry, meta: ListingMeta) -> ListingSummary {
+    ListingSummary {
+        name: file.name.clone(),
+        kind: ListingKind::File,
+        owner: file.owner.clone(),
+        size: Some(file.size),
+        chunk_count: None,
+        model: None,
+        dimension: None,
+        price: meta.price,
+        license: meta.license,
+        query_price: None,
+        rating: None,
+        review_count: 0,
+        tags: meta.tags,
+        encrypted: file.encrypted,
+        created_at: meta.created_at,
+        seq: meta.seq,
+    }
+}
+
+#[cfg(feature = "marketplace")]
+fn dataset_summary(
+    dataset: &EmbeddingDataset,
+    meta: ListingMeta,
+    query_price: Option<U128>,
+) -> ListingSummary {
+    ListingSummary {
+        name: dataset.name.clone(),
+        kind: ListingKind::Dataset,
+        owner: dataset.owner.clone(),
+        size: None,
+        chunk_count: Some(dataset.chunk_count),
+        model: Some(dataset.model.clone()),
+        dimension: Some(dataset.dimension),
+        price: meta.price,
+        license: meta.license,
+        query_price,
+        rating: None,
+        review_count: 0,
+        tags: meta.tags,
+        encrypted: dataset.encrypted,
+        created_at: meta.created_at,
+        seq: meta.seq,
+    }
+}
+
+impl FileExchangeState {
+    /// A state loaded in an older layout cannot be written back, so calls
+    /// that change it are refused with a pointer to `migrate` rather than
+    /// failing when the state is stored.
+    #[cfg(any(feature = "files", feature = "proposals"))]
+    fn check_migrated(&self) -> Result<(), FileExchangeError> {
+        match self.migration {
+            Some(_) => Err(FileExchangeError::MigrationRequired(
+                MIGRATION_REQUIRED.to_owned(),
+            )),
+            None => Ok(()),
         }
+    }
 
-        let file = FileEntry {
-            name: name.clone(),
-            content,
-            owner: owner.clone(),
+    /// Files and datasets are both sold by name, so a name may only be used once.
+    #[cfg(feature = "files")]
+    fn listing_exists(&self, name: &str) -> Result<bool, FileExchangeError> {
+        let file = self.files.get(name).map_err(FileExchangeError::storage)?;
+        let dataset = self
+            .datasets
+            .get(name)
+            .map_err(FileExchangeError::storage)?;
+        Ok(file.is_some() || dataset.is_some())
+    }
+
+    #[cfg(feature = "files")]
+    fn listing_owner(&self, name: &str) -> Result<String, FileExchangeError> {
+        if let Some(file) = self.files.get(name).map_err(FileExchangeError::storage)? {
+            return Ok(file.owner);
+        }
+        self.dataset(name).map(|dataset| dataset.owner)
+    }
+
+    /// Owners can always read their listings; everyone else needs a grant
+    /// that has not expired or a running subscription that covers it.
+    #[cfg(feature = "files")]
+    fn check_access(&self, name: &str, reader: &str) -> Result<(), FileExchangeError> {
+        let owner = self.listing_owner(name)?;
+        if owner == reader {
+            return Ok(());
+        }
+
+        let now = host::time_now();
+        let grant = match self.grants.get(name).map_err(FileExchangeError::storage)? {
+            Some(grants) => grants.get(reader).map_err(FileExchangeError::storage)?,
+            None => None,
         };
-        
-        self.files
-            .insert(name.clone(), file)
-            .map_err(|e| FileExchangeError::StorageError(e.to_string()))?;
+        if grant.is_some_and(|grant| grant.is_valid_at(now)) {
+            return Ok(());
+        }
 
-        app::emit!(Event::FileUploaded { name, owner });
-        Ok(())
+        if self.is_subscribed(name, &owner, reader, now)? {
+            return Ok(());
+        }
+        Err(FileExchangeError::Unauthorized)
     }
 
-    pub fn download_file(
+    #[cfg(feature = "files")]
+    fn is_subscribed(
         &self,
+        name: &str,
+        owner: &str,
+        reader: &str,
+        now: u64,
+    ) -> Result<bool, FileExchangeError> {
+        let Some(subscriptions) = self
+            .subscriptions
+            .get(reader)
+            .map_err(FileExchangeError::storage)?
+        else {
+            return Ok(false);
+        };
+
+        let running: Vec<PlanId> = subscriptions
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .filter(|(_, subscription)| subscription.is_active_at(now))
+            .map(|(plan_id, _)| plan_id)
+            .collect();
+        for plan_id in running {
+            if self.plan(plan_id)?.covers(name, owner) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    #[cfg(any(feature = "files", feature = "proposals"))]
+    fn put_grant(
+        &mut self,
         name: String,
-        downloader: String,
-    ) -> Result<String, FileExchangeError> {
-        let file = self.files
+        grant: AccessGrant,
+    ) -> Result<AccessGrant, FileExchangeError> {
+        let mut grants = self
+            .grants
             .get(&name)
-            .map_err(|e| FileExchangeError::StorageError(e.to_string()))?
-            .ok_or(FileExchangeError::FileNotFound)?;
+            .map_err(FileExchangeError::storage)?
+            .unwrap_or_default();
+        grants
+            .insert(grant.reader.clone(), grant.clone())
+            .map_err(FileExchangeError::storage)?;
+        self.grants
+            .insert(name.clone(), grants)
+            .map_err(FileExchangeError::storage)?;
 
-        app::emit!(Event::FileDownloaded {
+        host::emit(Event::AccessGranted {
             name,
-            downloader
+            reader: grant.reader.clone(),
         });
+        Ok(grant)
+    }
 
-        Ok(file.content)
+    #[cfg(feature = "files")]
+    fn drop_grants(&mut self, name: &str) -> Result<(), FileExchangeError> {
+        let grants = self
+            .grants
+            .remove(name)
+            .map_err(FileExchangeError::storage)?;
+        if let Some(mut grants) = grants {
+            grants.clear().map_err(FileExchangeError::storage)?;
+        }
+        Ok(())
     }
 
-    pub fn delete_file(
+    #[cfg(feature = "files")]
+    fn is_encrypted(&self, name: &str) -> Result<bool, FileExchangeError> {
+        if let Some(file) = self.files.get(name).map_err(FileExchangeError::storage)? {
+            return Ok(file.encrypted);
+        }
+        self.dataset(name).map(|dataset| dataset.encrypted)
+    }
+
+    #[cfg(feature = "files")]
+    fn take_wrapped_key(
         &mut self,
-        name: String,
-        requester: String,
-    ) -> Result<(), FileExchangeError> {
-        let file = self.files
-            .get(&name)
-            .map_err(|e| FileExchangeError::StorageError(e.to_string()))?
-            .ok_or(FileExchangeError::FileNotFound)?;
+        name: &str,
+        reader: &str,
+    ) -> Result<Option<WrappedKey>, FileExchangeError> {
+        let Some(mut keys) = self
+            .wrapped_keys
+            .get(name)
+            .map_err(FileExchangeError::storage)?
+        else {
+            return Ok(None);
+        };
 
-        if file.owner != requester {
-            return Err(FileExchangeError::Unauthorized);
+        let removed = keys.remove(reader).map_err(FileExchangeError::storage)?;
+        if removed.is_some() {
+            self.wrapped_keys
+                .insert(name.to_owned(), keys)
+                .map_err(FileExchangeError::storage)?;
+
+            host::emit(Event::KeyRemoved {
+                name: name.to_owned(),
+                reader: reader.to_owned(),
+            });
         }
-        
-        self.files
-            .remove(&name)
-            .map_err(|e| FileExchangeError::StorageError(e.to_string()))?;
+        Ok(removed)
+    }
 
-        app::emit!(Event::FileDeleted { name });
+    #[cfg(feature = "files")]
+    fn drop_wrapped_keys(&mut self, name: &str) -> Result<(), FileExchangeError> {
+        let keys = self
+            .wrapped_keys
+            .remove(name)
+            .map_err(FileExchangeError::storage)?;
+        if let Some(mut keys) = keys {
+            keys.clear().map_err(FileExchangeError::storage)?;
+        }
         Ok(())
     }
 
-    pub fn list_files(&self) -> Result<Vec<String>, FileExchangeError> {
-        self.files
-            .entries()
-            .map_err(|e| FileExchangeError::StorageError(e.to_string()))
-            .map(|entries| entries.map(|(key, _)| key).collect())
+    /// Opens an order for `item` paying the seller, splitting off the
+    /// marketplace fee while a fee account is set.
+    #[cfg(feature = "marketplace")]
+    fn open_payment(
+        &mut self,
+        item: OrderItem,
+        seller: String,
+        price: Price,
+        license: License,
+    ) -> Result<Order, FileExchangeError> {
+        if !price.is_native() {
+            return Err(FileExchangeError::UnsupportedToken(price.token));
+        }
+
+        let payout_account = self
+            .payout_accounts
+            .get(&seller)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::PayoutAccountMissing)?;
+
+        let (payout, fee) = match &self.fee_account {
+            Some(_) => split(price.amount, self.fee_bps),
+            None => (price.amount, U128(0)),
+        };
+
+        let mut actions = vec![CreateProposalRequest::Transfer {
+            receiver_id: payout_account,
+            amount: payout,
+        }];
+        if let Some(fee_account) = self.fee_account.clone().filter(|_| fee.0 > 0) {
+            actions.push(CreateProposalRequest::Transfer {
+                receiver_id: fee_account,
+                amount: fee,
+            });
+        }
+        self.place_order(item, seller, price, fee, license, actions)
     }
 
-    // ===== Proposal Functions =====
+    /// Records an order and sends the proposal that pays for it. The first
+    /// action is the payment; a second one is the marketplace fee.
+    #[cfg(feature = "marketplace")]
+    fn place_order(
+        &mut self,
+        item: OrderItem,
+        seller: String,
+        price: Price,
+        fee: U128,
+        license: License,
+        actions: Vec<CreateProposalRequest>,
+    ) -> Result<Order, FileExchangeError> {
+        let buyer = caller();
+        let action = actions[0].clone();
+        let mut summary = action.summary();
+        if let Some(CreateProposalRequest::Transfer {
+            receiver_id,
+            amount,
+        }) = actions.get(1)
+        {
+            summary = format!("{}, plus a fee of {} to {}", summary, amount.0, receiver_id);
+        }
+
+        host::log(&format!(
+            "Opening order for {:?}: {} {}",
+            item, summary, price.token
+        ));
+        let proposal_id = host::propose_all(actions);
+
+        let now = host::time_now();
+        let order = Order {
+            id: self.next_order_id,
+            item,
+            buyer: buyer.clone(),
+            seller,
+            amount: price.amount,
+            token: price.token,
+            fee,
+            license,
+            proposal_id,
+            status: OrderStatus::Pending,
+            created_at: now,
+            expires_at: now.saturating_add(ORDER_TTL),
+        };
+        self.next_order_id += 1;
+
+        self.orders
+            .insert(order.id, order.clone())
+            .map_err(FileExchangeError::storage)?;
+        self.order_proposals
+            .insert(proposal_id, order.id)
+            .map_err(FileExchangeError::storage)?;
+
+        let record = ProposalRecord {
+            summary,
+            ..ProposalRecord::new(proposal_id, buyer, action, now)
+        };
+        self.track_proposal(record)
+            .map_err(|e| FileExchangeError::ProposalError(e.to_string()))?;
+
+        Ok(order)
+    }
+
+    #[cfg(feature = "marketplace")]
+    fn order(&self, order_id: OrderId) -> Result<Order, FileExchangeError> {
+        self.orders
+            .get(&order_id)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::OrderNotFound)
+    }
+
+    /// Whether a proposal may still be approved as far as its order goes:
+    /// proposals that pay for no order always may, payments only while
+    /// their order is pending and not past its deadline.
+    #[cfg(feature = "proposals")]
+    fn order_is_payable(&self, proposal_id: ProposalId) -> Result<bool, Error> {
+        let Some(order_id) = self.order_proposals.get(&proposal_id)? else {
+            return Ok(true);
+        };
+        Ok(self.orders.get(&order_id)?.map_or(true, |order| {
+            order.is_pending() && host::time_now() < order.expires_at
+        }))
+    }
+
+    /// Records a freshly sent proposal in the registry and opens its
+    /// discussion thread.
+    #[cfg(feature = "proposals")]
+    fn track_proposal(&mut self, record: ProposalRecord) -> Result<(), Error> {
+        let old = self.proposal_messages.insert(record.id, Vector::new())?;
+        if old.is_some() {
+            return Err(Error::msg("proposal already exists"));
+        }
+
+        self.proposals.insert(record.id, record.clone())?;
+
+        host::emit(Event::ProposalCreated { id: record.id });
+
+        self.update_proposal_status(record)
+    }
+
+    /// Marks a pending proposal as executed once it has as many approvals
+    /// as the proxy requires, and applies its effects on the app state.
+    ///
+    /// The app cannot ask the proxy for its threshold, so `num_approvals`
+    /// mirrors it: it starts at the value given to `init`, which must be
+    /// the proxy's, and follows every SetNumApprovals proposal executed
+    /// through the app. Clients read the proxy's own value and report it
+    /// with `sync_num_approvals`. While the two differ the proxy was
+    /// reconfigured directly, or the report is wrong, so nothing settles;
+    /// only a SetNumApprovals proposal with enough approvals for both can
+    /// execute and bring them back together.
+    #[cfg(feature = "proposals")]
+    fn update_proposal_status(&mut self, mut record: ProposalRecord) -> Result<(), Error> {
+        if record.status != ProposalStatus::Pending {
+            return Ok(());
+        }
+        let required = match self.proxy_num_approvals {
+            Some(proxy) if proxy != self.num_approvals => {
+                if !matches!(record.action, CreateProposalRequest::SetNumApprovals { .. }) {
+                    host::log(&format!(
+                        "The proxy requires {} approvals and the app {}, not settling until they agree",
+                        proxy, self.num_approvals
+                    ));
+                    return Ok(());
+                }
+                proxy.max(self.num_approvals)
+            }
+            _ => self.num_approvals,
+        };
+        if !record.has_enough_approvals(required) {
+            return Ok(());
+        }
+
+        record.status = ProposalStatus::Executed;
+        self.proposals.insert(record.id, record.clone())?;
+        host::emit(Event::ProposalExecuted { id: record.id });
 
-    pub fn create_new_proposal(
+        match record.action {
+            CreateProposalRequest::SetNumApprovals { num_approvals } => {
+                let held = !self.num_approvals_agree();
+                self.num_approvals = num_approvals;
+                self.proxy_num_approvals = Some(num_approvals);
+                if held {
+                    self.settle_held_proposals()?;
+                }
+            }
+            CreateProposalRequest::DeleteProposal { proposal_id } => {
+                self.withdraw_proposal(proposal_id, ProposalStatus::Deleted)?;
+            }
+            CreateProposalRequest::SetContextValue { ref key, ref value }
+                if key == ARCHIVE_MESSAGES_KEY =>
+            {
+                if let Some(archive) = parse_flag(value) {
+                    self.archive_messages = archive;
+                }
+            }
+            CreateProposalRequest::SetContextValue { ref key, ref value }
+                if key == MARKETPLACE_FEE_KEY =>
+            {
+                if let Some(fee_bps) = parse_fee_bps(value) {
+                    self.fee_bps = fee_bps;
+                }
+            }
+            CreateProposalRequest::SetContextValue { ref key, ref value }
+                if key == MARKETPLACE_FEE_ACCOUNT_KEY =>
+            {
+                let account = value.trim();
+                self.fee_account = (!account.is_empty()).then(|| account.to_owned());
+            }
+            _ => {}
+        }
+
+        self.settle_order(record.id)?;
+        self.close_thread(record.id)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "proposals")]
+    fn num_approvals_agree(&self) -> bool {
+        self.proxy_num_approvals
+            .map_or(true, |proxy| proxy == self.num_approvals)
+    }
+
+    /// Re-checks the pending proposals, oldest first, once the thresholds
+    /// agree again: approvals gathered while they differed settle now.
+    #[cfg(feature = "proposals")]
+    fn settle_held_proposals(&mut self) -> Result<(), Error> {
+        let mut pending: Vec<ProposalRecord> = self
+            .proposals
+            .entries()?
+            .map(|(_, record)| record)
+            .filter(|record| record.status == ProposalStatus::Pending)
+            .collect();
+        pending.sort_by_key(|record| record.created_at);
+
+        for record in pending {
+            // An earlier one may have deleted it.
+            let Some(record) = self.proposals.get(&record.id)? else {
+                continue;
+            };
+            if self.order_is_payable(record.id)? {
+                self.update_proposal_status(record)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes a pending proposal out of play. It will never execute, so an
+    /// order waiting on it is cancelled as well.
+    #[cfg(feature = "proposals")]
+    fn withdraw_proposal(
         &mut self,
-        request: CreateProposalRequest,
-    ) -> Result<ProposalId, Error> {
-        env::log("Starting create_new_proposal");
-        env::log(&format!("Request type: {}", request.action_type));
-
-        let proposal_id = match request.action_type.as_str() {
-            "ExternalFunctionCall" => {
-                env::log("Processing ExternalFunctionCall");
-                let receiver_id = request.params["receiver_id"]
-                    .as_str()
-                    .ok_or_else(|| Error::msg("receiver_id is required"))?;
-                let method_name = request.params["method_name"]
-                    .as_str()
-                    .ok_or_else(|| Error::msg("method_name is required"))?;
-                let args = request.params["args"]
-                    .as_str()
-                    .ok_or_else(|| Error::msg("args is required"))?;
-                let deposit = request.params["deposit"]
-                    .as_str()
-                    .ok_or_else(|| Error::msg("deposit is required"))?
-                    .parse::<u128>()?;
-
-                env::log(&format!(
-                    "Parsed values: receiver_id={}, method_name={}, args={}, deposit={}",
-                    receiver_id, method_name, args, deposit
-                ));
-
-                Self::external()
-                    .propose()
-                    .external_function_call(
-                        receiver_id.to_string(),
-                        method_name.to_string(),
-                        args.to_string(),
-                        deposit,
-                    )
-                    .send()
+        proposal_id: ProposalId,
+        status: ProposalStatus,
+    ) -> Result<(), Error> {
+        let Some(mut target) = self.proposals.get(&proposal_id)? else {
+            return Ok(());
+        };
+        if target.status.is_closed() {
+            return Ok(());
+        }
+
+        target.status = status;
+        self.proposals.insert(proposal_id, target)?;
+
+        if let Some(order_id) = self.order_proposals.get(&proposal_id)? {
+            if let Some(mut order) = self.orders.get(&order_id)? {
+                if order.is_pending() {
+                    order.status = OrderStatus::Cancelled;
+                    self.orders.insert(order_id, order.clone())?;
+                    self.return_withdrawal(&order)?;
+                    host::emit(Event::OrderCancelled { id: order_id });
+                }
             }
-            "Transfer" => {
-                env::log("Processing Transfer");
-                let receiver_id = request.params["receiver_id"]
-                    .as_str()
-                    .ok_or_else(|| Error::msg("receiver_id is required"))?;
-                let amount = request.params["amount"]
-                    .as_str()
-                    .ok_or_else(|| Error::msg("amount is required"))?
-                    .parse::<u128>()?;
-
-                Self::external()
-                    .propose()
-                    .transfer(AccountId(receiver_id.to_string()), amount)
-                    .send()
+        }
+
+        self.close_thread(proposal_id)?;
+        Ok(())
+    }
+
+    /// Takes the proposal paying for `order` out of play and asks the proxy
+    /// to delete it. The proxy deletes a proposal straight away when its
+    /// author, the buyer, asks; a DeleteProposal from anyone else is
+    /// tracked here and needs approvals like any other proposal.
+    #[cfg(feature = "marketplace")]
+    fn withdraw_payment(&mut self, order: &Order, status: ProposalStatus) -> Result<(), Error> {
+        let request = CreateProposalRequest::DeleteProposal {
+            proposal_id: order.proposal_id,
+        };
+        let delete_id = host::propose(request.clone());
+        self.withdraw_proposal(order.proposal_id, status)?;
+
+        let author = caller();
+        if author != order.buyer {
+            let record = ProposalRecord::new(delete_id, author, request, host::time_now());
+            self.track_proposal(record)?;
+        }
+        Ok(())
+    }
+
+    /// The thread of a proposal that still takes messages.
+    #[cfg(feature = "proposals")]
+    fn open_thread(&self, proposal_id: ProposalId) -> Result<Vector<Message>, MessageError> {
+        let record = self
+            .proposals
+            .get(&proposal_id)
+            .map_err(|_| MessageError::ProposalNotFound)?;
+        if record.is_some_and(|record| record.status.is_closed()) {
+            return Err(MessageError::ThreadClosed);
+        }
+
+        self.proposal_messages
+            .get(&proposal_id)
+            .map_err(|_| MessageError::ProposalNotFound)?
+            .ok_or(MessageError::ProposalNotFound)
+    }
+
+    #[cfg(feature = "proposals")]
+    fn change_message(
+        &mut self,
+        proposal_id: ProposalId,
+        message_id: MessageId,
+        change: impl FnOnce(&mut Message),
+    ) -> Result<Message, Error> {
+        let mut thread = self.open_thread(proposal_id)?;
+        let mut message = thread
+            .get(message_id as usize)?
+            .ok_or(MessageError::MessageNotFound)?;
+        message.check_author(&caller())?;
+
+        change(&mut message);
+        thread.update(message_id as usize, message.clone())?;
+        self.proposal_messages.insert(proposal_id, thread)?;
+        Ok(message)
+    }
+
+    /// Finished proposals take no more messages: their thread is deleted,
+    /// or moved to `archived_messages` while archiving is on. Returns
+    /// whether there was a thread to close.
+    #[cfg(feature = "proposals")]
+    fn close_thread(&mut self, proposal_id: ProposalId) -> Result<bool, Error> {
+        let Some(mut thread) = self.proposal_messages.remove(&proposal_id)? else {
+            return Ok(false);
+        };
+
+        if self.archive_messages {
+            self.archived_messages.insert(proposal_id, thread)?;
+        } else {
+            thread.clear()?;
+        }
+        Ok(true)
+    }
+
+    /// Marks the order paid by an executed Transfer proposal and grants the
+    /// buyer access.
+    #[cfg(feature = "proposals")]
+    fn settle_order(&mut self, proposal_id: ProposalId) -> Result<(), Error> {
+        let Some(order_id) = self.order_proposals.get(&proposal_id)? else {
+            return Ok(());
+        };
+        let Some(mut order) = self.orders.get(&order_id)? else {
+            return Ok(());
+        };
+
+        if !order.is_pending() {
+            host::log(&format!(
+                "Order {} is {:?}, not granting access",
+                order.id, order.status
+            ));
+            return Ok(());
+        }
+
+        order.status = OrderStatus::Paid;
+        self.orders.insert(order.id, order.clone())?;
+        host::emit(Event::OrderPaid { id: order.id });
+
+        match order.item {
+            OrderItem::Listing(name) => {
+                self.put_grant(
+                    name,
+                    AccessGrant {
+                        reader: order.buyer,
+                        granted_by: order.seller,
+                        granted_at: host::time_now(),
+                        expires_at: None,
+                    },
+                )?;
+            }
+            OrderItem::Subscription(plan_id) => {
+                self.add_period(plan_id, order.buyer)?;
             }
-            "SetContextValue" => {
-                env::log("Processing SetContextValue");
-                let key = request.params["key"]
-                    .as_str()
-                    .ok_or_else(|| Error::msg("key is required"))?
-                    .as_bytes()
-                    .to_vec()
-                    .into_boxed_slice();
-                let value = request.params["value"]
-                    .as_str()
-                    .ok_or_else(|| Error::msg("value is required"))?
-                    .as_bytes()
-                    .to_vec()
-                    .into_boxed_slice();
-
-                Self::external()
-                    .propose()
-                    .set_context_value(key, value)
-                    .send()
+            OrderItem::CreditDeposit => {
+                let kind = EntryKind::Deposit { order_id: order.id };
+                self.post_entry(&order.buyer, kind, order.amount)?;
+                host::emit(Event::CreditDeposited {
+                    account: order.buyer,
+                    amount: order.amount,
+                });
             }
-            "SetNumApprovals" => Self::external()
-                .propose()
-                .set_num_approvals(
-                    request.params["num_approvals"]
-                        .as_u64()
-                        .ok_or(Error::msg("num_approvals is required"))? as u32,
-                )
-                .send(),
-            "SetActiveProposalsLimit" => Self::external()
-                .propose()
-                .set_active_proposals_limit(
-                    request.params["active_proposals_limit"]
-                        .as_u64()
-                        .ok_or(Error::msg("active_proposals_limit is required"))?
-                        as u32,
-                )
-                .send(),
-            _ => return Err(Error::msg("Invalid action type")),
+            OrderItem::CreditWithdrawal => {
+                host::emit(Event::CreditWithdrawn {
+                    account: order.buyer,
+                    amount: order.amount,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Books back the credit a withdrawal took out once its order ends
+    /// without being paid.
+    #[cfg(feature = "proposals")]
+    fn return_withdrawal(&mut self, order: &Order) -> Result<(), FileExchangeError> {
+        if order.item == OrderItem::CreditWithdrawal {
+            let kind = EntryKind::WithdrawalReturned { order_id: order.id };
+            self.post_entry(&order.buyer, kind, order.amount)?;
+        }
+        Ok(())
+    }
+
+    /// Only buyers with a paid order or a grant, current or expired, may
+    /// review a listing.
+    #[cfg(feature = "marketplace")]
+    fn is_verified_buyer(&self, name: &str, reader: &str) -> Result<bool, FileExchangeError> {
+        let granted = match self.grants.get(name).map_err(FileExchangeError::storage)? {
+            Some(grants) => grants
+                .get(reader)
+                .map_err(FileExchangeError::storage)?
+                .is_some(),
+            None => false,
         };
+        if granted {
+            return Ok(true);
+        }
 
-        // Initialize proposal messages storage
-        let old = self.proposal_messages.insert(proposal_id, Vector::new())?;
-        if old.is_some() {
-            return Err(Error::msg("proposal already exists"));
+        let item = OrderItem::Listing(name.to_owned());
+        Ok(self
+            .orders
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .any(|(_, order)| {
+                order.buyer == reader && order.item == item && order.status == OrderStatus::Paid
+            }))
+    }
+
+    #[cfg(feature = "marketplace")]
+    fn rating_tally(&self, name: &str) -> Result<RatingTally, FileExchangeError> {
+        Ok(self
+            .ratings
+            .get(name)
+            .map_err(FileExchangeError::storage)?
+            .unwrap_or_default())
+    }
+
+    #[cfg(feature = "files")]
+    fn query_price(&self, name: &str) -> Result<Option<U128>, FileExchangeError> {
+        self.query_prices
+            .get(name)
+            .map_err(FileExchangeError::storage)
+    }
+
+    #[cfg(any(feature = "files", feature = "proposals"))]
+    fn balance(&self, account: &str) -> Result<U128, FileExchangeError> {
+        Ok(self
+            .credit_balances
+            .get(account)
+            .map_err(FileExchangeError::storage)?
+            .unwrap_or_default())
+    }
+
+    /// Moves `price` of the buyer's credit to the dataset's owner.
+    #[cfg(feature = "files")]
+    fn charge_query(
+        &mut self,
+        dataset: &EmbeddingDataset,
+        buyer: String,
+        price: U128,
+    ) -> Result<(), FileExchangeError> {
+        let balance = self.balance(&buyer)?;
+        if balance < price {
+            return Err(FileExchangeError::InsufficientCredit { balance, price });
+        }
+
+        let charge = EntryKind::QueryCharge {
+            dataset: dataset.name.clone(),
+            seller: dataset.owner.clone(),
+        };
+        self.post_entry(&buyer, charge, price)?;
+        let income = EntryKind::QueryIncome {
+            dataset: dataset.name.clone(),
+            buyer: buyer.clone(),
+        };
+        self.post_entry(&dataset.owner, income, price)?;
+
+        host::emit(Event::QueryCharged {
+            dataset: dataset.name.clone(),
+            buyer,
+            amount: price,
+        });
+        Ok(())
+    }
+
+    /// Books `amount` to or from `account` and adds it to the statement.
+    #[cfg(any(feature = "files", feature = "proposals"))]
+    fn post_entry(
+        &mut self,
+        account: &str,
+        kind: EntryKind,
+        amount: U128,
+    ) -> Result<LedgerEntry, FileExchangeError> {
+        let balance = self.balance(account)?;
+        let balance = if kind.is_credit() {
+            balance
+                .0
+                .checked_add(amount.0)
+                .ok_or(FileExchangeError::CreditOverflow)?
+        } else {
+            balance
+                .0
+                .checked_sub(amount.0)
+                .ok_or(FileExchangeError::InsufficientCredit {
+                    balance,
+                    price: amount,
+                })?
+        };
+        let balance = U128(balance);
+
+        let mut entries = self
+            .ledger
+            .get(account)
+            .map_err(FileExchangeError::storage)?
+            .unwrap_or_default();
+        let entry = LedgerEntry {
+            id: entries.len().map_err(FileExchangeError::storage)? as EntryId,
+            kind,
+            amount,
+            balance,
+            created_at: host::time_now(),
+        };
+        entries
+            .push(entry.clone())
+            .map_err(FileExchangeError::storage)?;
+        self.ledger
+            .insert(account.to_owned(), entries)
+            .map_err(FileExchangeError::storage)?;
+        self.credit_balances
+            .insert(account.to_owned(), balance)
+            .map_err(FileExchangeError::storage)?;
+        Ok(entry)
+    }
+
+    #[cfg(any(feature = "files", feature = "proposals"))]
+    fn plan(&self, plan_id: PlanId) -> Result<Plan, FileExchangeError> {
+        self.plans
+            .get(&plan_id)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::PlanNotFound)
+    }
+
+    #[cfg(feature = "marketplace")]
+    fn subscription(
+        &self,
+        subscriber: &str,
+        plan_id: PlanId,
+    ) -> Result<Option<Subscription>, FileExchangeError> {
+        match self
+            .subscriptions
+            .get(subscriber)
+            .map_err(FileExchangeError::storage)?
+        {
+            Some(subscriptions) => subscriptions
+                .get(&plan_id)
+                .map_err(FileExchangeError::storage),
+            None => Ok(None),
+        }
+    }
+
+    /// Opens the order paying for one period of a plan.
+    #[cfg(feature = "marketplace")]
+    fn order_period(&mut self, plan_id: PlanId, buyer: String) -> Result<Order, FileExchangeError> {
+        let plan = self.plan(plan_id)?;
+
+        if plan.retired {
+            return Err(FileExchangeError::PlanRetired);
         }
+        if plan.seller == buyer {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+
+        let order = self.open_payment(
+            OrderItem::Subscription(plan_id),
+            plan.seller,
+            plan.price,
+            plan.license,
+        )?;
 
-        app::emit!(Event::ProposalCreated { id: proposal_id });
-        Ok(proposal_id)
+        host::emit(Event::SubscriptionOrderOpened {
+            id: order.id,
+            plan_id,
+            buyer,
+        });
+        Ok(order)
     }
 
-    pub fn approve_proposal(&self, proposal_id: ProposalId) -> Result<(), Error> {
-        Self::external().approve(proposal_id);
-        app::emit!(Event::ApprovedProposal { id: proposal_id });
+    /// Starts or renews a subscription once a period has been paid for.
+    /// The period is the plan's when the payment executes.
+    #[cfg(feature = "proposals")]
+    fn add_period(&mut self, plan_id: PlanId, subscriber: String) -> Result<(), FileExchangeError> {
+        let plan = self.plan(plan_id)?;
+        let now = host::time_now();
+
+        let mut subscriptions = self
+            .subscriptions
+            .get(&subscriber)
+            .map_err(FileExchangeError::storage)?
+            .unwrap_or_default();
+        let existing = subscriptions
+            .get(&plan_id)
+            .map_err(FileExchangeError::storage)?;
+
+        let (subscription, event) = match existing {
+            Some(mut subscription) => {
+                subscription.renew(plan.period, now);
+                let event = Event::SubscriptionRenewed {
+                    plan_id,
+                    subscriber: subscriber.clone(),
+                    expires_at: subscription.expires_at,
+                };
+                (subscription, event)
+            }
+            None => {
+                let subscription =
+                    Subscription::start(plan_id, subscriber.clone(), plan.period, now);
+                let event = Event::SubscriptionStarted {
+                    plan_id,
+                    subscriber: subscriber.clone(),
+                    expires_at: subscription.expires_at,
+                };
+                (subscription, event)
+            }
+        };
+
+        subscriptions
+            .insert(plan_id, subscription)
+            .map_err(FileExchangeError::storage)?;
+        self.subscriptions
+            .insert(subscriber, subscriptions)
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(event);
         Ok(())
     }
 
-    pub fn get_proposal_messages(&self, proposal_id: ProposalId) -> Result<Vec<Message>, Error> {
-        let Some(msgs) = self.proposal_messages.get(&proposal_id)? else {
-            return Ok(vec![]);
+    /// Gives a new file or dataset its place in the catalog.
+    #[cfg(feature = "files")]
+    fn add_listing(&mut self, name: &str) -> Result<(), FileExchangeError> {
+        let meta = ListingMeta {
+            seq: self.next_listing_seq,
+            created_at: host::time_now(),
+            ..ListingMeta::default()
         };
-        
-        // Create a new vector and collect the entries
-        let entries: Vec<Message> = msgs.entries()?.collect();
-        Ok(entries)
+        self.next_listing_seq += 1;
+        self.listings
+            .insert(name.to_owned(), meta)
+            .map_err(FileExchangeError::storage)?;
+        Ok(())
     }
 
-    pub fn send_proposal_messages(
+    #[cfg(feature = "files")]
+    fn drop_listing(&mut self, name: &str) -> Result<(), FileExchangeError> {
+        self.listings
+            .remove(name)
+            .map_err(FileExchangeError::storage)?;
+        self.ratings
+            .remove(name)
+            .map_err(FileExchangeError::storage)?;
+        if let Some(mut reviews) = self
+            .reviews
+            .remove(name)
+            .map_err(FileExchangeError::storage)?
+        {
+            reviews.clear().map_err(FileExchangeError::storage)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "marketplace")]
+    fn listing_meta(&self, name: &str) -> Result<ListingMeta, FileExchangeError> {
+        Ok(self
+            .listings
+            .get(name)
+            .map_err(FileExchangeError::storage)?
+            .unwrap_or_default())
+    }
+
+    #[cfg(feature = "marketplace")]
+    fn update_listing(
         &mut self,
-        proposal_id: ProposalId,
-        message: Message,
-    ) -> Result<(), Error> {
-        let mut messages = self.proposal_messages.get(&proposal_id)?.unwrap_or_default();
-        messages.push(message)?;
-        self.proposal_messages.insert(proposal_id, messages)?;
+        name: String,
+        change: impl FnOnce(&mut ListingMeta),
+    ) -> Result<ListingSummary, FileExchangeError> {
+        if self.listing_owner(&name)? != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+
+        let mut meta = self.listing_meta(&name)?;
+        change(&mut meta);
+        self.listings
+            .insert(name.clone(), meta)
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::ListingUpdated { name: name.clone() });
+        self.listing_summary(&name)
+    }
+
+    #[cfg(feature = "marketplace")]
+    fn listing_summary(&self, name: &str) -> Result<ListingSummary, FileExchangeError> {
+        let meta = self.listing_meta(name)?;
+        let summary = match self.files.get(name).map_err(FileExchangeError::storage)? {
+            Some(file) => file_summary(&file, meta),
+            None => {
+                let dataset = self.dataset(name)?;
+                let query_price = self.query_price(name)?;
+                dataset_summary(&dataset, meta, query_price)
+            }
+        };
+        self.rated(summary)
+    }
+
+    #[cfg(feature = "marketplace")]
+    fn rated(&self, mut summary: ListingSummary) -> Result<ListingSummary, FileExchangeError> {
+        let tally = self.rating_tally(&summary.name)?;
+        summary.rating = tally.average();
+        summary.review_count = tally.count;
+        Ok(summary)
+    }
+
+    #[cfg(feature = "marketplace")]
+    fn listing_summaries(&self) -> Result<Vec<ListingSummary>, FileExchangeError> {
+        let files: Vec<FileEntry> = self
+            .files
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .map(|(_, file)| file)
+            .collect();
+        let datasets: Vec<EmbeddingDataset> = self
+            .datasets
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .map(|(_, dataset)| dataset)
+            .collect();
+
+        let mut summaries = Vec::with_capacity(files.len() + datasets.len());
+        for file in files {
+            let meta = self.listing_meta(&file.name)?;
+            summaries.push(file_summary(&file, meta));
+        }
+        for dataset in datasets {
+            let meta = self.listing_meta(&dataset.name)?;
+            let query_price = self.query_price(&dataset.name)?;
+            summaries.push(dataset_summary(&dataset, meta, query_price));
+        }
+        summaries
+            .into_iter()
+            .map(|summary| self.rated(summary))
+            .collect()
+    }
+
+    #[cfg(feature = "files")]
+    fn file(&self, name: &str) -> Result<FileEntry, FileExchangeError> {
+        self.files
+            .get(name)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::FileNotFound)
+    }
+
+    #[cfg(feature = "files")]
+    fn add_file(
+        &mut self,
+        name: String,
+        size: u64,
+        sha256: String,
+        encrypted: bool,
+    ) -> Result<String, FileExchangeError> {
+        let owner = caller();
+        let file = FileEntry {
+            name: name.clone(),
+            owner: owner.clone(),
+            size,
+            sha256: sha256.clone(),
+            encrypted,
+        };
+        self.files
+            .insert(name.clone(), file)
+            .map_err(FileExchangeError::storage)?;
+        self.add_listing(&name)?;
+
+        host::emit(Event::FileUploaded {
+            name,
+            owner,
+            sha256: sha256.clone(),
+        });
+        Ok(sha256)
+    }
+
+    /// Adds a reference to the blob holding `content`, creating it if this
+    /// content is new, and returns its digest.
+    fn store_content(&mut self, content: &str) -> Result<String, FileExchangeError> {
+        let sha256 = sha256_hex(content.as_bytes());
+
+        let mut blob = match self.blob(&sha256)? {
+            Some(blob) => blob,
+            None => {
+                let mut chunks = Vector::new();
+                for piece in split_content(content) {
+                    chunks.push(piece).map_err(FileExchangeError::storage)?;
+                }
+                Blob {
+                    size: content.len() as u64,
+                    chunks,
+                    refs: 0,
+                }
+            }
+        };
+        blob.refs += 1;
+        self.blobs
+            .insert(sha256.clone(), blob)
+            .map_err(FileExchangeError::storage)?;
+
+        Ok(sha256)
+    }
+
+    fn blob(&self, sha256: &str) -> Result<Option<Blob>, FileExchangeError> {
+        self.blobs.get(sha256).map_err(FileExchangeError::storage)
+    }
+
+    #[cfg(feature = "files")]
+    fn content(&self, sha256: &str) -> Result<Blob, FileExchangeError> {
+        self.blob(sha256)?
+            .ok_or_else(|| FileExchangeError::StorageError(format!("missing content {}", sha256)))
+    }
+
+    /// Drops one reference to a blob, deleting it when no file uses it.
+    #[cfg(feature = "files")]
+    fn release_blob(&mut self, sha256: &str) -> Result<(), FileExchangeError> {
+        let mut blob = self.content(sha256)?;
+
+        blob.refs = blob.refs.saturating_sub(1);
+        if blob.refs > 0 {
+            self.blobs
+                .insert(sha256.to_owned(), blob)
+                .map_err(FileExchangeError::storage)?;
+            return Ok(());
+        }
+
+        blob.chunks.clear().map_err(FileExchangeError::storage)?;
+        self.blobs
+            .remove(sha256)
+            .map_err(FileExchangeError::storage)?;
+        Ok(())
+    }
+
+    /// Only the identity that began an upload may touch it, and only
+    /// until it expires.
+    #[cfg(feature = "files")]
+    fn upload_session(&self, upload_id: UploadId) -> Result<UploadSession, FileExchangeError> {
+        let session = self
+            .uploads
+            .get(&upload_id)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::UploadNotFound)?;
+
+        if session.owner != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+        if session.is_expired_at(host::time_now()) {
+            return Err(FileExchangeError::UploadNotFound);
+        }
+        Ok(session)
+    }
+
+    #[cfg(feature = "files")]
+    fn drop_upload(&mut self, mut session: UploadSession) -> Result<(), FileExchangeError> {
+        session.chunks.clear().map_err(FileExchangeError::storage)?;
+        session
+            .chunk_hashes
+            .clear()
+            .map_err(FileExchangeError::storage)?;
+        self.uploads
+            .remove(&session.id)
+            .map_err(FileExchangeError::storage)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "files")]
+    fn dataset(&self, name: &str) -> Result<EmbeddingDataset, FileExchangeError> {
+        self.datasets
+            .get(name)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::FileNotFound)
+    }
+
+    #[cfg(feature = "files")]
+    fn push_chunks(
+        dataset: &mut EmbeddingDataset,
+        chunks: Vec<EmbeddingChunk>,
+    ) -> Result<(), FileExchangeError> {
+        let mut batch = HashSet::new();
+        for chunk in &chunks {
+            dataset
+                .check_chunk(chunk)
+                .map_err(FileExchangeError::InvalidDataset)?;
+
+            let stored = dataset
+                .chunk_ids
+                .contains(&chunk.chunk_id)
+                .map_err(FileExchangeError::storage)?;
+            if stored || !batch.insert(chunk.chunk_id.as_str()) {
+                return Err(FileExchangeError::InvalidDataset(format!(
+                    "duplicate chunk_id {}",
+                    chunk.chunk_id
+                )));
+            }
+        }
+
+        for chunk in chunks {
+            dataset
+                .chunk_ids
+                .insert(chunk.chunk_id.clone(), dataset.chunk_count)
+                .map_err(FileExchangeError::storage)?;
+            dataset
+                .chunks
+                .push(chunk)
+                .map_err(FileExchangeError::storage)?;
+            dataset.chunk_count += 1;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,692 @@
+//! Methods of the `marketplace` feature: the catalog, purchases,
+//! subscriptions, query credit and reviews. Payments go through proposals,
+//! and what is sold are the `files` listings, so it needs both.
+
+use super::*;
+
+#[app::logic]
+impl FileExchangeState {
+    // ===== Catalog Functions =====
+
+    /// Pages through files and datasets together. Pass the previous page's
+    /// `next_cursor` to continue; `offset` skips listings after the cursor
+    /// (or from the start) and is only stable while the catalog does not
+    /// change.
+    pub fn list_listings(
+        &self,
+        filter: Option<ListingFilter>,
+        sort: Option<ListingSort>,
+        cursor: Option<String>,
+        offset: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<ListingPage, FileExchangeError> {
+        let filter = filter.unwrap_or_default();
+        let sort = sort.unwrap_or_default();
+        let after = cursor
+            .map(|cursor| decode_cursor(&cursor).ok_or(FileExchangeError::InvalidCursor))
+            .transpose()?;
+
+        let mut listings: Vec<_> = self
+            .listing_summaries()?
+            .into_iter()
+            .filter(|listing| filter.matches(listing))
+            .map(|listing| (sort.key(&listing), listing))
+            .filter(|(key, _)| after.as_ref().map_or(true, |after| key > after))
+            .collect();
+        listings.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let size = ListingPage::size(limit);
+        let mut page: Vec<_> = listings
+            .into_iter()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(size + 1)
+            .collect();
+
+        let next_cursor = if page.len() > size {
+            page.truncate(size);
+            page.last().map(|(key, _)| encode_cursor(key))
+        } else {
+            None
+        };
+        Ok(ListingPage {
+            listings: page.into_iter().map(|(_, listing)| listing).collect(),
+            next_cursor,
+        })
+    }
+
+    pub fn get_listing(&self, name: String) -> Result<ListingSummary, FileExchangeError> {
+        self.listing_summary(&name)
+    }
+
+    /// Replaces the listing's tags. Tags are matched case-insensitively.
+    pub fn set_listing_tags(
+        &mut self,
+        name: String,
+        tags: Vec<String>,
+    ) -> Result<ListingSummary, FileExchangeError> {
+        self.check_migrated()?;
+        let tags = normalize_tags(tags).map_err(FileExchangeError::InvalidListing)?;
+        self.update_listing(name, |meta| meta.tags = tags)
+    }
+
+    /// The price buyers pay through `open_order`; `None` takes the
+    /// listing off sale. Open orders keep the price they were opened at.
+    pub fn set_listing_price(
+        &mut self,
+        name: String,
+        price: Option<Price>,
+    ) -> Result<ListingSummary, FileExchangeError> {
+        self.check_migrated()?;
+        if let Some(price) = &price {
+            price.check().map_err(FileExchangeError::InvalidListing)?;
+        }
+        self.update_listing(name, |meta| meta.price = price)
+    }
+
+    pub fn set_listing_license(
+        &mut self,
+        name: String,
+        license: License,
+    ) -> Result<ListingSummary, FileExchangeError> {
+        self.check_migrated()?;
+        self.update_listing(name, |meta| meta.license = license)
+    }
+
+    pub fn get_marketplace_fee(&self) -> MarketplaceFee {
+        MarketplaceFee {
+            fee_bps: self.fee_bps,
+            fee_account: self.fee_account.clone(),
+        }
+    }
+
+    // ===== Purchase Functions =====
+
+    /// Sets the ICP account that Transfer proposals for the caller's
+    /// listings pay into.
+    pub fn set_payout_account(&mut self, account_id: String) -> Result<(), FileExchangeError> {
+        self.check_migrated()?;
+        if account_id.is_empty() {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+
+        self.payout_accounts
+            .insert(caller(), account_id)
+            .map_err(FileExchangeError::storage)?;
+        Ok(())
+    }
+
+    /// Opens an order for a listing at its current price and creates the
+    /// proposal that pays for it: one Transfer to the seller and, while a
+    /// marketplace fee is configured, one to the fee account. Once the
+    /// proposal has gathered enough approvals the order is marked paid and
+    /// the buyer gets access.
+    pub fn open_order(&mut self, listing: String) -> Result<Order, FileExchangeError> {
+        self.check_migrated()?;
+        let buyer = caller();
+        let seller = self.listing_owner(&listing)?;
+        let meta = self.listing_meta(&listing)?;
+        let price = meta.price.ok_or(FileExchangeError::ListingNotForSale)?;
+
+        if self.check_access(&listing, &buyer).is_ok() {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+
+        let order = self.open_payment(
+            OrderItem::Listing(listing.clone()),
+            seller,
+            price,
+            meta.license,
+        )?;
+
+        host::emit(Event::OrderOpened {
+            id: order.id,
+            listing,
+            buyer,
+        });
+        Ok(order)
+    }
+
+    /// Cancels a pending order and withdraws the proposal paying for it.
+    /// Only the buyer, who authored that proposal, may cancel; a seller
+    /// who no longer wants to sell takes the listing off sale instead.
+    pub fn cancel_order(&mut self, order_id: OrderId) -> Result<Order, FileExchangeError> {
+        self.check_migrated()?;
+        let order = self.order(order_id)?;
+
+        if order.buyer != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+        if !order.is_pending() {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+
+        self.withdraw_payment(&order, ProposalStatus::Cancelled)
+            .map_err(|e| FileExchangeError::ProposalError(e.to_string()))?;
+        host::emit(Event::ProposalCancelled {
+            id: order.proposal_id,
+        });
+        self.order(order_id)
+    }
+
+    /// Moves every pending order past its deadline to expired and
+    /// withdraws the proposals paying for them. Anyone may call this; it
+    /// returns the ids of the orders it expired.
+    pub fn expire_orders(&mut self) -> Result<Vec<OrderId>, FileExchangeError> {
+        self.check_migrated()?;
+        let now = host::time_now();
+        let expired: Vec<Order> = self
+            .orders
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .map(|(_, order)| order)
+            .filter(|order| order.is_expired_at(now))
+            .collect();
+
+        let mut ids = Vec::with_capacity(expired.len());
+        for mut order in expired {
+            order.status = OrderStatus::Expired;
+            self.orders
+                .insert(order.id, order.clone())
+                .map_err(FileExchangeError::storage)?;
+            self.return_withdrawal(&order)?;
+            self.withdraw_payment(&order, ProposalStatus::Cancelled)
+                .map_err(|e| FileExchangeError::ProposalError(e.to_string()))?;
+
+            host::emit(Event::OrderExpired { id: order.id });
+            ids.push(order.id);
+        }
+        Ok(ids)
+    }
+
+    pub fn get_order(&self, order_id: OrderId) -> Result<Order, FileExchangeError> {
+        let order = self.order(order_id)?;
+
+        if !order.involves(&caller()) {
+            return Err(FileExchangeError::Unauthorized);
+        }
+        Ok(order)
+    }
+
+    /// Orders where the caller is either the buyer or the seller.
+    pub fn list_orders(&self) -> Result<Vec<Order>, FileExchangeError> {
+        let me = caller();
+
+        self.orders
+            .entries()
+            .map_err(FileExchangeError::storage)
+            .map(|entries| {
+                entries
+                    .map(|(_, order)| order)
+                    .filter(|order| order.involves(&me))
+                    .collect()
+            })
+    }
+
+    // ===== Subscription Functions =====
+
+    /// Offers access to some of the caller's datasets for `period`
+    /// nanoseconds at a time. `license` defaults to the personal license.
+    pub fn create_plan(
+        &mut self,
+        name: String,
+        period: u64,
+        price: Price,
+        datasets: Vec<String>,
+        license: Option<License>,
+    ) -> Result<Plan, FileExchangeError> {
+        self.check_migrated()?;
+        let seller = caller();
+
+        check_plan(&name, period, &price).map_err(FileExchangeError::InvalidPlan)?;
+        let mut covered: Vec<String> = Vec::with_capacity(datasets.len());
+        for dataset in datasets {
+            if self.dataset(&dataset)?.owner != seller {
+                return Err(FileExchangeError::Unauthorized);
+            }
+            if !covered.contains(&dataset) {
+                covered.push(dataset);
+            }
+        }
+        if covered.is_empty() {
+            return Err(FileExchangeError::InvalidPlan(
+                "a plan must cover at least one dataset".to_owned(),
+            ));
+        }
+
+        let plan = Plan {
+            id: self.next_plan_id,
+            seller: seller.clone(),
+            name: name.trim().to_owned(),
+            period,
+            price,
+            datasets: covered,
+            license: license.unwrap_or_default(),
+            retired: false,
+            created_at: host::time_now(),
+        };
+        self.next_plan_id += 1;
+        self.plans
+            .insert(plan.id, plan.clone())
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::PlanCreated {
+            id: plan.id,
+            seller,
+        });
+        Ok(plan)
+    }
+
+    /// Stops new subscriptions and renewals. Subscribers keep access until
+    /// the window they paid for runs out.
+    pub fn retire_plan(&mut self, plan_id: PlanId) -> Result<Plan, FileExchangeError> {
+        self.check_migrated()?;
+        let mut plan = self.plan(plan_id)?;
+
+        if plan.seller != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+        if plan.retired {
+            return Err(FileExchangeError::PlanRetired);
+        }
+
+        plan.retired = true;
+        self.plans
+            .insert(plan_id, plan.clone())
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::PlanRetired { id: plan_id });
+        Ok(plan)
+    }
+
+    pub fn get_plan(&self, plan_id: PlanId) -> Result<Plan, FileExchangeError> {
+        self.plan(plan_id)
+    }
+
+    /// Plans that still take subscribers, oldest first, optionally only
+    /// those of one seller.
+    pub fn list_plans(&self, seller: Option<String>) -> Result<Vec<Plan>, FileExchangeError> {
+        let mut plans: Vec<Plan> = self
+            .plans
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .map(|(_, plan)| plan)
+            .filter(|plan| !plan.retired)
+            .filter(|plan| {
+                seller
+                    .as_ref()
+                    .map_or(true, |seller| plan.seller == *seller)
+            })
+            .collect();
+        plans.sort_by_key(|plan| plan.id);
+        Ok(plans)
+    }
+
+    /// Opens an order for the first period of a plan. The subscription
+    /// starts once its Transfer proposal executes.
+    pub fn subscribe(&mut self, plan_id: PlanId) -> Result<Order, FileExchangeError> {
+        self.check_migrated()?;
+        let buyer = caller();
+
+        if let Some(subscription) = self.subscription(&buyer, plan_id)? {
+            if subscription.is_active_at(host::time_now()) {
+                return Err(FileExchangeError::AlreadySubscribed);
+            }
+        }
+        self.order_period(plan_id, buyer)
+    }
+
+    /// Opens an order for one more period of a plan the caller has
+    /// subscribed to. Paying before the window runs out extends it without
+    /// a gap; paying after starts a new window.
+    pub fn renew_subscription(&mut self, plan_id: PlanId) -> Result<Order, FileExchangeError> {
+        self.check_migrated()?;
+        let buyer = caller();
+
+        if self.subscription(&buyer, plan_id)?.is_none() {
+            return Err(FileExchangeError::SubscriptionNotFound);
+        }
+        self.order_period(plan_id, buyer)
+    }
+
+    /// The caller's subscriptions, lapsed ones included.
+    pub fn list_subscriptions(&self) -> Result<Vec<Subscription>, FileExchangeError> {
+        let Some(subscriptions) = self
+            .subscriptions
+            .get(&caller())
+            .map_err(FileExchangeError::storage)?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut subscriptions: Vec<Subscription> = subscriptions
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .map(|(_, subscription)| subscription)
+            .collect();
+        subscriptions.sort_by_key(|subscription| subscription.plan_id);
+        Ok(subscriptions)
+    }
+
+    /// Reports every subscription whose window has run out since the last
+    /// call. Access already ends with the window; this only marks the lapse
+    /// and emits `SubscriptionExpired` for it. Anyone may call this.
+    pub fn expire_subscriptions(&mut self) -> Result<Vec<Subscription>, FileExchangeError> {
+        self.check_migrated()?;
+        let now = host::time_now();
+        let subscribers: Vec<(String, UnorderedMap<PlanId, Subscription>)> = self
+            .subscriptions
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .collect();
+
+        let mut lapsed = Vec::new();
+        for (subscriber, mut subscriptions) in subscribers {
+            let expired: Vec<Subscription> = subscriptions
+                .entries()
+                .map_err(FileExchangeError::storage)?
+                .map(|(_, subscription)| subscription)
+                .filter(|subscription| !subscription.lapsed && !subscription.is_active_at(now))
+                .collect();
+            if expired.is_empty() {
+                continue;
+            }
+
+            for mut subscription in expired {
+                subscription.lapsed = true;
+                subscriptions
+                    .insert(subscription.plan_id, subscription.clone())
+                    .map_err(FileExchangeError::storage)?;
+
+                host::emit(Event::SubscriptionExpired {
+                    plan_id: subscription.plan_id,
+                    subscriber: subscriber.clone(),
+                });
+                lapsed.push(subscription);
+            }
+            self.subscriptions
+                .insert(subscriber, subscriptions)
+                .map_err(FileExchangeError::storage)?;
+        }
+        Ok(lapsed)
+    }
+
+    // ===== Credit Functions =====
+
+    /// Lets buyers without access search one of the caller's datasets for
+    /// `price` credit per query; `None` stops selling queries.
+    pub fn set_query_price(
+        &mut self,
+        name: String,
+        price: Option<U128>,
+    ) -> Result<ListingSummary, FileExchangeError> {
+        self.check_migrated()?;
+        if self.dataset(&name)?.owner != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+
+        match price {
+            Some(U128(0)) => {
+                return Err(FileExchangeError::InvalidListing(
+                    "query price must be greater than zero".to_owned(),
+                ))
+            }
+            Some(price) => self.query_prices.insert(name.clone(), price),
+            None => self.query_prices.remove(&name),
+        }
+        .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::ListingUpdated { name: name.clone() });
+        self.listing_summary(&name)
+    }
+
+    /// Opens an order buying `amount` of credit in the native token. The
+    /// money stays in the proxy's treasury, which pays the withdrawals, so
+    /// the proposal moves nothing: it records the deposit as a context
+    /// value, and the credit is booked once it executes.
+    pub fn deposit_credit(&mut self, amount: U128) -> Result<Order, FileExchangeError> {
+        self.check_migrated()?;
+        if amount.0 == 0 {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+        let account = self
+            .fee_account
+            .clone()
+            .ok_or(FileExchangeError::MarketplaceAccountMissing)?;
+
+        let action = CreateProposalRequest::SetContextValue {
+            key: format!("{}{}", CREDIT_DEPOSIT_KEY_PREFIX, self.next_order_id),
+            value: format!("{} {}", caller(), amount.0),
+        };
+        self.place_order(
+            OrderItem::CreditDeposit,
+            account,
+            Price {
+                amount,
+                token: NATIVE_TOKEN.to_owned(),
+            },
+            U128(0),
+            License::default(),
+            vec![action],
+        )
+    }
+
+    /// Pays `amount` of the caller's credit, bought or earned from queries,
+    /// out of the proxy's treasury to their payout account. This is how
+    /// sellers are paid for queries: the credit is taken when the order
+    /// opens, and the Transfer proposal paying it, on the marketplace's
+    /// behalf, needs approvals like any other. The credit is booked back if
+    /// the order is cancelled or expires.
+    pub fn withdraw_credit(&mut self, amount: U128) -> Result<Order, FileExchangeError> {
+        self.check_migrated()?;
+        if amount.0 == 0 {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+        let account = caller();
+        let marketplace = self
+            .fee_account
+            .clone()
+            .ok_or(FileExchangeError::MarketplaceAccountMissing)?;
+        let payout_account = self
+            .payout_accounts
+            .get(&account)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::PayoutAccountMissing)?;
+
+        // Taken before the proposal is sent, as it may execute at once.
+        let order_id = self.next_order_id;
+        self.post_entry(&account, EntryKind::Withdrawal { order_id }, amount)?;
+
+        let action = CreateProposalRequest::Transfer {
+            receiver_id: payout_account,
+            amount,
+        };
+        self.place_order(
+            OrderItem::CreditWithdrawal,
+            marketplace,
+            Price {
+                amount,
+                token: NATIVE_TOKEN.to_owned(),
+            },
+            U128(0),
+            License::default(),
+            vec![action],
+        )
+    }
+
+    pub fn get_credit_balance(&self) -> Result<U128, FileExchangeError> {
+        self.balance(&caller())
+    }
+
+    /// The caller's balance and a page of the entries behind it, oldest
+    /// first.
+    pub fn get_statement(
+        &self,
+        from: Option<EntryId>,
+        limit: Option<u32>,
+    ) -> Result<Statement, FileExchangeError> {
+        let account = caller();
+        let balance = self.balance(&account)?;
+        let Some(entries) = self
+            .ledger
+            .get(&account)
+            .map_err(FileExchangeError::storage)?
+        else {
+            return Ok(Statement {
+                balance,
+                entries: vec![],
+                next: None,
+            });
+        };
+
+        let size = Statement::size(limit);
+        let mut entries: Vec<LedgerEntry> = entries
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .skip(from.unwrap_or(0) as usize)
+            .take(size + 1)
+            .collect();
+
+        let next = if entries.len() > size {
+            entries.pop().map(|entry| entry.id)
+        } else {
+            None
+        };
+        Ok(Statement {
+            balance,
+            entries,
+            next,
+        })
+    }
+
+    // ===== Review Functions =====
+
+    /// Reviews a listing the caller bought or was granted. Each buyer
+    /// reviews a listing once; `text` may be empty.
+    pub fn submit_review(
+        &mut self,
+        name: String,
+        rating: u8,
+        text: String,
+    ) -> Result<Review, FileExchangeError> {
+        self.check_migrated()?;
+        let reviewer = caller();
+
+        check_rating(rating).map_err(FileExchangeError::InvalidReview)?;
+        check_review_text(&text).map_err(FileExchangeError::InvalidReview)?;
+        if self.listing_owner(&name)? == reviewer {
+            return Err(FileExchangeError::InvalidOperation);
+        }
+        if !self.is_verified_buyer(&name, &reviewer)? {
+            return Err(FileExchangeError::NotAVerifiedBuyer);
+        }
+
+        let mut reviews = self
+            .reviews
+            .get(&name)
+            .map_err(FileExchangeError::storage)?
+            .unwrap_or_default();
+        if reviews
+            .get(&reviewer)
+            .map_err(FileExchangeError::storage)?
+            .is_some()
+        {
+            return Err(FileExchangeError::AlreadyReviewed);
+        }
+
+        let review = Review {
+            listing: name.clone(),
+            reviewer: reviewer.clone(),
+            rating,
+            text,
+            created_at: host::time_now(),
+            reply: None,
+        };
+        reviews
+            .insert(reviewer.clone(), review.clone())
+            .map_err(FileExchangeError::storage)?;
+        self.reviews
+            .insert(name.clone(), reviews)
+            .map_err(FileExchangeError::storage)?;
+
+        let mut tally = self.rating_tally(&name)?;
+        tally.add(rating);
+        self.ratings
+            .insert(name.clone(), tally)
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::ReviewSubmitted {
+            name,
+            reviewer,
+            rating,
+        });
+        Ok(review)
+    }
+
+    /// The owner's public answer to a review. It cannot be changed once
+    /// posted.
+    pub fn reply_to_review(
+        &mut self,
+        name: String,
+        reviewer: String,
+        text: String,
+    ) -> Result<Review, FileExchangeError> {
+        self.check_migrated()?;
+        if self.listing_owner(&name)? != caller() {
+            return Err(FileExchangeError::Unauthorized);
+        }
+        if text.trim().is_empty() {
+            return Err(FileExchangeError::InvalidReview(
+                "reply must not be empty".to_owned(),
+            ));
+        }
+        check_review_text(&text).map_err(FileExchangeError::InvalidReview)?;
+
+        let mut reviews = self
+            .reviews
+            .get(&name)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::ReviewNotFound)?;
+        let mut review = reviews
+            .get(&reviewer)
+            .map_err(FileExchangeError::storage)?
+            .ok_or(FileExchangeError::ReviewNotFound)?;
+        if review.reply.is_some() {
+            return Err(FileExchangeError::AlreadyReplied);
+        }
+
+        review.reply = Some(Reply {
+            text,
+            created_at: host::time_now(),
+        });
+        reviews
+            .insert(reviewer.clone(), review.clone())
+            .map_err(FileExchangeError::storage)?;
+        self.reviews
+            .insert(name.clone(), reviews)
+            .map_err(FileExchangeError::storage)?;
+
+        host::emit(Event::ReviewReplied { name, reviewer });
+        Ok(review)
+    }
+
+    /// A listing's reviews, oldest first.
+    pub fn list_reviews(&self, name: String) -> Result<Vec<Review>, FileExchangeError> {
+        self.listing_owner(&name)?;
+
+        let Some(reviews) = self
+            .reviews
+            .get(&name)
+            .map_err(FileExchangeError::storage)?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut reviews: Vec<Review> = reviews
+            .entries()
+            .map_err(FileExchangeError::storage)?
+            .map(|(_, review)| review)
+            .collect();
+        reviews.sort_by(|a, b| (a.created_at, &a.reviewer).cmp(&(b.created_at, &b.reviewer)));
+        Ok(reviews)
+    }
+}
@@ -0,0 +1,140 @@
+//! Stand-in for the node runtime under `cargo test`: a settable executor
+//! and clock, and captured logs, events, proposals and approvals. Storage
+//! needs nothing extra, as `calimero_storage` keeps collections in memory
+//! when built natively. All state is per thread, so every test starts clean.
+
+use std::cell::RefCell;
+
+#[cfg(feature = "proposals")]
+use getem_types::{CreateProposalRequest, ProposalId};
+
+use crate::Event;
+
+/// Node time at the start of every test: 2024-01-01T00:00:00Z.
+pub const START_TIME: u64 = 1_704_067_200_000_000_000;
+
+/// A proposal the app sent to the context proxy.
+#[cfg(feature = "proposals")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SentProposal {
+    pub id: ProposalId,
+    pub proposer: [u8; 32],
+    pub actions: Vec<CreateProposalRequest>,
+}
+
+struct MockHost {
+    executor_id: [u8; 32],
+    now: u64,
+    #[cfg(feature = "proposals")]
+    next_proposal: u64,
+    #[cfg(feature = "proposals")]
+    logs: Vec<String>,
+    events: Vec<Event>,
+    #[cfg(feature = "proposals")]
+    outbox: Vec<SentProposal>,
+    #[cfg(feature = "proposals")]
+    approvals: Vec<(ProposalId, [u8; 32])>,
+}
+
+thread_local! {
+    static HOST: RefCell<MockHost> = RefCell::new(MockHost {
+        executor_id: [0; 32],
+        now: START_TIME,
+        #[cfg(feature = "proposals")]
+        next_proposal: 0,
+        #[cfg(feature = "proposals")]
+        logs: Vec::new(),
+        events: Vec::new(),
+        #[cfg(feature = "proposals")]
+        outbox: Vec::new(),
+        #[cfg(feature = "proposals")]
+        approvals: Vec::new(),
+    });
+}
+
+fn with<T>(f: impl FnOnce(&mut MockHost) -> T) -> T {
+    HOST.with(|host| f(&mut host.borrow_mut()))
+}
+
+// ---------------- Runtime Calls ----------------
+
+pub fn executor_id() -> [u8; 32] {
+    with(|host| host.executor_id)
+}
+
+pub fn time_now() -> u64 {
+    with(|host| host.now)
+}
+
+#[cfg(feature = "proposals")]
+pub fn log(message: &str) {
+    with(|host| host.logs.push(message.to_owned()));
+}
+
+pub fn emit(event: Event) {
+    with(|host| host.events.push(event));
+}
+
+#[cfg(feature = "proposals")]
+pub fn propose(action: CreateProposalRequest) -> ProposalId {
+    propose_all(vec![action])
+}
+
+/// Hands out sequential proposal ids instead of contacting a proxy.
+#[cfg(feature = "proposals")]
+pub fn propose_all(actions: Vec<CreateProposalRequest>) -> ProposalId {
+    with(|host| {
+        host.next_proposal += 1;
+        let mut id = [0; 32];
+        id[24..].copy_from_slice(&host.next_proposal.to_be_bytes());
+
+        let id = ProposalId(id);
+        host.outbox.push(SentProposal {
+            id,
+            proposer: host.executor_id,
+            actions,
+        });
+        id
+    })
+}
+
+#[cfg(feature = "proposals")]
+pub fn approve(proposal_id: ProposalId) {
+    with(|host| {
+        let approver = host.executor_id;
+        host.approvals.push((proposal_id, approver));
+    });
+}
+
+// ---------------- Test Controls ----------------
+
+/// Makes the identity derived from `seed` the caller of the following
+/// calls and returns it as the app sees it.
+pub fn act_as(seed: u8) -> String {
+    with(|host| host.executor_id = [seed; 32]);
+    bs58::encode([seed; 32]).into_string()
+}
+
+pub fn advance_time(nanos: u64) {
+    with(|host| host.now += nanos);
+}
+
+/// Events emitted since the last call.
+pub fn take_events() -> Vec<Event> {
+    with(|host| std::mem::take(&mut host.events))
+}
+
+#[cfg(feature = "proposals")]
+pub fn logs() -> Vec<String> {
+    with(|host| host.logs.clone())
+}
+
+#[cfg(feature = "proposals")]
+pub fn outbox() -> Vec<SentProposal> {
+    with(|host| host.outbox.clone())
+}
+
+#[cfg(feature = "proposals")]
+pub fn approvals() -> Vec<(ProposalId, [u8; 32])> {
+    with(|host| host.approvals.clone())
+}
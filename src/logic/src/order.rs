@@ -0,0 +1,10 @@
+pub use getem_types::order::{Order, OrderId};
+#[cfg(feature = "proposals")]
+pub use getem_types::order::{OrderItem, OrderStatus};
+
+// ---------------- Order Types ----------------
+
+/// How long a buyer's Transfer proposal may wait for approvals before the
+/// order can be expired (nanoseconds, matching `env::time_now`).
+#[cfg(feature = "marketplace")]
+pub const ORDER_TTL: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
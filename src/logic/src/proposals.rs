@@ -0,0 +1,223 @@
+//! Methods of the `proposals` feature: proposals to the context proxy and
+//! their discussion threads.
+
+use super::*;
+
+#[app::logic]
+impl FileExchangeState {
+    // ===== Proposal Functions =====
+
+    pub fn create_new_proposal(
+        &mut self,
+        request: CreateProposalRequest,
+    ) -> Result<ProposalId, Error> {
+        self.check_migrated()?;
+        host::log("Starting create_new_proposal");
+        host::log(&format!("Request type: {}", request.action_type()));
+
+        request.validate()?;
+        validate_setting(&request)?;
+
+        let proposal_id = host::propose(request.clone());
+
+        let record = ProposalRecord::new(proposal_id, caller(), request, host::time_now());
+        self.track_proposal(record)?;
+        Ok(proposal_id)
+    }
+
+    pub fn approve_proposal(&mut self, proposal_id: ProposalId) -> Result<(), Error> {
+        self.check_migrated()?;
+        let approver = caller();
+        let Some(mut record) = self.proposals.get(&proposal_id)? else {
+            // Proposals sent before the app kept a registry have a thread
+            // but no record; they are approved on the proxy as they were.
+            if !self.proposal_messages.contains(&proposal_id)? {
+                return Err(ApprovalError::ProposalNotFound.into());
+            }
+            host::approve(proposal_id);
+            host::emit(Event::ApprovedProposal { id: proposal_id });
+            return Ok(());
+        };
+        record.check_approval(&approver)?;
+        if !self.order_is_payable(proposal_id)? {
+            return Err(Error::msg(
+                "the order this proposal pays for is no longer pending",
+            ));
+        }
+
+        host::approve(proposal_id);
+        host::emit(Event::ApprovedProposal { id: proposal_id });
+
+        record.approvers.push(approver);
+        self.proposals.insert(proposal_id, record.clone())?;
+
+        self.update_proposal_status(record)
+    }
+
+    /// Records the approval threshold the context proxy reports, as read
+    /// by a client from the node (the proxy's `get_num_approvals`).
+    /// Proposals only settle while it matches the app's `num_approvals`;
+    /// returns whether it does.
+    pub fn sync_num_approvals(&mut self, num_approvals: u32) -> Result<bool, Error> {
+        self.check_migrated()?;
+        if num_approvals == 0 {
+            return Err(Error::msg("num_approvals must be greater than zero"));
+        }
+
+        let held = !self.num_approvals_agree();
+        self.proxy_num_approvals = Some(num_approvals);
+        let agree = self.num_approvals_agree();
+        if held && agree {
+            self.settle_held_proposals()?;
+        }
+        Ok(agree)
+    }
+
+    pub fn list_proposals(
+        &self,
+        status: Option<ProposalStatus>,
+    ) -> Result<Vec<ProposalRecord>, Error> {
+        let mut records: Vec<ProposalRecord> = self
+            .proposals
+            .entries()?
+            .map(|(_, record)| record)
+            .filter(|record| status.map_or(true, |status| record.status == status))
+            .collect();
+        records.sort_by_key(|record| record.created_at);
+        Ok(records)
+    }
+
+    pub fn get_proposal(&self, proposal_id: ProposalId) -> Result<ProposalRecord, Error> {
+        self.proposals
+            .get(&proposal_id)?
+            .ok_or_else(|| Error::msg("proposal not found"))
+    }
+
+    /// Withdraws a pending proposal. Only its creator may cancel it; the
+    /// proxy removes a proposal straight away when its author deletes it.
+    pub fn cancel_proposal(&mut self, proposal_id: ProposalId) -> Result<ProposalRecord, Error> {
+        self.check_migrated()?;
+        let record = self
+            .proposals
+            .get(&proposal_id)?
+            .ok_or(ApprovalError::ProposalNotFound)?;
+
+        if record.creator != caller() {
+            return Err(Error::msg("only the creator can cancel a proposal"));
+        }
+        if record.status.is_closed() {
+            return Err(Error::msg("proposal is no longer pending"));
+        }
+
+        host::propose(CreateProposalRequest::DeleteProposal { proposal_id });
+        self.withdraw_proposal(proposal_id, ProposalStatus::Cancelled)?;
+        host::emit(Event::ProposalCancelled { id: proposal_id });
+
+        self.get_proposal(proposal_id)
+    }
+
+    /// Cleans up the threads of proposals that finished before threads
+    /// were cleaned up automatically. Returns how many were closed.
+    pub fn prune_proposal_messages(&mut self) -> Result<u32, Error> {
+        self.check_migrated()?;
+        let finished: Vec<ProposalId> = self
+            .proposals
+            .entries()?
+            .filter(|(_, record)| record.status.is_closed())
+            .map(|(id, _)| id)
+            .collect();
+
+        let mut closed = 0;
+        for proposal_id in finished {
+            if self.close_thread(proposal_id)? {
+                closed += 1;
+            }
+        }
+        Ok(closed)
+    }
+
+    /// Open threads first, then archived ones. Pages start at message id
+    /// `from` (the first message by default); deleted messages come back as
+    /// tombstones so the ids of a page stay contiguous.
+    pub fn get_proposal_messages(
+        &self,
+        proposal_id: ProposalId,
+        from: Option<MessageId>,
+        limit: Option<u32>,
+    ) -> Result<MessagePage, Error> {
+        let thread = match self.proposal_messages.get(&proposal_id)? {
+            Some(thread) => thread,
+            None => match self.archived_messages.get(&proposal_id)? {
+                Some(thread) => thread,
+                None => {
+                    return Ok(MessagePage {
+                        messages: vec![],
+                        next: None,
+                    })
+                }
+            },
+        };
+
+        let size = MessagePage::size(limit);
+        let mut messages: Vec<Message> = thread
+            .entries()?
+            .skip(from.unwrap_or(0) as usize)
+            .take(size + 1)
+            .collect();
+
+        let next = if messages.len() > size {
+            messages.pop().map(|message| message.id)
+        } else {
+            None
+        };
+        Ok(MessagePage { messages, next })
+    }
+
+    /// Posts `text` to a pending proposal's thread as the caller.
+    pub fn send_proposal_messages(
+        &mut self,
+        proposal_id: ProposalId,
+        text: String,
+    ) -> Result<Message, Error> {
+        self.check_migrated()?;
+        check_text(&text)?;
+        let mut thread = self.open_thread(proposal_id)?;
+
+        let message = Message::new(
+            thread.len()? as MessageId,
+            proposal_id,
+            caller(),
+            text,
+            host::time_now(),
+        );
+        thread.push(message.clone())?;
+        self.proposal_messages.insert(proposal_id, thread)?;
+        Ok(message)
+    }
+
+    pub fn edit_proposal_message(
+        &mut self,
+        proposal_id: ProposalId,
+        message_id: MessageId,
+        text: String,
+    ) -> Result<Message, Error> {
+        self.check_migrated()?;
+        check_text(&text)?;
+        self.change_message(proposal_id, message_id, |message| {
+            message.edit(text, host::time_now())
+        })
+    }
+
+    /// Deleted messages keep their place in the thread, with the text
+    /// cleared.
+    pub fn delete_proposal_message(
+        &mut self,
+        proposal_id: ProposalId,
+        message_id: MessageId,
+    ) -> Result<Message, Error> {
+        self.check_migrated()?;
+        self.change_message(proposal_id, message_id, |message| {
+            message.delete(host::time_now())
+        })
+    }
+}
@@ -0,0 +1,57 @@
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "marketplace")]
+pub use getem_types::review::Reply;
+pub use getem_types::review::Review;
+
+// ---------------- Review Types ----------------
+
+#[cfg(feature = "marketplace")]
+pub const MIN_RATING: u8 = 1;
+#[cfg(feature = "marketplace")]
+pub const MAX_RATING: u8 = 5;
+
+/// Longest review or reply text the app accepts, in bytes.
+#[cfg(feature = "marketplace")]
+pub const MAX_REVIEW_BYTES: usize = 2048;
+
+/// Running totals behind a listing's score, so summaries need not read
+/// every review.
+#[derive(Clone, Copy, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+pub struct RatingTally {
+    pub count: u64,
+    pub total: u64,
+}
+
+impl RatingTally {
+    #[cfg(feature = "marketplace")]
+    pub fn add(&mut self, rating: u8) {
+        self.count += 1;
+        self.total += u64::from(rating);
+    }
+
+    /// `None` until the listing has a review.
+    #[cfg(feature = "marketplace")]
+    pub fn average(&self) -> Option<f32> {
+        (self.count > 0).then(|| self.total as f32 / self.count as f32)
+    }
+}
+
+#[cfg(feature = "marketplace")]
+pub fn check_rating(rating: u8) -> Result<(), String> {
+    if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+        return Err(format!(
+            "rating must be between {} and {}",
+            MIN_RATING, MAX_RATING
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "marketplace")]
+pub fn check_review_text(text: &str) -> Result<(), String> {
+    if text.len() > MAX_REVIEW_BYTES {
+        return Err(format!("text must be at most {} bytes", MAX_REVIEW_BYTES));
+    }
+    Ok(())
+}
@@ -0,0 +1,330 @@
+//! Versioned storage layout of `FileExchangeState`.
+//!
+//! The state is written as `STATE_MAGIC`, the `STATE_VERSION` it was written
+//! with, and then the fields in declaration order. When a field is added,
+//! bump `STATE_VERSION`, append the field to both impls below and start it
+//! fresh when reading an older version, so upgrading the wasm keeps the
+//! existing marketplace.
+//!
+//! The proposals app, since merged into this one, stored only its message
+//! threads, unversioned; such states are taken over as well, see
+//! `read_proposals_app`.
+//!
+//! Loading never writes. Fields stored in an older format are kept aside
+//! in a `PendingMigration` and converted by the `migrate` call, which
+//! writes the root in the current layout; until then the state cannot be
+//! written back.
+
+use std::io::{self, Read, Write};
+
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use calimero_storage::collections::{UnorderedMap, Vector};
+use getem_types::{Message, MessageId, ProposalId};
+
+use crate::catalog::ListingMeta;
+use crate::{FileEntry, FileExchangeState};
+
+/// Marks a versioned state. The unversioned layout that came before starts
+/// with the id of the `files` collection instead.
+const STATE_MAGIC: [u8; 4] = *b"GTEM";
+
+/// 1: first versioned layout.
+pub const STATE_VERSION: u32 = 1;
+
+/// The unversioned layout did not store the proxy's threshold; the app
+/// then assumed the proxy's default of 3 approvals. A SetNumApprovals
+/// proposal corrects it.
+pub const LEGACY_NUM_APPROVALS: u32 = 3;
+
+/// Why a state loaded in an older layout cannot be changed yet.
+pub const MIGRATION_REQUIRED: &str =
+    "the state is stored in an older layout, call `migrate()` once to convert it";
+
+impl BorshSerialize for FileExchangeState {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        if self.migration.is_some() {
+            return Err(io::Error::other(MIGRATION_REQUIRED));
+        }
+
+        writer.write_all(&STATE_MAGIC)?;
+        STATE_VERSION.serialize(writer)?;
+
+        self.files.serialize(writer)?;
+        self.blobs.serialize(writer)?;
+        self.uploads.serialize(writer)?;
+        self.next_upload_id.serialize(writer)?;
+        self.datasets.serialize(writer)?;
+        self.proposal_messages.serialize(writer)?;
+        self.proposals.serialize(writer)?;
+        self.num_approvals.serialize(writer)?;
+        self.orders.serialize(writer)?;
+        self.order_proposals.serialize(writer)?;
+        self.next_order_id.serialize(writer)?;
+        self.payout_accounts.serialize(writer)?;
+        self.grants.serialize(writer)?;
+        self.wrapped_keys.serialize(writer)?;
+        self.archived_messages.serialize(writer)?;
+        self.archive_messages.serialize(writer)?;
+        self.listings.serialize(writer)?;
+        self.next_listing_seq.serialize(writer)?;
+        self.fee_bps.serialize(writer)?;
+        self.fee_account.serialize(writer)?;
+        self.plans.serialize(writer)?;
+        self.next_plan_id.serialize(writer)?;
+        self.subscriptions.serialize(writer)?;
+        self.query_prices.serialize(writer)?;
+        self.credit_balances.serialize(writer)?;
+        self.ledger.serialize(writer)?;
+        self.reviews.serialize(writer)?;
+        self.ratings.serialize(writer)?;
+        self.proxy_num_approvals.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for FileExchangeState {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        match bytes.strip_prefix(&STATE_MAGIC) {
+            Some(mut rest) => {
+                let version = u32::deserialize(&mut rest)?;
+                Self::read_version(version, &mut rest)
+            }
+            None => match Self::read_proposals_app(&bytes) {
+                Some(state) => Ok(state),
+                None => Self::read_unversioned(&mut bytes.as_slice()),
+            },
+        }
+    }
+}
+
+impl FileExchangeState {
+    fn read_version(version: u32, buf: &mut &[u8]) -> io::Result<Self> {
+        if version != STATE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "state version {} is not supported by this app (version {})",
+                    version, STATE_VERSION
+                ),
+            ));
+        }
+
+        Ok(FileExchangeState {
+            files: read(buf)?,
+            blobs: read(buf)?,
+            uploads: read(buf)?,
+            next_upload_id: read(buf)?,
+            datasets: read(buf)?,
+            proposal_messages: read(buf)?,
+            proposals: read(buf)?,
+            num_approvals: read(buf)?,
+            orders: read(buf)?,
+            order_proposals: read(buf)?,
+            next_order_id: read(buf)?,
+            payout_accounts: read(buf)?,
+            grants: read(buf)?,
+            wrapped_keys: read(buf)?,
+            archived_messages: read(buf)?,
+            archive_messages: read(buf)?,
+            listings: read(buf)?,
+            next_listing_seq: read(buf)?,
+            fee_bps: read(buf)?,
+            fee_account: read(buf)?,
+            plans: read(buf)?,
+            next_plan_id: read(buf)?,
+            subscriptions: read(buf)?,
+            query_prices: read(buf)?,
+            credit_balances: read(buf)?,
+            ledger: read(buf)?,
+            reviews: read(buf)?,
+            ratings: read(buf)?,
+            proxy_num_approvals: read(buf)?,
+            migration: None,
+        })
+    }
+
+    /// The layout before versioning: files stored their content inline and
+    /// there was nothing but files and proposal messages. Everything else
+    /// starts out empty.
+    fn read_unversioned(buf: &mut &[u8]) -> io::Result<Self> {
+        let pending = PendingMigration {
+            files: Some(read(buf)?),
+            threads: Some(read(buf)?),
+            backfill_listings: true,
+        };
+        Ok(FileExchangeState::init(LEGACY_NUM_APPROVALS).with_migration(pending))
+    }
+
+    /// Reads a state of the proposals app, which held nothing but its
+    /// message threads. Both apps' unversioned layouts start with a map,
+    /// so `bytes` are only taken for one when they hold exactly that; this
+    /// app's is longer. Returns `None` for anything else.
+    fn read_proposals_app(mut bytes: &[u8]) -> Option<Self> {
+        let buf = &mut bytes;
+        let pending = PendingMigration {
+            threads: Some(read(buf).ok()?),
+            ..PendingMigration::default()
+        };
+        buf.is_empty()
+            .then(|| FileExchangeState::init(LEGACY_NUM_APPROVALS).with_migration(pending))
+    }
+
+    fn with_migration(mut self, pending: PendingMigration) -> Self {
+        if !pending.is_empty() {
+            self.migration = Some(pending);
+        }
+        self
+    }
+
+    /// Converts the fields loading left in `migration` and drops their
+    /// legacy collections. Returns whether there was anything to convert.
+    pub(crate) fn run_migration(&mut self) -> io::Result<bool> {
+        let Some(pending) = self.migration.take() else {
+            return Ok(false);
+        };
+
+        if let Some(files) = pending.files {
+            self.migrate_files(files)?;
+        }
+        if let Some(threads) = pending.threads {
+            self.proposal_messages = migrate_threads(threads)?;
+        }
+        if pending.backfill_listings {
+            self.backfill_listings()?;
+        }
+        Ok(true)
+    }
+
+    /// File content is moved into blobs.
+    fn migrate_files(
+        &mut self,
+        mut legacy: UnorderedMap<String, LegacyFileEntry>,
+    ) -> io::Result<()> {
+        let files: Vec<LegacyFileEntry> = legacy
+            .entries()
+            .map_err(io::Error::other)?
+            .map(|(_, file)| file)
+            .collect();
+        for file in files {
+            let sha256 = self
+                .store_content(&file.content)
+                .map_err(io::Error::other)?;
+            let entry = FileEntry {
+                name: file.name.clone(),
+                owner: file.owner,
+                size: file.content.len() as u64,
+                sha256,
+                encrypted: false,
+            };
+            self.files
+                .insert(file.name, entry)
+                .map_err(io::Error::other)?;
+        }
+        legacy.clear().map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    /// Puts listings that predate the catalog into it, by name as their
+    /// real order is unknown, with no timestamp.
+    fn backfill_listings(&mut self) -> io::Result<()> {
+        let mut names: Vec<String> = self
+            .files
+            .entries()
+            .map_err(io::Error::other)?
+            .map(|(name, _)| name)
+            .collect();
+        names.extend(
+            self.datasets
+                .entries()
+                .map_err(io::Error::other)?
+                .map(|(name, _)| name),
+        );
+        names.sort();
+
+        for name in names {
+            let meta = ListingMeta {
+                seq: self.next_listing_seq,
+                ..ListingMeta::default()
+            };
+            self.next_listing_seq += 1;
+            self.listings.insert(name, meta).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+}
+
+fn read<T: BorshDeserialize>(buf: &mut &[u8]) -> io::Result<T> {
+    T::deserialize(buf)
+}
+
+type Threads = UnorderedMap<ProposalId, Vector<Message>>;
+type LegacyThreads = UnorderedMap<ProposalId, Vector<LegacyMessage>>;
+
+/// Renumbers every thread in posting order. Client timestamps are kept
+/// when they are plain nanoseconds and dropped to 0 otherwise.
+fn migrate_threads(mut legacy: LegacyThreads) -> io::Result<Threads> {
+    let old: Vec<(ProposalId, Vector<LegacyMessage>)> =
+        legacy.entries().map_err(io::Error::other)?.collect();
+
+    let mut threads = UnorderedMap::new();
+    for (proposal_id, mut old_thread) in old {
+        let mut thread = Vector::new();
+        let messages: Vec<LegacyMessage> =
+            old_thread.entries().map_err(io::Error::other)?.collect();
+        for (id, message) in messages.into_iter().enumerate() {
+            thread
+                .push(Message::new(
+                    id as MessageId,
+                    proposal_id,
+                    message.author,
+                    message.text,
+                    message.created_at.parse().unwrap_or(0),
+                ))
+                .map_err(io::Error::other)?;
+        }
+        old_thread.clear().map_err(io::Error::other)?;
+        threads
+            .insert(proposal_id, thread)
+            .map_err(io::Error::other)?;
+    }
+    legacy.clear().map_err(io::Error::other)?;
+
+    Ok(threads)
+}
+
+/// Collections of a loaded state still in an older format, read but not
+/// yet converted.
+#[derive(Default)]
+pub(crate) struct PendingMigration {
+    files: Option<UnorderedMap<String, LegacyFileEntry>>,
+    threads: Option<LegacyThreads>,
+    backfill_listings: bool,
+}
+
+impl PendingMigration {
+    fn is_empty(&self) -> bool {
+        self.files.is_none() && self.threads.is_none() && !self.backfill_listings
+    }
+}
+
+/// `FileEntry` as stored by the unversioned layout.
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+pub(crate) struct LegacyFileEntry {
+    pub name: String,
+    pub content: String,
+    pub owner: String,
+}
+
+/// `Message` as stored by the unversioned layouts, built whole by clients.
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+pub(crate) struct LegacyMessage {
+    pub id: String,
+    pub proposal_id: String,
+    pub author: String,
+    pub text: String,
+    pub created_at: String,
+}
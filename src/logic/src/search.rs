@@ -0,0 +1,65 @@
+use std::cmp::Ordering;
+
+use calimero_sdk::serde::Serialize;
+
+use crate::dataset::{DistanceMetric, EmbeddingChunk};
+
+/// Upper bound on `k` so a single query cannot ask the node to return the
+/// whole dataset.
+pub const MAX_SEARCH_K: u32 = 100;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct SearchHit {
+    pub chunk_id: String,
+    pub score: f32,
+    pub text_excerpt: Option<String>,
+}
+
+/// Keeps the best `k` hits seen so far, best first.
+pub struct TopK {
+    k: usize,
+    metric: DistanceMetric,
+    hits: Vec<SearchHit>,
+}
+
+impl TopK {
+    pub fn new(k: u32, metric: DistanceMetric) -> Self {
+        TopK {
+            k: k as usize,
+            metric,
+            hits: Vec::with_capacity(k as usize + 1),
+        }
+    }
+
+    pub fn offer(&mut self, query: &[f32], chunk: EmbeddingChunk) {
+        let score = self.metric.score(query, &chunk.vector);
+
+        if self.hits.len() == self.k {
+            let Some(worst) = self.hits.last() else {
+                return;
+            };
+            if self.metric.rank(score, worst.score) != Ordering::Less {
+                return;
+            }
+        }
+
+        let position = self
+            .hits
+            .partition_point(|hit| self.metric.rank(hit.score, score) != Ordering::Greater);
+
+        self.hits.insert(
+            position,
+            SearchHit {
+                chunk_id: chunk.chunk_id,
+                score,
+                text_excerpt: chunk.metadata.text_excerpt,
+            },
+        );
+        self.hits.truncate(self.k);
+    }
+
+    pub fn into_hits(self) -> Vec<SearchHit> {
+        self.hits
+    }
+}
@@ -0,0 +1,26 @@
+pub use getem_types::subscription::{Plan, PlanId, Subscription};
+
+#[cfg(feature = "marketplace")]
+use crate::terms::Price;
+
+// ---------------- Subscription Types ----------------
+
+#[cfg(feature = "marketplace")]
+pub const MAX_PLAN_NAME_BYTES: usize = 64;
+
+#[cfg(feature = "marketplace")]
+pub fn check_plan(name: &str, period: u64, price: &Price) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("plan name must not be empty".to_owned());
+    }
+    if name.len() > MAX_PLAN_NAME_BYTES {
+        return Err(format!(
+            "plan name must be at most {} bytes",
+            MAX_PLAN_NAME_BYTES
+        ));
+    }
+    if period == 0 {
+        return Err("period must be greater than zero".to_owned());
+    }
+    price.check()
+}
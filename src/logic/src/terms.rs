@@ -0,0 +1,63 @@
+#[cfg(feature = "marketplace")]
+use calimero_sdk::serde::Serialize;
+#[cfg(feature = "marketplace")]
+pub use getem_types::terms::NATIVE_TOKEN;
+pub use getem_types::terms::{License, Price};
+#[cfg(feature = "marketplace")]
+use getem_types::U128;
+#[cfg(feature = "proposals")]
+use getem_types::{CreateProposalRequest, ProposalRequestError};
+
+// ---------------- Marketplace Fee ----------------
+
+/// Context values that configure the marketplace fee. Like every context
+/// value they can only be changed by a SetContextValue proposal.
+#[cfg(feature = "proposals")]
+pub const MARKETPLACE_FEE_KEY: &str = "getem.marketplace_fee_bps";
+#[cfg(feature = "proposals")]
+pub const MARKETPLACE_FEE_ACCOUNT_KEY: &str = "getem.marketplace_fee_account";
+
+/// The fee is given in basis points: 250 is 2.5%.
+#[cfg(feature = "proposals")]
+pub const MAX_FEE_BPS: u32 = 10_000;
+
+#[cfg(feature = "proposals")]
+pub fn parse_fee_bps(value: &str) -> Option<u32> {
+    value.trim().parse().ok().filter(|bps| *bps <= MAX_FEE_BPS)
+}
+
+/// Rejects fee settings the app could not apply once executed.
+#[cfg(feature = "proposals")]
+pub fn validate_setting(request: &CreateProposalRequest) -> Result<(), ProposalRequestError> {
+    let CreateProposalRequest::SetContextValue { key, value } = request else {
+        return Ok(());
+    };
+
+    if key == MARKETPLACE_FEE_KEY && parse_fee_bps(value).is_none() {
+        return Err(ProposalRequestError::InvalidValue("value"));
+    }
+    if key == MARKETPLACE_FEE_ACCOUNT_KEY && value.trim().is_empty() {
+        return Err(ProposalRequestError::EmptyField("value"));
+    }
+    Ok(())
+}
+
+/// Splits a payment into what the seller gets and the marketplace fee,
+/// rounding the fee down.
+#[cfg(feature = "marketplace")]
+pub fn split(amount: U128, fee_bps: u32) -> (U128, U128) {
+    let bps = u128::from(fee_bps.min(MAX_FEE_BPS));
+    let scale = u128::from(MAX_FEE_BPS);
+    let fee = amount.0 / scale * bps + amount.0 % scale * bps / scale;
+    (U128(amount.0 - fee), U128(fee))
+}
+
+/// What `get_marketplace_fee` returns. No fee is taken until a fee
+/// account is set.
+#[cfg(feature = "marketplace")]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct MarketplaceFee {
+    pub fee_bps: u32,
+    pub fee_account: Option<String>,
+}
@@ -0,0 +1,1720 @@
+#[cfg(feature = "marketplace")]
+use std::collections::BTreeMap;
+
+#[cfg(feature = "files")]
+use calimero_sdk::borsh::BorshSerialize;
+use calimero_sdk::borsh::{self, BorshDeserialize};
+#[cfg(feature = "proposals")]
+use getem_types::message::MAX_MESSAGE_BYTES;
+#[cfg(feature = "marketplace")]
+use getem_types::terms::{AllowedUses, LicenseKind};
+
+use super::*;
+use mock::{act_as, advance_time, take_events, START_TIME};
+#[cfg(feature = "proposals")]
+use mock::{approvals, logs, outbox};
+#[cfg(feature = "files")]
+use schema::LegacyFileEntry;
+use schema::{LegacyMessage, LEGACY_NUM_APPROVALS, STATE_VERSION};
+
+/// The state as stored before it carried a version.
+#[cfg(feature = "files")]
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+struct UnversionedState {
+    files: UnorderedMap<String, LegacyFileEntry>,
+    proposal_messages: UnorderedMap<ProposalId, Vector<LegacyMessage>>,
+}
+
+fn legacy_message(id: &str, created_at: &str) -> LegacyMessage {
+    LegacyMessage {
+        id: id.to_owned(),
+        proposal_id: "proposal".to_owned(),
+        author: "alice".to_owned(),
+        text: "hello".to_owned(),
+        created_at: created_at.to_owned(),
+    }
+}
+
+fn legacy_threads(proposal_id: ProposalId) -> UnorderedMap<ProposalId, Vector<LegacyMessage>> {
+    let mut thread = Vector::new();
+    thread
+        .push(legacy_message("m1", "2024-01-01T00:00:00Z"))
+        .unwrap();
+    thread
+        .push(legacy_message("m2", &START_TIME.to_string()))
+        .unwrap();
+    let mut threads = UnorderedMap::new();
+    threads.insert(proposal_id, thread).unwrap();
+    threads
+}
+
+/// Legacy messages are renumbered; only numeric timestamps survive.
+fn migrated_thread(proposal_id: ProposalId) -> Vec<Message> {
+    let alice = "alice".to_owned();
+    let hello = "hello".to_owned();
+    vec![
+        Message::new(0, proposal_id, alice.clone(), hello.clone(), 0),
+        Message::new(1, proposal_id, alice, hello, START_TIME),
+    ]
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_unversioned_state_is_migrated() {
+    let mut files = UnorderedMap::new();
+    for (name, owner) in [("a.txt", "alice"), ("b.txt", "bob")] {
+        files
+            .insert(
+                name.to_owned(),
+                LegacyFileEntry {
+                    name: name.to_owned(),
+                    content: "same content".to_owned(),
+                    owner: owner.to_owned(),
+                },
+            )
+            .unwrap();
+    }
+    let old = borsh::to_vec(&UnversionedState {
+        files,
+        proposal_messages: legacy_threads(ProposalId([1; 32])),
+    })
+    .unwrap();
+    // Loading alone converts and drops nothing, and cannot be written back.
+    let mut unmigrated = FileExchangeState::try_from_slice(&old).expect("old layout should load");
+    assert!(unmigrated.file("a.txt").is_err());
+    assert!(borsh::to_vec(&unmigrated).is_err());
+    act_as(ALICE);
+    match unmigrated.upload_file("c.txt".to_owned(), "c".to_owned(), None) {
+        Err(FileExchangeError::MigrationRequired(message)) => assert_eq!(
+            message,
+            "the state is stored in an older layout, call `migrate()` once to convert it"
+        ),
+        other => panic!("expected MigrationRequired, got {:?}", other),
+    }
+    drop(unmigrated);
+
+    let mut state = FileExchangeState::try_from_slice(&old).unwrap();
+    assert!(state.migrate().unwrap());
+    assert!(!state.migrate().unwrap(), "nothing left to convert");
+    assert_eq!(
+        FileExchangeState::try_from_slice(&borsh::to_vec(&state).unwrap())
+            .unwrap()
+            .file("a.txt")
+            .unwrap()
+            .sha256,
+        sha256_hex(b"same content")
+    );
+
+    let a = state.file("a.txt").unwrap();
+    let b = state.file("b.txt").unwrap();
+    assert_eq!(a.owner, "alice");
+    assert_eq!(a.size, "same content".len() as u64);
+    assert_eq!(a.sha256, sha256_hex(b"same content"));
+    assert!(!a.encrypted);
+    assert_eq!(a.sha256, b.sha256);
+
+    let blob = state
+        .blob(&a.sha256)
+        .unwrap()
+        .expect("content moved to a blob");
+    assert_eq!(blob.refs, 2);
+    let content: String = blob.chunks.entries().unwrap().collect();
+    assert_eq!(content, "same content");
+
+    let thread = state
+        .proposal_messages
+        .get(&ProposalId([1; 32]))
+        .unwrap()
+        .expect("messages kept");
+    assert_eq!(
+        thread.entries().unwrap().collect::<Vec<_>>(),
+        migrated_thread(ProposalId([1; 32]))
+    );
+    assert_eq!(state.num_approvals, LEGACY_NUM_APPROVALS);
+    assert_eq!(state.next_listing_seq, 2, "both files are listed");
+}
+
+#[test]
+fn test_state_round_trips_with_version_header() {
+    let mut state = FileExchangeState::init(PROXY_APPROVALS);
+    state.num_approvals = 5;
+    state.next_order_id = 7;
+
+    let bytes = borsh::to_vec(&state).unwrap();
+    assert_eq!(&bytes[..4], b"GTEM");
+    assert_eq!(
+        u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+        STATE_VERSION
+    );
+
+    let mut loaded = FileExchangeState::try_from_slice(&bytes).unwrap();
+    assert!(!loaded.migrate().unwrap());
+    assert_eq!(loaded.num_approvals, 5);
+    assert_eq!(loaded.next_order_id, 7);
+}
+
+#[test]
+fn test_newer_state_version_is_rejected() {
+    let mut bytes = borsh::to_vec(&FileExchangeState::init(PROXY_APPROVALS)).unwrap();
+    bytes[4..8].copy_from_slice(&(STATE_VERSION + 1).to_le_bytes());
+
+    assert!(FileExchangeState::try_from_slice(&bytes).is_err());
+}
+
+#[test]
+fn test_unversioned_proposals_app_state_is_migrated() {
+    let old = borsh::to_vec(&legacy_threads(ProposalId([6; 32]))).unwrap();
+
+    let mut state = FileExchangeState::try_from_slice(&old).expect("old layout should load");
+    assert!(state.migrate().unwrap());
+    let thread = state
+        .proposal_messages
+        .get(&ProposalId([6; 32]))
+        .unwrap()
+        .expect("messages kept");
+    assert_eq!(
+        thread.entries().unwrap().collect::<Vec<_>>(),
+        migrated_thread(ProposalId([6; 32]))
+    );
+    assert_eq!(state.num_approvals, LEGACY_NUM_APPROVALS);
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_proposal_from_before_the_upgrade_can_be_approved() {
+    let id = ProposalId([7; 32]);
+    let old = borsh::to_vec(&legacy_threads(id)).unwrap();
+    let mut state = FileExchangeState::try_from_slice(&old).unwrap();
+    state.migrate().unwrap();
+    assert!(state.get_proposal(id).is_err(), "no record was kept then");
+
+    act_as(BOB);
+    state.approve_proposal(id).unwrap();
+    assert_eq!(approvals(), vec![(id, [BOB; 32])]);
+    assert_eq!(take_events(), vec![Event::ApprovedProposal { id }]);
+
+    let unknown = ProposalId([8; 32]);
+    assert!(state.approve_proposal(unknown).is_err());
+    assert_eq!(approvals().len(), 1);
+}
+
+// ---------------- Files ----------------
+
+/// The threshold of the proxy the tests' contexts are created with.
+const PROXY_APPROVALS: u32 = 3;
+
+const ALICE: u8 = 1;
+const BOB: u8 = 2;
+#[cfg(feature = "proposals")]
+const CAROL: u8 = 3;
+#[cfg(feature = "proposals")]
+const DAVE: u8 = 4;
+
+#[cfg(feature = "files")]
+#[test]
+fn test_upload_download_and_delete() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    let alice = act_as(ALICE);
+
+    let sha256 = app
+        .upload_file("notes.txt".to_owned(), "hello".to_owned(), None)
+        .unwrap();
+    assert_eq!(sha256, sha256_hex(b"hello"));
+    assert!(matches!(
+        take_events().as_slice(),
+        [Event::FileUploaded { name, owner, .. }] if name == "notes.txt" && *owner == alice
+    ));
+
+    let download = app.download_file("notes.txt".to_owned()).unwrap();
+    assert_eq!(download.content, "hello");
+    assert_eq!(download.sha256, sha256);
+
+    act_as(BOB);
+    assert!(matches!(
+        app.download_file("notes.txt".to_owned()),
+        Err(FileExchangeError::Unauthorized)
+    ));
+    assert!(matches!(
+        app.delete_file("notes.txt".to_owned()),
+        Err(FileExchangeError::Unauthorized)
+    ));
+
+    act_as(ALICE);
+    app.delete_file("notes.txt".to_owned()).unwrap();
+    assert!(app.list_files().unwrap().is_empty());
+    assert!(app.blob(&sha256).unwrap().is_none());
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_identical_content_is_stored_once() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+
+    let first = app
+        .upload_file("a.txt".to_owned(), "same".to_owned(), None)
+        .unwrap();
+    let second = app
+        .upload_file("b.txt".to_owned(), "same".to_owned(), None)
+        .unwrap();
+    assert_eq!(first, second);
+    assert_eq!(app.blob(&first).unwrap().unwrap().refs, 2);
+
+    app.delete_file("a.txt".to_owned()).unwrap();
+    assert_eq!(app.blob(&first).unwrap().unwrap().refs, 1);
+    assert_eq!(
+        app.download_file("b.txt".to_owned()).unwrap().content,
+        "same"
+    );
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_chunked_upload() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+
+    let upload = app.begin_upload("big.txt".to_owned(), None).unwrap();
+    for (index, piece) in ["first ", "second"].into_iter().enumerate() {
+        app.append_upload_chunk(
+            upload.id,
+            index as u64,
+            piece.to_owned(),
+            sha256_hex(piece.as_bytes()),
+        )
+        .unwrap();
+    }
+
+    assert!(matches!(
+        app.finalize_upload(upload.id, Some(sha256_hex(b"wrong"))),
+        Err(FileExchangeError::ChecksumMismatch)
+    ));
+    let sha256 = app
+        .finalize_upload(upload.id, Some(sha256_hex(b"first second")))
+        .unwrap();
+
+    let info = app.get_file_info("big.txt".to_owned()).unwrap();
+    assert_eq!(info.size, 12);
+    assert_eq!(info.sha256, sha256);
+    assert!(matches!(
+        app.upload_status(upload.id),
+        Err(FileExchangeError::UploadNotFound)
+    ));
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_pending_upload_does_not_reserve_its_name() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    let stalled = app.begin_upload("big.txt".to_owned(), None).unwrap();
+
+    act_as(BOB);
+    let upload = app.begin_upload("big.txt".to_owned(), None).unwrap();
+    app.append_upload_chunk(upload.id, 0, "bob".to_owned(), sha256_hex(b"bob"))
+        .unwrap();
+    app.finalize_upload(upload.id, None).unwrap();
+
+    act_as(ALICE);
+    assert!(matches!(
+        app.begin_upload("big.txt".to_owned(), None),
+        Err(FileExchangeError::InvalidOperation)
+    ));
+    assert!(matches!(
+        app.finalize_upload(stalled.id, None),
+        Err(FileExchangeError::InvalidOperation)
+    ));
+    app.abort_upload(stalled.id).unwrap();
+    assert_eq!(
+        app.get_file_info("big.txt".to_owned()).unwrap().owner,
+        act_as(BOB)
+    );
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_abandoned_upload_expires() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    let active = app.begin_upload("active.txt".to_owned(), None).unwrap();
+    let abandoned = app.begin_upload("abandoned.txt".to_owned(), None).unwrap();
+    take_events();
+
+    // Every chunk gives the upload another UPLOAD_TTL.
+    advance_time(UPLOAD_TTL - 1);
+    app.append_upload_chunk(active.id, 0, "a".to_owned(), sha256_hex(b"a"))
+        .unwrap();
+    advance_time(1);
+    assert!(matches!(
+        app.append_upload_chunk(abandoned.id, 0, "b".to_owned(), sha256_hex(b"b")),
+        Err(FileExchangeError::UploadNotFound)
+    ));
+
+    act_as(BOB);
+    assert_eq!(app.expire_uploads().unwrap(), [abandoned.id]);
+    assert_eq!(take_events(), [Event::UploadExpired { id: abandoned.id }]);
+    assert!(app.uploads.get(&abandoned.id).unwrap().is_none());
+    assert!(app.expire_uploads().unwrap().is_empty());
+
+    act_as(ALICE);
+    assert_eq!(app.upload_status(active.id).unwrap().next_index, 1);
+    app.finalize_upload(active.id, None).unwrap();
+}
+
+// ---------------- Proposals ----------------
+
+#[cfg(feature = "proposals")]
+fn set_num_approvals(num_approvals: u32) -> CreateProposalRequest {
+    CreateProposalRequest::SetNumApprovals { num_approvals }
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_proposal_executes_after_enough_approvals() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+
+    let id = app.create_new_proposal(set_num_approvals(2)).unwrap();
+    assert_eq!(outbox().len(), 1);
+    assert_eq!(outbox()[0].actions, [set_num_approvals(2)]);
+    assert_eq!(outbox()[0].proposer, [ALICE; 32]);
+
+    act_as(BOB);
+    app.approve_proposal(id).unwrap();
+    assert_eq!(
+        app.get_proposal(id).unwrap().status,
+        ProposalStatus::Pending
+    );
+    assert!(app.approve_proposal(id).is_err(), "approving twice");
+
+    act_as(CAROL);
+    app.approve_proposal(id).unwrap();
+    assert_eq!(
+        app.get_proposal(id).unwrap().status,
+        ProposalStatus::Executed
+    );
+    assert_eq!(app.num_approvals, 2);
+    assert_eq!(approvals(), vec![(id, [BOB; 32]), (id, [CAROL; 32])]);
+    assert!(take_events()
+        .iter()
+        .any(|event| matches!(event, Event::ProposalExecuted { id: executed } if *executed == id)));
+
+    act_as(DAVE);
+    assert!(
+        app.approve_proposal(id).is_err(),
+        "approving a closed proposal"
+    );
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_nothing_settles_while_the_proxy_threshold_differs() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    assert!(app.sync_num_approvals(0).is_err());
+    assert!(app.sync_num_approvals(PROXY_APPROVALS).unwrap());
+    // The proxy was reconfigured directly.
+    assert!(!app.sync_num_approvals(2).unwrap());
+
+    act_as(ALICE);
+    let held = app
+        .create_new_proposal(CreateProposalRequest::SetContextValue {
+            key: "color".to_owned(),
+            value: "blue".to_owned(),
+        })
+        .unwrap();
+    for approver in [BOB, CAROL] {
+        act_as(approver);
+        app.approve_proposal(held).unwrap();
+    }
+    assert_eq!(
+        app.get_proposal(held).unwrap().status,
+        ProposalStatus::Pending
+    );
+    assert!(logs().iter().any(|line| line.contains("not settling")));
+
+    // Aligning the app needs enough approvals for both thresholds.
+    act_as(ALICE);
+    let align = app.create_new_proposal(set_num_approvals(2)).unwrap();
+    act_as(BOB);
+    app.approve_proposal(align).unwrap();
+    assert_eq!(
+        app.get_proposal(align).unwrap().status,
+        ProposalStatus::Pending
+    );
+    act_as(CAROL);
+    app.approve_proposal(align).unwrap();
+    assert_eq!(
+        app.get_proposal(align).unwrap().status,
+        ProposalStatus::Executed
+    );
+    assert_eq!(app.num_approvals, 2);
+    assert_eq!(
+        app.get_proposal(held).unwrap().status,
+        ProposalStatus::Executed
+    );
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_proposal_is_recorded_for_its_creator() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    let alice = act_as(ALICE);
+
+    let id = app.create_new_proposal(set_num_approvals(2)).unwrap();
+
+    assert_eq!(outbox()[0].id, id);
+    assert!(logs().iter().any(|line| line.contains("SetNumApprovals")));
+    let record = app.get_proposal(id).unwrap();
+    assert_eq!(record.creator, alice);
+    assert_eq!(record.approvers, vec![alice]);
+    assert_eq!(record.status, ProposalStatus::Pending);
+    assert!(matches!(
+        take_events().as_slice(),
+        [Event::ProposalCreated { id: created }] if *created == id
+    ));
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_delete_proposal_marks_target_deleted() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    let target = app.create_new_proposal(set_num_approvals(2)).unwrap();
+    let delete = app
+        .create_new_proposal(CreateProposalRequest::DeleteProposal {
+            proposal_id: target,
+        })
+        .unwrap();
+
+    for approver in [BOB, CAROL] {
+        act_as(approver);
+        app.approve_proposal(delete).unwrap();
+    }
+
+    assert_eq!(
+        app.get_proposal(target).unwrap().status,
+        ProposalStatus::Deleted
+    );
+    assert_eq!(app.num_approvals, PROXY_APPROVALS);
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_messages_get_id_author_and_time_from_the_app() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    let id = app.create_new_proposal(set_num_approvals(2)).unwrap();
+
+    let bob = act_as(BOB);
+    let first = app.send_proposal_messages(id, "hi".to_owned()).unwrap();
+    advance_time(5);
+    let second = app.send_proposal_messages(id, "again".to_owned()).unwrap();
+
+    assert_eq!(
+        first,
+        Message::new(0, id, bob.clone(), "hi".to_owned(), START_TIME)
+    );
+    assert_eq!(
+        (second.id, second.author, second.created_at),
+        (1, bob, START_TIME + 5)
+    );
+
+    assert!(
+        app.send_proposal_messages(ProposalId([9; 32]), "hi".to_owned())
+            .is_err(),
+        "unknown proposal"
+    );
+    assert!(app.send_proposal_messages(id, " ".to_owned()).is_err());
+    assert!(app
+        .send_proposal_messages(id, "x".repeat(MAX_MESSAGE_BYTES + 1))
+        .is_err());
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_only_the_author_edits_or_deletes_a_message() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    let id = app.create_new_proposal(set_num_approvals(2)).unwrap();
+    app.send_proposal_messages(id, "first".to_owned()).unwrap();
+    app.send_proposal_messages(id, "second".to_owned()).unwrap();
+
+    act_as(BOB);
+    assert!(app
+        .edit_proposal_message(id, 0, "forged".to_owned())
+        .is_err());
+    assert!(app.delete_proposal_message(id, 0).is_err());
+
+    act_as(ALICE);
+    advance_time(7);
+    let edited = app
+        .edit_proposal_message(id, 0, "fixed".to_owned())
+        .unwrap();
+    assert_eq!(edited.text, "fixed");
+    assert_eq!(edited.edited_at, Some(START_TIME + 7));
+
+    let deleted = app.delete_proposal_message(id, 0).unwrap();
+    assert!(deleted.deleted && deleted.text.is_empty());
+    assert!(app.edit_proposal_message(id, 0, "back".to_owned()).is_err());
+    assert!(app.delete_proposal_message(id, 5).is_err());
+
+    let page = app.get_proposal_messages(id, None, None).unwrap();
+    assert_eq!(page.messages[0], deleted);
+    assert_eq!(page.messages[1].text, "second");
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_messages_are_paginated_by_id() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    let id = app.create_new_proposal(set_num_approvals(2)).unwrap();
+    for n in 0..5 {
+        app.send_proposal_messages(id, format!("message {}", n))
+            .unwrap();
+    }
+
+    let mut ids = Vec::new();
+    let mut from = None;
+    loop {
+        let page = app.get_proposal_messages(id, from, Some(2)).unwrap();
+        assert!(page.messages.len() <= 2);
+        ids.extend(page.messages.iter().map(|message| message.id));
+        match page.next {
+            Some(next) => from = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(ids, [0, 1, 2, 3, 4]);
+
+    let all = app.get_proposal_messages(id, None, Some(0)).unwrap();
+    assert_eq!(all.messages.len(), 1, "page size is at least 1");
+    assert_eq!(all.next, Some(1));
+}
+
+// ---------------- Orders ----------------
+
+#[cfg(feature = "marketplace")]
+fn price(amount: u128) -> Price {
+    Price {
+        amount: U128(amount),
+        token: NATIVE_TOKEN.to_owned(),
+    }
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_paid_order_grants_access() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    app.upload_file("data.csv".to_owned(), "1,2,3".to_owned(), None)
+        .unwrap();
+    app.set_payout_account("alice-account".to_owned()).unwrap();
+    app.set_listing_price("data.csv".to_owned(), Some(price(10)))
+        .unwrap();
+
+    let bob = act_as(BOB);
+    let order = app.open_order("data.csv".to_owned()).unwrap();
+    assert_eq!(order.status, OrderStatus::Pending);
+    assert_eq!(
+        outbox().last().unwrap().actions,
+        [CreateProposalRequest::Transfer {
+            receiver_id: "alice-account".to_owned(),
+            amount: U128(10),
+        }]
+    );
+    assert!(logs().iter().any(|line| line.contains("alice-account")));
+    assert!(!app.has_access("data.csv".to_owned()).unwrap());
+
+    for approver in [CAROL, DAVE] {
+        act_as(approver);
+        app.approve_proposal(order.proposal_id).unwrap();
+    }
+
+    act_as(BOB);
+    assert_eq!(app.get_order(order.id).unwrap().status, OrderStatus::Paid);
+    assert!(app.has_access("data.csv".to_owned()).unwrap());
+    assert_eq!(
+        app.download_file("data.csv".to_owned()).unwrap().content,
+        "1,2,3"
+    );
+    assert!(matches!(
+        app.list_grants("data.csv".to_owned()),
+        Err(FileExchangeError::Unauthorized)
+    ));
+
+    act_as(ALICE);
+    let grants = app.list_grants("data.csv".to_owned()).unwrap().grants;
+    assert_eq!(grants.len(), 1);
+    assert_eq!(grants[0].reader, bob);
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_expired_grant_denies_access() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    app.upload_file("data.csv".to_owned(), "1,2,3".to_owned(), None)
+        .unwrap();
+    let bob = act_as(BOB);
+    act_as(ALICE);
+    app.grant_access("data.csv".to_owned(), bob, Some(host::time_now() + 10))
+        .unwrap();
+
+    act_as(BOB);
+    assert!(app.has_access("data.csv".to_owned()).unwrap());
+    advance_time(10);
+    assert!(!app.has_access("data.csv".to_owned()).unwrap());
+}
+
+#[cfg(feature = "marketplace")]
+fn open_data_order(app: &mut FileExchangeState) -> Order {
+    act_as(ALICE);
+    app.upload_file("data.csv".to_owned(), "1,2,3".to_owned(), None)
+        .unwrap();
+    app.set_payout_account("alice-account".to_owned()).unwrap();
+    app.set_listing_price("data.csv".to_owned(), Some(price(10)))
+        .unwrap();
+
+    act_as(BOB);
+    app.open_order("data.csv".to_owned()).unwrap()
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_cancelled_order_never_grants_access() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    let order = open_data_order(&mut app);
+
+    act_as(ALICE);
+    assert!(
+        matches!(
+            app.cancel_order(order.id),
+            Err(FileExchangeError::Unauthorized)
+        ),
+        "only the buyer authored the payment"
+    );
+
+    act_as(BOB);
+    let cancelled = app.cancel_order(order.id).unwrap();
+    assert_eq!(cancelled.status, OrderStatus::Cancelled);
+    let delete = outbox().last().unwrap().clone();
+    assert_eq!(delete.proposer, [BOB; 32]);
+    assert_eq!(
+        delete.actions,
+        [CreateProposalRequest::DeleteProposal {
+            proposal_id: order.proposal_id
+        }]
+    );
+    assert_eq!(
+        app.get_proposal(order.proposal_id).unwrap().status,
+        ProposalStatus::Cancelled
+    );
+
+    take_events();
+    for approver in [CAROL, DAVE] {
+        act_as(approver);
+        assert!(app.approve_proposal(order.proposal_id).is_err());
+    }
+    assert!(approvals().is_empty(), "no approval reached the proxy");
+    assert!(take_events().is_empty());
+
+    act_as(BOB);
+    assert_eq!(
+        app.get_order(order.id).unwrap().status,
+        OrderStatus::Cancelled
+    );
+    assert!(!app.has_access("data.csv".to_owned()).unwrap());
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_expired_order_withdraws_its_payment() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    let order = open_data_order(&mut app);
+
+    act_as(CAROL);
+    assert!(app.expire_orders().unwrap().is_empty());
+    advance_time(order.expires_at - host::time_now());
+    assert!(
+        app.approve_proposal(order.proposal_id).is_err(),
+        "past its deadline the payment cannot be approved"
+    );
+
+    assert_eq!(app.expire_orders().unwrap(), [order.id]);
+    assert!(app.expire_orders().unwrap().is_empty());
+
+    act_as(BOB);
+    assert_eq!(
+        app.get_order(order.id).unwrap().status,
+        OrderStatus::Expired
+    );
+    assert_eq!(
+        app.get_proposal(order.proposal_id).unwrap().status,
+        ProposalStatus::Cancelled
+    );
+
+    // Only the buyer deletes their proposal outright; Carol's request
+    // goes to the members.
+    let delete = outbox().last().unwrap().clone();
+    assert_eq!(delete.proposer, [CAROL; 32]);
+    assert_eq!(
+        delete.actions,
+        [CreateProposalRequest::DeleteProposal {
+            proposal_id: order.proposal_id
+        }]
+    );
+    assert_eq!(
+        app.get_proposal(delete.id).unwrap().status,
+        ProposalStatus::Pending
+    );
+
+    act_as(DAVE);
+    assert!(app.approve_proposal(order.proposal_id).is_err());
+    assert!(approvals().is_empty());
+    act_as(BOB);
+    assert!(!app.has_access("data.csv".to_owned()).unwrap());
+}
+
+// ---------------- Listing Terms ----------------
+
+#[cfg(feature = "marketplace")]
+fn set_value(app: &mut FileExchangeState, key: &str, value: &str) {
+    act_as(ALICE);
+    let id = app
+        .create_new_proposal(CreateProposalRequest::SetContextValue {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        })
+        .unwrap();
+    for approver in [BOB, CAROL] {
+        act_as(approver);
+        app.approve_proposal(id).unwrap();
+    }
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_purchase_splits_the_marketplace_fee() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    set_value(&mut app, MARKETPLACE_FEE_KEY, "250");
+    set_value(&mut app, MARKETPLACE_FEE_ACCOUNT_KEY, "market-account");
+    assert_eq!(
+        app.get_marketplace_fee(),
+        MarketplaceFee {
+            fee_bps: 250,
+            fee_account: Some("market-account".to_owned()),
+        }
+    );
+
+    act_as(ALICE);
+    app.upload_file("data.csv".to_owned(), "1,2,3".to_owned(), None)
+        .unwrap();
+    app.set_payout_account("alice-account".to_owned()).unwrap();
+    app.set_listing_price("data.csv".to_owned(), Some(price(1_001)))
+        .unwrap();
+    let license = License {
+        kind: LicenseKind::Research,
+        allowed_uses: AllowedUses {
+            model_training: true,
+            ..AllowedUses::default()
+        },
+    };
+    app.set_listing_license("data.csv".to_owned(), license)
+        .unwrap();
+
+    act_as(BOB);
+    let order = app.open_order("data.csv".to_owned()).unwrap();
+    assert_eq!((order.amount, order.fee), (U128(1_001), U128(25)));
+    assert_eq!(order.token, NATIVE_TOKEN);
+    assert_eq!(order.license, license);
+    assert_eq!(
+        outbox().last().unwrap().actions,
+        [
+            CreateProposalRequest::Transfer {
+                receiver_id: "alice-account".to_owned(),
+                amount: U128(976),
+            },
+            CreateProposalRequest::Transfer {
+                receiver_id: "market-account".to_owned(),
+                amount: U128(25),
+            },
+        ]
+    );
+
+    // Changing the terms later does not touch the order.
+    act_as(ALICE);
+    app.set_listing_license("data.csv".to_owned(), License::default())
+        .unwrap();
+    assert_eq!(app.get_order(order.id).unwrap().license, license);
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_unpriced_listing_is_not_for_sale() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    app.upload_file("data.csv".to_owned(), "1,2,3".to_owned(), None)
+        .unwrap();
+    app.set_payout_account("alice-account".to_owned()).unwrap();
+    assert!(app
+        .set_listing_price(
+            "data.csv".to_owned(),
+            Some(Price {
+                amount: U128(0),
+                token: NATIVE_TOKEN.to_owned(),
+            }),
+        )
+        .is_err());
+
+    act_as(BOB);
+    assert!(matches!(
+        app.open_order("data.csv".to_owned()),
+        Err(FileExchangeError::ListingNotForSale)
+    ));
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_prices_are_in_the_native_token() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    app.upload_file("data.csv".to_owned(), "1,2,3".to_owned(), None)
+        .unwrap();
+    app.set_payout_account("alice-account".to_owned()).unwrap();
+    let near = Price {
+        amount: U128(10),
+        token: "NEAR".to_owned(),
+    };
+    assert!(matches!(
+        app.set_listing_price("data.csv".to_owned(), Some(near.clone())),
+        Err(FileExchangeError::InvalidListing(_))
+    ));
+
+    // A price stored before tokens were checked is never charged.
+    app.update_listing("data.csv".to_owned(), |meta| meta.price = Some(near))
+        .unwrap();
+    act_as(BOB);
+    assert!(matches!(
+        app.open_order("data.csv".to_owned()),
+        Err(FileExchangeError::UnsupportedToken(token)) if token == "NEAR"
+    ));
+    assert!(outbox().is_empty());
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_fee_setting_must_be_valid() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+
+    for (key, value) in [
+        (MARKETPLACE_FEE_KEY, "2.5"),
+        (MARKETPLACE_FEE_KEY, "10001"),
+        (MARKETPLACE_FEE_ACCOUNT_KEY, " "),
+    ] {
+        assert!(app
+            .create_new_proposal(CreateProposalRequest::SetContextValue {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            })
+            .is_err());
+    }
+}
+
+// ---------------- Subscriptions ----------------
+
+#[cfg(feature = "marketplace")]
+const PERIOD: u64 = 30 * 24 * 60 * 60 * 1_000_000_000;
+
+/// Alice sells "vectors" but not "extra" through a monthly plan.
+#[cfg(feature = "marketplace")]
+fn subscription_market() -> (FileExchangeState, Plan) {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    for name in ["vectors", "extra"] {
+        app.upload_dataset(
+            name.to_owned(),
+            "test-model".to_owned(),
+            2,
+            DistanceMetric::Cosine,
+            vec![chunk("east", vec![1.0, 0.0])],
+            None,
+        )
+        .unwrap();
+    }
+    app.set_payout_account("alice-account".to_owned()).unwrap();
+    let plan = app
+        .create_plan(
+            "monthly".to_owned(),
+            PERIOD,
+            price(30),
+            vec!["vectors".to_owned()],
+            None,
+        )
+        .unwrap();
+    (app, plan)
+}
+
+/// Approves the order's Transfer until it executes.
+#[cfg(feature = "marketplace")]
+fn pay(app: &mut FileExchangeState, order: &Order) {
+    for approver in [CAROL, DAVE] {
+        act_as(approver);
+        app.approve_proposal(order.proposal_id).unwrap();
+    }
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_subscription_covers_its_datasets_for_one_period() {
+    let (mut app, plan) = subscription_market();
+
+    act_as(BOB);
+    let order = app.subscribe(plan.id).unwrap();
+    assert_eq!(order.item, OrderItem::Subscription(plan.id));
+    assert!(!app.has_access("vectors".to_owned()).unwrap());
+    take_events();
+
+    pay(&mut app, &order);
+    let bob = act_as(BOB);
+    assert!(take_events().iter().any(|event| matches!(
+        event,
+        Event::SubscriptionStarted { subscriber, expires_at, .. }
+            if *subscriber == bob && *expires_at == START_TIME + PERIOD
+    )));
+    assert!(app.has_access("vectors".to_owned()).unwrap());
+    assert!(!app.has_access("extra".to_owned()).unwrap());
+    assert!(matches!(
+        app.subscribe(plan.id),
+        Err(FileExchangeError::AlreadySubscribed)
+    ));
+
+    advance_time(PERIOD);
+    assert!(!app.has_access("vectors".to_owned()).unwrap());
+
+    let lapsed = app.expire_subscriptions().unwrap();
+    assert_eq!(lapsed.len(), 1);
+    assert!(lapsed[0].lapsed);
+    assert!(matches!(
+        take_events().as_slice(),
+        [Event::SubscriptionExpired { subscriber, .. }] if *subscriber == bob
+    ));
+    assert!(app.expire_subscriptions().unwrap().is_empty());
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_renewal_extends_the_window() {
+    let (mut app, plan) = subscription_market();
+
+    act_as(BOB);
+    assert!(matches!(
+        app.renew_subscription(plan.id),
+        Err(FileExchangeError::SubscriptionNotFound)
+    ));
+    let order = app.subscribe(plan.id).unwrap();
+    pay(&mut app, &order);
+
+    // Renewing early adds the period to the end of the current window.
+    advance_time(PERIOD / 2);
+    act_as(BOB);
+    let order = app.renew_subscription(plan.id).unwrap();
+    pay(&mut app, &order);
+    act_as(BOB);
+    let subscription = &app.list_subscriptions().unwrap()[0];
+    assert_eq!(subscription.expires_at, START_TIME + 2 * PERIOD);
+    assert_eq!(subscription.renewals, 1);
+
+    advance_time(PERIOD);
+    assert!(app.has_access("vectors".to_owned()).unwrap());
+
+    // Renewing after the lapse starts a new window.
+    advance_time(PERIOD);
+    assert!(!app.has_access("vectors".to_owned()).unwrap());
+    let order = app.renew_subscription(plan.id).unwrap();
+    pay(&mut app, &order);
+    act_as(BOB);
+    let now = host::time_now();
+    let subscription = &app.list_subscriptions().unwrap()[0];
+    assert_eq!(
+        (subscription.started_at, subscription.expires_at),
+        (now, now + PERIOD)
+    );
+    assert!(app.has_access("vectors".to_owned()).unwrap());
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_retired_plan_keeps_paid_windows() {
+    let (mut app, plan) = subscription_market();
+
+    act_as(BOB);
+    assert!(matches!(
+        app.create_plan(
+            "stolen".to_owned(),
+            PERIOD,
+            price(1),
+            vec!["vectors".to_owned()],
+            None
+        ),
+        Err(FileExchangeError::Unauthorized)
+    ));
+    let order = app.subscribe(plan.id).unwrap();
+    pay(&mut app, &order);
+
+    act_as(ALICE);
+    app.retire_plan(plan.id).unwrap();
+    assert!(app.list_plans(None).unwrap().is_empty());
+
+    act_as(BOB);
+    assert!(app.has_access("vectors".to_owned()).unwrap());
+    assert!(matches!(
+        app.renew_subscription(plan.id),
+        Err(FileExchangeError::PlanRetired)
+    ));
+    act_as(CAROL);
+    assert!(matches!(
+        app.subscribe(plan.id),
+        Err(FileExchangeError::PlanRetired)
+    ));
+}
+
+// ---------------- Credit ----------------
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_searches_are_paid_from_credit() {
+    let (mut app, _) = subscription_market();
+    let alice = act_as(ALICE);
+    app.set_query_price("vectors".to_owned(), Some(U128(3)))
+        .unwrap();
+    let query = || vec![1.0, 0.0];
+
+    act_as(BOB);
+    assert!(matches!(
+        app.deposit_credit(U128(5)),
+        Err(FileExchangeError::MarketplaceAccountMissing)
+    ));
+    assert!(matches!(
+        app.search("extra".to_owned(), query(), 1, None),
+        Err(FileExchangeError::Unauthorized)
+    ));
+    assert!(matches!(
+        app.search("vectors".to_owned(), query(), 1, None),
+        Err(FileExchangeError::InsufficientCredit {
+            balance: U128(0),
+            price: U128(3),
+        })
+    ));
+
+    set_value(&mut app, MARKETPLACE_FEE_ACCOUNT_KEY, "market-account");
+    act_as(BOB);
+    let order = app.deposit_credit(U128(5)).unwrap();
+    assert_eq!(
+        outbox().last().unwrap().actions,
+        [CreateProposalRequest::SetContextValue {
+            key: format!("getem.credit_deposit.{}", order.id),
+            value: format!("{} 5", order.buyer),
+        }]
+    );
+    assert_eq!(app.get_credit_balance().unwrap(), U128(0));
+    pay(&mut app, &order);
+
+    act_as(BOB);
+    assert_eq!(app.get_credit_balance().unwrap(), U128(5));
+    assert_eq!(
+        app.search("vectors".to_owned(), query(), 1, None)
+            .unwrap()
+            .len(),
+        1
+    );
+    // A query the node rejects costs nothing.
+    assert!(app
+        .search("vectors".to_owned(), vec![1.0], 1, None)
+        .is_err());
+    assert!(matches!(
+        app.search("vectors".to_owned(), query(), 1, None),
+        Err(FileExchangeError::InsufficientCredit {
+            balance: U128(2),
+            price: U128(3),
+        })
+    ));
+
+    let statement = app.get_statement(None, Some(1)).unwrap();
+    assert_eq!(statement.balance, U128(2));
+    assert_eq!(
+        statement.entries[0].kind,
+        EntryKind::Deposit { order_id: order.id }
+    );
+    assert_eq!(statement.next, Some(1));
+    let statement = app.get_statement(statement.next, None).unwrap();
+    assert_eq!(
+        statement.entries[0].kind,
+        EntryKind::QueryCharge {
+            dataset: "vectors".to_owned(),
+            seller: alice,
+        }
+    );
+    assert_eq!(statement.entries[0].balance, U128(2));
+    assert_eq!(statement.next, None);
+
+    act_as(ALICE);
+    let income = app.get_statement(None, None).unwrap();
+    assert_eq!(income.balance, U128(3));
+    assert!(matches!(
+        income.entries.as_slice(),
+        [LedgerEntry {
+            kind: EntryKind::QueryIncome { .. },
+            amount: U128(3),
+            ..
+        }]
+    ));
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_query_income_is_withdrawn_to_the_payout_account() {
+    let (mut app, _) = subscription_market();
+    let alice = act_as(ALICE);
+    app.set_query_price("vectors".to_owned(), Some(U128(3)))
+        .unwrap();
+    assert!(matches!(
+        app.withdraw_credit(U128(1)),
+        Err(FileExchangeError::MarketplaceAccountMissing)
+    ));
+    set_value(&mut app, MARKETPLACE_FEE_ACCOUNT_KEY, "market-account");
+    act_as(BOB);
+    let deposit = app.deposit_credit(U128(3)).unwrap();
+    pay(&mut app, &deposit);
+    act_as(BOB);
+    app.search("vectors".to_owned(), vec![1.0, 0.0], 1, None)
+        .unwrap();
+
+    act_as(ALICE);
+    assert!(matches!(
+        app.withdraw_credit(U128(4)),
+        Err(FileExchangeError::InsufficientCredit {
+            balance: U128(3),
+            price: U128(4),
+        })
+    ));
+    let order = app.withdraw_credit(U128(2)).unwrap();
+    assert_eq!(order.item, OrderItem::CreditWithdrawal);
+    assert_eq!(order.seller, "market-account");
+    assert_eq!(
+        outbox().last().unwrap().actions,
+        [CreateProposalRequest::Transfer {
+            receiver_id: "alice-account".to_owned(),
+            amount: U128(2),
+        }]
+    );
+    assert_eq!(app.get_credit_balance().unwrap(), U128(1));
+
+    // A withdrawal that never pays out gives the credit back.
+    app.cancel_order(order.id).unwrap();
+    assert_eq!(app.get_credit_balance().unwrap(), U128(3));
+    let statement = app.get_statement(None, None).unwrap();
+    assert_eq!(
+        statement.entries.last().unwrap().kind,
+        EntryKind::WithdrawalReturned { order_id: order.id }
+    );
+
+    let order = app.withdraw_credit(U128(3)).unwrap();
+    take_events();
+    pay(&mut app, &order);
+    assert!(take_events().iter().any(|event| matches!(
+        event,
+        Event::CreditWithdrawn { account, amount: U128(3) } if *account == alice
+    )));
+    act_as(ALICE);
+    assert_eq!(app.get_order(order.id).unwrap().status, OrderStatus::Paid);
+    assert_eq!(app.get_credit_balance().unwrap(), U128(0));
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_credit_is_paid_out_of_the_treasury_once() {
+    let (mut app, _) = subscription_market();
+    set_value(&mut app, MARKETPLACE_FEE_ACCOUNT_KEY, "market-account");
+    act_as(BOB);
+    app.set_payout_account("bob-account".to_owned()).unwrap();
+
+    let deposit = app.deposit_credit(U128(5)).unwrap();
+    pay(&mut app, &deposit);
+    act_as(BOB);
+    let withdrawal = app.withdraw_credit(U128(5)).unwrap();
+    pay(&mut app, &withdrawal);
+
+    // What the executed proposals moved out of the proxy, by receiver.
+    let mut received: BTreeMap<String, u128> = BTreeMap::new();
+    for sent in outbox() {
+        if app.get_proposal(sent.id).unwrap().status != ProposalStatus::Executed {
+            continue;
+        }
+        for action in sent.actions {
+            if let CreateProposalRequest::Transfer {
+                receiver_id,
+                amount,
+            } = action
+            {
+                *received.entry(receiver_id).or_default() += amount.0;
+            }
+        }
+    }
+    assert_eq!(received, BTreeMap::from([("bob-account".to_owned(), 5)]));
+    act_as(BOB);
+    assert_eq!(app.get_credit_balance().unwrap(), U128(0));
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_credit_overflow_is_not_reported_as_insufficient() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    let bob = act_as(BOB);
+    let deposit = |order_id| EntryKind::Deposit { order_id };
+
+    app.post_entry(&bob, deposit(0), U128(u128::MAX)).unwrap();
+    assert!(matches!(
+        app.post_entry(&bob, deposit(1), U128(1)),
+        Err(FileExchangeError::CreditOverflow)
+    ));
+    assert_eq!(app.get_credit_balance().unwrap(), U128(u128::MAX));
+}
+
+// ---------------- Reviews ----------------
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_only_verified_buyers_review_once() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    app.upload_file("data.csv".to_owned(), "1,2,3".to_owned(), None)
+        .unwrap();
+    app.set_payout_account("alice-account".to_owned()).unwrap();
+    app.set_listing_price("data.csv".to_owned(), Some(price(10)))
+        .unwrap();
+    let review = |app: &mut FileExchangeState, rating| {
+        app.submit_review("data.csv".to_owned(), rating, "useful".to_owned())
+    };
+
+    let bob = act_as(BOB);
+    assert!(matches!(
+        review(&mut app, 5),
+        Err(FileExchangeError::NotAVerifiedBuyer)
+    ));
+    let order = app.open_order("data.csv".to_owned()).unwrap();
+    assert!(matches!(
+        review(&mut app, 5),
+        Err(FileExchangeError::NotAVerifiedBuyer)
+    ));
+    pay(&mut app, &order);
+
+    act_as(BOB);
+    assert!(matches!(
+        review(&mut app, 6),
+        Err(FileExchangeError::InvalidReview(_))
+    ));
+    review(&mut app, 5).unwrap();
+    assert!(matches!(
+        review(&mut app, 1),
+        Err(FileExchangeError::AlreadyReviewed)
+    ));
+
+    // A grant counts even once it has expired.
+    let carol = act_as(CAROL);
+    act_as(ALICE);
+    assert!(matches!(
+        review(&mut app, 5),
+        Err(FileExchangeError::InvalidOperation)
+    ));
+    app.grant_access("data.csv".to_owned(), carol, Some(host::time_now() + 1))
+        .unwrap();
+    advance_time(1);
+    act_as(CAROL);
+    review(&mut app, 2).unwrap();
+
+    let summary = app.get_listing("data.csv".to_owned()).unwrap();
+    assert_eq!((summary.rating, summary.review_count), (Some(3.5), 2));
+
+    act_as(BOB);
+    assert!(matches!(
+        app.reply_to_review("data.csv".to_owned(), bob.clone(), "thanks".to_owned()),
+        Err(FileExchangeError::Unauthorized)
+    ));
+    act_as(ALICE);
+    let replied = app
+        .reply_to_review("data.csv".to_owned(), bob.clone(), "thanks".to_owned())
+        .unwrap();
+    assert_eq!(replied.reply.unwrap().text, "thanks");
+    assert!(matches!(
+        app.reply_to_review("data.csv".to_owned(), bob.clone(), "again".to_owned()),
+        Err(FileExchangeError::AlreadyReplied)
+    ));
+
+    let reviews = app.list_reviews("data.csv".to_owned()).unwrap();
+    assert_eq!(reviews.len(), 2);
+    assert_eq!(reviews[0].reviewer, bob);
+    assert!(reviews[0].reply.is_some());
+    assert_eq!(reviews[1].rating, 2);
+}
+
+// ---------------- Datasets ----------------
+
+#[cfg(feature = "files")]
+fn chunk(id: &str, vector: Vec<f32>) -> EmbeddingChunk {
+    EmbeddingChunk {
+        chunk_id: id.to_owned(),
+        metadata: Default::default(),
+        vector,
+        sealed: None,
+    }
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_search_ranks_by_similarity() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    app.upload_dataset(
+        "vectors".to_owned(),
+        "test-model".to_owned(),
+        2,
+        DistanceMetric::Cosine,
+        vec![
+            chunk("east", vec![1.0, 0.0]),
+            chunk("north", vec![0.0, 1.0]),
+            chunk("north-east", vec![1.0, 1.0]),
+        ],
+        None,
+    )
+    .unwrap();
+
+    let hits = app
+        .search("vectors".to_owned(), vec![0.1, 1.0], 2, None)
+        .unwrap();
+    let ids: Vec<&str> = hits.iter().map(|hit| hit.chunk_id.as_str()).collect();
+    assert_eq!(ids, ["north", "north-east"]);
+
+    assert!(matches!(
+        app.upload_dataset(
+            "bad".to_owned(),
+            "test-model".to_owned(),
+            2,
+            DistanceMetric::Cosine,
+            vec![chunk("short", vec![1.0])],
+            None,
+        ),
+        Err(FileExchangeError::InvalidDataset(_))
+    ));
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_duplicate_chunk_ids_are_rejected() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    assert!(matches!(
+        app.upload_dataset(
+            "vectors".to_owned(),
+            "test-model".to_owned(),
+            2,
+            DistanceMetric::Cosine,
+            vec![chunk("east", vec![1.0, 0.0]), chunk("east", vec![0.0, 1.0])],
+            None,
+        ),
+        Err(FileExchangeError::InvalidDataset(_))
+    ));
+
+    app.upload_dataset(
+        "vectors".to_owned(),
+        "test-model".to_owned(),
+        2,
+        DistanceMetric::Cosine,
+        vec![chunk("east", vec![1.0, 0.0])],
+        None,
+    )
+    .unwrap();
+    assert!(matches!(
+        app.append_dataset_chunks(
+            "vectors".to_owned(),
+            vec![chunk("north", vec![0.0, 1.0]), chunk("east", vec![1.0, 1.0])],
+        ),
+        Err(FileExchangeError::InvalidDataset(message)) if message == "duplicate chunk_id east"
+    ));
+    let info = app
+        .append_dataset_chunks("vectors".to_owned(), vec![chunk("north", vec![0.0, 1.0])])
+        .unwrap();
+    assert_eq!(info.chunk_count, 2);
+}
+
+// ---------------- Proposal Lifecycle ----------------
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_creator_can_cancel_pending_proposal() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    let id = app.create_new_proposal(set_num_approvals(2)).unwrap();
+    app.send_proposal_messages(id, "m1".to_owned()).unwrap();
+
+    act_as(BOB);
+    assert!(app.cancel_proposal(id).is_err(), "only the creator cancels");
+
+    act_as(ALICE);
+    let record = app.cancel_proposal(id).unwrap();
+    assert_eq!(record.status, ProposalStatus::Cancelled);
+    assert_eq!(
+        outbox().last().unwrap().actions,
+        [CreateProposalRequest::DeleteProposal { proposal_id: id }]
+    );
+    assert!(app
+        .get_proposal_messages(id, None, None)
+        .unwrap()
+        .messages
+        .is_empty());
+    assert!(app.send_proposal_messages(id, "m2".to_owned()).is_err());
+    assert!(app.cancel_proposal(id).is_err(), "already cancelled");
+
+    act_as(BOB);
+    assert!(app.approve_proposal(id).is_err());
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_cancelling_an_order_proposal_cancels_the_order() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    app.upload_file("data.csv".to_owned(), "1,2,3".to_owned(), None)
+        .unwrap();
+    app.set_payout_account("alice-account".to_owned()).unwrap();
+    app.set_listing_price("data.csv".to_owned(), Some(price(10)))
+        .unwrap();
+
+    act_as(BOB);
+    let order = app.open_order("data.csv".to_owned()).unwrap();
+    app.cancel_proposal(order.proposal_id).unwrap();
+
+    assert_eq!(
+        app.get_order(order.id).unwrap().status,
+        OrderStatus::Cancelled
+    );
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_executed_proposal_thread_is_deleted_or_archived() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+
+    let archive = app
+        .create_new_proposal(CreateProposalRequest::SetContextValue {
+            key: ARCHIVE_MESSAGES_KEY.to_owned(),
+            value: "true".to_owned(),
+        })
+        .unwrap();
+    let deleted = app.create_new_proposal(set_num_approvals(3)).unwrap();
+    app.send_proposal_messages(deleted, "m1".to_owned())
+        .unwrap();
+
+    // Executed before archiving is on: the thread is deleted.
+    for approver in [BOB, CAROL] {
+        act_as(approver);
+        app.approve_proposal(deleted).unwrap();
+    }
+    assert!(app
+        .get_proposal_messages(deleted, None, None)
+        .unwrap()
+        .messages
+        .is_empty());
+    assert!(!app.archive_messages);
+
+    for approver in [BOB, CAROL] {
+        act_as(approver);
+        app.approve_proposal(archive).unwrap();
+    }
+    assert!(app.archive_messages);
+
+    act_as(ALICE);
+    let kept = app.create_new_proposal(set_num_approvals(3)).unwrap();
+    let note = app.send_proposal_messages(kept, "m2".to_owned()).unwrap();
+    for approver in [BOB, CAROL] {
+        act_as(approver);
+        app.approve_proposal(kept).unwrap();
+    }
+
+    assert!(app.proposal_messages.get(&kept).unwrap().is_none());
+    assert_eq!(
+        app.get_proposal_messages(kept, None, None)
+            .unwrap()
+            .messages,
+        vec![note]
+    );
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_prune_closes_threads_of_finished_proposals() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    let id = app.create_new_proposal(set_num_approvals(2)).unwrap();
+
+    // A thread left behind by a proposal that finished before cleanup.
+    let mut record = app.get_proposal(id).unwrap();
+    record.status = ProposalStatus::Executed;
+    app.proposals.insert(id, record).unwrap();
+
+    assert_eq!(app.prune_proposal_messages().unwrap(), 1);
+    assert_eq!(app.prune_proposal_messages().unwrap(), 0);
+}
+
+#[cfg(feature = "proposals")]
+#[test]
+fn test_archive_flag_must_be_boolean() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+
+    assert!(app
+        .create_new_proposal(CreateProposalRequest::SetContextValue {
+            key: ARCHIVE_MESSAGES_KEY.to_owned(),
+            value: "sometimes".to_owned(),
+        })
+        .is_err());
+}
+
+// ---------------- Catalog ----------------
+
+#[cfg(feature = "marketplace")]
+fn names(page: &ListingPage) -> Vec<&str> {
+    page.listings
+        .iter()
+        .map(|listing| listing.name.as_str())
+        .collect()
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_catalog_filters_and_sorts_listings() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    app.upload_file("notes.txt".to_owned(), "hello".to_owned(), None)
+        .unwrap();
+    advance_time(1);
+    app.upload_dataset(
+        "vectors".to_owned(),
+        "test-model".to_owned(),
+        2,
+        DistanceMetric::Cosine,
+        vec![chunk("a", vec![1.0, 0.0])],
+        None,
+    )
+    .unwrap();
+    app.set_listing_price("vectors".to_owned(), Some(price(50)))
+        .unwrap();
+    app.set_listing_tags(
+        "vectors".to_owned(),
+        vec![" NLP ".to_owned(), "nlp".to_owned()],
+    )
+    .unwrap();
+
+    act_as(BOB);
+    app.upload_file("report.pdf".to_owned(), "pdf".to_owned(), None)
+        .unwrap();
+    app.set_listing_price("report.pdf".to_owned(), Some(price(5)))
+        .unwrap();
+    assert!(matches!(
+        app.set_listing_price("notes.txt".to_owned(), Some(price(1))),
+        Err(FileExchangeError::Unauthorized)
+    ));
+
+    let newest = app.list_listings(None, None, None, None, None).unwrap();
+    assert_eq!(names(&newest), ["report.pdf", "vectors", "notes.txt"]);
+
+    let vectors = &newest.listings[1];
+    assert_eq!(vectors.kind, ListingKind::Dataset);
+    assert_eq!((vectors.chunk_count, vectors.size), (Some(1), None));
+    assert_eq!(vectors.tags, ["nlp"]);
+    assert_eq!(vectors.created_at, START_TIME + 1);
+
+    let by_price = app
+        .list_listings(None, Some(ListingSort::PriceHighToLow), None, None, None)
+        .unwrap();
+    assert_eq!(names(&by_price), ["vectors", "report.pdf", "notes.txt"]);
+
+    let filtered = |filter: ListingFilter| {
+        app.list_listings(Some(filter), Some(ListingSort::Name), None, None, None)
+            .unwrap()
+    };
+    let alice = act_as(ALICE);
+    assert_eq!(
+        names(&filtered(ListingFilter {
+            owner: Some(alice),
+            ..ListingFilter::default()
+        })),
+        ["notes.txt", "vectors"]
+    );
+    assert_eq!(
+        names(&filtered(ListingFilter {
+            tag: Some("NLP".to_owned()),
+            ..ListingFilter::default()
+        })),
+        ["vectors"]
+    );
+    assert!(filtered(ListingFilter {
+        dimension: Some(3),
+        ..ListingFilter::default()
+    })
+    .listings
+    .is_empty());
+}
+
+#[cfg(feature = "marketplace")]
+#[test]
+fn test_catalog_cursor_survives_changes() {
+    let mut app = FileExchangeState::init(PROXY_APPROVALS);
+    act_as(ALICE);
+    for name in ["a", "b", "c", "d"] {
+        app.upload_file(name.to_owned(), name.to_owned(), None)
+            .unwrap();
+    }
+
+    let first = app
+        .list_listings(None, Some(ListingSort::Oldest), None, None, Some(2))
+        .unwrap();
+    assert_eq!(names(&first), ["a", "b"]);
+
+    // Neither a deleted listing before the cursor nor a new one shifts
+    // the next page.
+    app.delete_file("a".to_owned()).unwrap();
+    app.upload_file("e".to_owned(), "e".to_owned(), None)
+        .unwrap();
+
+    let second = app
+        .list_listings(
+            None,
+            Some(ListingSort::Oldest),
+            first.next_cursor,
+            None,
+            Some(2),
+        )
+        .unwrap();
+    assert_eq!(names(&second), ["c", "d"]);
+
+    let last = app
+        .list_listings(
+            None,
+            Some(ListingSort::Oldest),
+            second.next_cursor,
+            None,
+            Some(2),
+        )
+        .unwrap();
+    assert_eq!(names(&last), ["e"]);
+    assert_eq!(last.next_cursor, None);
+
+    assert!(matches!(
+        app.list_listings(None, None, Some("garbage".to_owned()), None, None),
+        Err(FileExchangeError::InvalidCursor)
+    ));
+}
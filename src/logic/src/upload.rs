@@ -0,0 +1,152 @@
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "files")]
+use calimero_sdk::serde::Serialize;
+use calimero_storage::collections::Vector;
+pub use getem_types::args::MAX_CHUNK_BYTES;
+use sha2::{Digest, Sha256};
+
+// ---------------- Transfer Types ----------------
+
+pub type UploadId = u64;
+
+/// How long an upload may go without a chunk before `expire_uploads` drops
+/// it (nanoseconds, matching `env::time_now`).
+#[cfg(feature = "files")]
+pub const UPLOAD_TTL: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// A file being uploaded piece by piece. Chunks are appended in order; a
+/// client that lost track can ask for the status and carry on from
+/// `next_index`. Every chunk extends `expires_at` by `UPLOAD_TTL`.
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "calimero_sdk::borsh")]
+pub struct UploadSession {
+    pub id: UploadId,
+    pub name: String,
+    pub owner: String,
+    pub encrypted: bool,
+    pub chunks: Vector<String>,
+    pub chunk_hashes: Vector<String>,
+    pub next_index: u64,
+    pub received_bytes: u64,
+    pub started_at: u64,
+    pub expires_at: u64,
+}
+
+#[cfg(feature = "files")]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct UploadStatus {
+    pub id: UploadId,
+    pub name: String,
+    pub next_index: u64,
+    pub received_bytes: u64,
+    pub expires_at: u64,
+}
+
+/// One slice of a file. Ranges are in bytes and always end on a character
+/// boundary, so `next_offset` is where the following request should start.
+#[cfg(feature = "files")]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(crate = "calimero_sdk::serde")]
+pub struct ContentRange {
+    pub name: String,
+    pub offset: u64,
+    pub data: String,
+    pub next_offset: u64,
+    pub total_size: u64,
+    pub eof: bool,
+    /// Digest of the whole file, to check once every range is in.
+    pub sha256: String,
+}
+
+#[cfg(feature = "files")]
+impl UploadSession {
+    pub fn status(&self) -> UploadStatus {
+        UploadStatus {
+            id: self.id,
+            name: self.name.clone(),
+            next_index: self.next_index,
+            received_bytes: self.received_bytes,
+            expires_at: self.expires_at,
+        }
+    }
+
+    pub fn is_expired_at(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Splits content into pieces of at most `MAX_CHUNK_BYTES`, never cutting a
+/// character in half.
+pub fn split_content(content: &str) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = content;
+
+    while !rest.is_empty() {
+        let mut end = rest.len().min(MAX_CHUNK_BYTES);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (piece, tail) = rest.split_at(end);
+        pieces.push(piece.to_owned());
+        rest = tail;
+    }
+
+    pieces
+}
+
+/// Collects the bytes `offset..offset + length` out of a chunked file. The
+/// end is moved back to the previous character boundary, but at least one
+/// character is always returned while there is data left.
+#[cfg(feature = "files")]
+pub fn read_range(
+    chunks: impl Iterator<Item = String>,
+    offset: u64,
+    length: u64,
+) -> Option<(String, u64)> {
+    let length = length.min(MAX_CHUNK_BYTES as u64);
+    let end = offset.saturating_add(length);
+
+    let mut data = String::new();
+    let mut chunk_start = 0u64;
+    let mut started = false;
+
+    for chunk in chunks {
+        let chunk_end = chunk_start + chunk.len() as u64;
+        if chunk_end <= offset {
+            chunk_start = chunk_end;
+            continue;
+        }
+        if chunk_start >= end && started {
+            break;
+        }
+
+        let from = offset.saturating_sub(chunk_start) as usize;
+        if !chunk.is_char_boundary(from) {
+            return None;
+        }
+        started = true;
+
+        let mut to = (end.saturating_sub(chunk_start) as usize).min(chunk.len());
+        while !chunk.is_char_boundary(to) {
+            to -= 1;
+        }
+        if to <= from && data.is_empty() {
+            // The requested length is shorter than the next character.
+            to = from + chunk[from..].chars().next().map_or(0, char::len_utf8);
+        }
+
+        data.push_str(&chunk[from..to]);
+        if to < chunk.len() {
+            break;
+        }
+        chunk_start = chunk_end;
+    }
+
+    let next_offset = offset + data.len() as u64;
+    Some((data, next_offset))
+}
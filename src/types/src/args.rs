@@ -0,0 +1,82 @@
+//! Arguments of the proposal and message methods, as clients send them in
+//! `argsJson`. The apps take them as plain parameters; these exist so that
+//! clients build the same objects the methods deserialize.
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::MessageId;
+use crate::proposal::{CreateProposalRequest, ProposalId};
+
+/// Largest piece of file content the app accepts or returns in a single
+/// call, chosen to stay well under the node's JSON-RPC payload limit.
+/// Larger files go through `begin_upload` and `download_range`.
+pub const MAX_CHUNK_BYTES: usize = 256 * 1024;
+
+/// `create_new_proposal`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct CreateNewProposalRequest {
+    pub request: CreateProposalRequest,
+}
+
+/// `approve_proposal`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ApproveProposalRequest {
+    pub proposal_id: ProposalId,
+}
+
+/// `sync_num_approvals`: the threshold read from the context proxy.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SyncNumApprovalsRequest {
+    pub num_approvals: u32,
+}
+
+/// `cancel_proposal`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct CancelProposalRequest {
+    pub proposal_id: ProposalId,
+}
+
+/// `get_proposal_messages`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct GetProposalMessagesRequest {
+    pub proposal_id: ProposalId,
+    /// Message id the page starts at, the `next` of the previous page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(optional, type = "number"))]
+    pub from: Option<MessageId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub limit: Option<u32>,
+}
+
+/// `send_proposal_messages`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct SendProposalMessageRequest {
+    pub proposal_id: ProposalId,
+    pub text: String,
+}
+
+/// `edit_proposal_message`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct EditProposalMessageRequest {
+    pub proposal_id: ProposalId,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub message_id: MessageId,
+    pub text: String,
+}
+
+/// `delete_proposal_message`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct DeleteProposalMessageRequest {
+    pub proposal_id: ProposalId,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub message_id: MessageId,
+}
@@ -0,0 +1,19 @@
+//! Writes the frontend's TypeScript definitions. Run from `src/types`:
+//!
+//! ```sh
+//! cargo run --features ts --bin generate-ts
+//! ```
+
+use std::fs;
+use std::path::Path;
+
+use getem_types::ts::{definitions, OUTPUT};
+
+fn main() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(OUTPUT);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).expect("create the output directory");
+    }
+    fs::write(&path, definitions()).expect("write the definitions");
+    println!("wrote {}", path.display());
+}
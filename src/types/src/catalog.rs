@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+use crate::proposal::U128;
+use crate::terms::{License, Price};
+
+// ---------------- Catalog Types ----------------
+
+/// Listings per page of `list_listings` unless the client asks for fewer,
+/// and the most it may ask for.
+pub const DEFAULT_PAGE_SIZE: u32 = 25;
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum ListingKind {
+    File,
+    Dataset,
+}
+
+/// One row of the catalog. File-only and dataset-only fields are `None`
+/// for the other kind.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ListingSummary {
+    pub name: String,
+    pub kind: ListingKind,
+    pub owner: String,
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub size: Option<u64>,
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub chunk_count: Option<u64>,
+    pub model: Option<String>,
+    pub dimension: Option<u32>,
+    pub price: Option<Price>,
+    pub license: License,
+    /// Credit charged per search for buyers without access. Datasets only.
+    pub query_price: Option<U128>,
+    /// Average review rating, `None` until the first review.
+    pub rating: Option<f32>,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub review_count: u64,
+    pub tags: Vec<String>,
+    pub encrypted: bool,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+    #[serde(skip)]
+    pub seq: u64,
+}
+
+/// Every field that is set must match. `model` and `dimension` only match
+/// datasets.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ListingFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(optional))]
+    pub dimension: Option<u32>,
+}
+
+impl ListingFilter {
+    pub fn matches(&self, listing: &ListingSummary) -> bool {
+        self.owner
+            .as_ref()
+            .map_or(true, |owner| *owner == listing.owner)
+            && self.tag.as_ref().map_or(true, |tag| {
+                listing.tags.contains(&tag.trim().to_lowercase())
+            })
+            && self
+                .model
+                .as_ref()
+                .map_or(true, |model| listing.model.as_ref() == Some(model))
+            && self
+                .dimension
+                .map_or(true, |dimension| listing.dimension == Some(dimension))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum ListingSort {
+    #[default]
+    Newest,
+    Oldest,
+    Name,
+    /// Unpriced listings come last, as do prices in any token but the
+    /// native one, which no purchase can pay.
+    PriceLowToHigh,
+    PriceHighToLow,
+}
+
+/// Where a listing falls in a sort order: listings are ordered by the
+/// number, then by name. Names are unique, so no two listings share a key.
+pub type SortKey = (u128, String);
+
+impl ListingSort {
+    pub fn key(self, listing: &ListingSummary) -> SortKey {
+        let rank = match self {
+            ListingSort::Newest => u128::from(u64::MAX - listing.seq),
+            ListingSort::Oldest => u128::from(listing.seq),
+            ListingSort::Name => 0,
+            ListingSort::PriceLowToHigh => listing
+                .price
+                .as_ref()
+                .filter(|price| price.is_native())
+                .map_or(u128::MAX, |price| price.amount.0),
+            ListingSort::PriceHighToLow => listing
+                .price
+                .as_ref()
+                .filter(|price| price.is_native())
+                .map_or(u128::MAX, |price| u128::MAX - price.amount.0),
+        };
+        (rank, listing.name.clone())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ListingPage {
+    pub listings: Vec<ListingSummary>,
+    /// Pass back as `cursor` for the next page; `None` on the last one.
+    pub next_cursor: Option<String>,
+}
+
+impl ListingPage {
+    /// Clamps the requested page size to `1..=MAX_PAGE_SIZE`.
+    pub fn size(limit: Option<u32>) -> usize {
+        limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as usize
+    }
+}
@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+// ---------------- Dataset Types ----------------
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum DistanceMetric {
+    #[default]
+    Cosine,
+    DotProduct,
+    Euclidean,
+}
+
+impl DistanceMetric {
+    /// Cosine and dot-product are similarities, Euclidean is a distance.
+    pub fn score(self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            DistanceMetric::Cosine => {
+                let norm = norm(a) * norm(b);
+                if norm == 0.0 {
+                    return 0.0;
+                }
+                dot(a, b) / norm
+            }
+            DistanceMetric::DotProduct => dot(a, b),
+            DistanceMetric::Euclidean => a
+                .iter()
+                .zip(b)
+                .map(|(x, y)| (x - y) * (x - y))
+                .sum::<f32>()
+                .sqrt(),
+        }
+    }
+
+    /// Orders scores so that the best match comes first.
+    pub fn rank(self, a: f32, b: f32) -> Ordering {
+        match self {
+            DistanceMetric::Cosine | DistanceMetric::DotProduct => b.total_cmp(&a),
+            DistanceMetric::Euclidean => a.total_cmp(&b),
+        }
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn norm(a: &[f32]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+impl FromStr for DistanceMetric {
+    type Err = UnknownMetric;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "cosine" => Ok(DistanceMetric::Cosine),
+            "dotproduct" | "dot-product" | "dot" => Ok(DistanceMetric::DotProduct),
+            "euclidean" => Ok(DistanceMetric::Euclidean),
+            _ => Err(UnknownMetric(value.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownMetric(pub String);
+
+impl fmt::Display for UnknownMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown metric {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownMetric {}
+
+#[derive(
+    Clone, Debug, Default, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ChunkMetadata {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub text_excerpt: Option<String>,
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct EmbeddingChunk {
+    pub chunk_id: String,
+    #[serde(default)]
+    pub metadata: ChunkMetadata,
+    #[serde(default)]
+    pub vector: Vec<f32>,
+    /// For encrypted datasets: the chunk (metadata and vector) sealed with
+    /// the dataset's content key. `metadata` and `vector` are then empty.
+    #[serde(default)]
+    pub sealed: Option<String>,
+}
+
+impl EmbeddingChunk {
+    /// A plaintext chunk.
+    pub fn new(chunk_id: String, metadata: ChunkMetadata, vector: Vec<f32>) -> Self {
+        EmbeddingChunk {
+            chunk_id,
+            metadata,
+            vector,
+            sealed: None,
+        }
+    }
+}
+
+/// What `list_datasets`/`get_dataset` return: everything but the vectors.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct DatasetInfo {
+    pub name: String,
+    pub owner: String,
+    pub model: String,
+    pub dimension: u32,
+    pub metric: DistanceMetric,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub chunk_count: u64,
+    pub encrypted: bool,
+}
@@ -0,0 +1,81 @@
+/// Defines the `Event` enum the app emits.
+///
+/// `#[app::event]` has to be applied in the crate that emits the events, so
+/// rather than a type this is the enum's definition, expanded in the app
+/// with the attributes passed in:
+///
+/// ```ignore
+/// getem_types::define_events! {
+///     #[app::event]
+/// }
+/// ```
+///
+/// The upload, order and plan ids are the app's `u64` id aliases. The
+/// `@with` form also puts the second list of attributes on every `u64`
+/// field; the crate uses it for the TypeScript mirror below.
+#[macro_export]
+macro_rules! define_events {
+    ($(#[$attr:meta])*) => {
+        $crate::define_events!(@with [$(#[$attr])*] []);
+    };
+    (@with [$(#[$attr:meta])*] [$(#[$number:meta])*]) => {
+        $(#[$attr])*
+        pub enum Event {
+            // File exchange events
+            FileUploaded { name: String, owner: String, sha256: String },
+            FileDownloaded { name: String, downloader: String },
+            FileDeleted { name: String },
+            UploadStarted { $(#[$number])* id: u64, name: String },
+            UploadAborted { $(#[$number])* id: u64 },
+            UploadExpired { $(#[$number])* id: u64 },
+            // Dataset events
+            DatasetUploaded { name: String, owner: String, $(#[$number])* chunk_count: u64 },
+            DatasetChunksAppended { name: String, $(#[$number])* chunk_count: u64 },
+            DatasetDeleted { name: String },
+            ListingUpdated { name: String },
+            // Proposal events
+            ProposalCreated { id: $crate::ProposalId },
+            ApprovedProposal { id: $crate::ProposalId },
+            ProposalExecuted { id: $crate::ProposalId },
+            ProposalCancelled { id: $crate::ProposalId },
+            // Purchase events
+            OrderOpened { $(#[$number])* id: u64, listing: String, buyer: String },
+            OrderPaid { $(#[$number])* id: u64 },
+            OrderCancelled { $(#[$number])* id: u64 },
+            OrderExpired { $(#[$number])* id: u64 },
+            // Subscription events
+            PlanCreated { $(#[$number])* id: u64, seller: String },
+            PlanRetired { $(#[$number])* id: u64 },
+            SubscriptionOrderOpened { $(#[$number])* id: u64, $(#[$number])* plan_id: u64, buyer: String },
+            SubscriptionStarted { $(#[$number])* plan_id: u64, subscriber: String, $(#[$number])* expires_at: u64 },
+            SubscriptionRenewed { $(#[$number])* plan_id: u64, subscriber: String, $(#[$number])* expires_at: u64 },
+            SubscriptionExpired { $(#[$number])* plan_id: u64, subscriber: String },
+            // Credit events
+            CreditDeposited { account: String, amount: $crate::U128 },
+            CreditWithdrawn { account: String, amount: $crate::U128 },
+            QueryCharged { dataset: String, buyer: String, amount: $crate::U128 },
+            // Review events
+            ReviewSubmitted { name: String, reviewer: String, rating: u8 },
+            ReviewReplied { name: String, reviewer: String },
+            // Access control events
+            AccessGranted { name: String, reader: String },
+            AccessRevoked { name: String, reader: String },
+            KeyWrapped { name: String, reader: String },
+            KeyRemoved { name: String, reader: String },
+        }
+    };
+}
+
+// A copy of the app's events for the TypeScript definitions only.
+#[cfg(feature = "ts")]
+define_events! {
+    @with [
+        /// An event the app emitted. The node reports each one as its
+        /// variant name, `kind`, and the JSON of its fields, `data`.
+        #[derive(serde::Serialize, ts_rs::TS)]
+        #[serde(tag = "kind", content = "data")]
+        #[allow(dead_code)]
+    ] [
+        #[ts(type = "number")]
+    ]
+}
@@ -0,0 +1,80 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::order::OrderId;
+use crate::proposal::U128;
+
+// ---------------- Ledger Types ----------------
+
+/// An entry's position in its account's statement.
+pub type EntryId = u64;
+
+/// Entries per page of `get_statement` unless the client asks for fewer,
+/// and the most it may ask for.
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+pub const MAX_PAGE_SIZE: u32 = 200;
+
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum EntryKind {
+    /// Credit bought by an executed Transfer proposal.
+    Deposit {
+        #[cfg_attr(feature = "ts", ts(type = "number"))]
+        order_id: OrderId,
+    },
+    /// A search the account paid for.
+    QueryCharge { dataset: String, seller: String },
+    /// A search someone else paid the account for.
+    QueryIncome { dataset: String, buyer: String },
+    /// Credit taken out to be paid by a Transfer proposal.
+    Withdrawal {
+        #[cfg_attr(feature = "ts", ts(type = "number"))]
+        order_id: OrderId,
+    },
+    /// A withdrawal whose proposal never executed, booked back.
+    WithdrawalReturned {
+        #[cfg_attr(feature = "ts", ts(type = "number"))]
+        order_id: OrderId,
+    },
+}
+
+impl EntryKind {
+    pub fn is_credit(&self) -> bool {
+        !matches!(
+            self,
+            EntryKind::QueryCharge { .. } | EntryKind::Withdrawal { .. }
+        )
+    }
+}
+
+/// One movement of credit. `balance` is the account's balance right after
+/// it.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct LedgerEntry {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub id: EntryId,
+    pub kind: EntryKind,
+    pub amount: U128,
+    pub balance: U128,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+}
+
+/// A slice of an account's entries, oldest first.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Statement {
+    pub balance: U128,
+    pub entries: Vec<LedgerEntry>,
+    /// Pass as `from` to fetch the next page; `None` on the last one.
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub next: Option<EntryId>,
+}
+
+impl Statement {
+    /// Clamps the requested page size to `1..=MAX_PAGE_SIZE`.
+    pub fn size(limit: Option<u32>) -> usize {
+        limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as usize
+    }
+}
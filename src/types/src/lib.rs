@@ -0,0 +1,45 @@
+//! Types shared by the getem app (`src/logic`), the tools under `tools/`
+//! and, through generated TypeScript, the frontend.
+//!
+//! Nothing here depends on the Calimero SDK, so tooling can use the types
+//! without building the app. What the SDK has to provide is kept at the
+//! edges: [`ProposalId`] is converted to the runtime's id in the app's
+//! `host` module, and the `Event` enum is written out by
+//! [`define_events!`] inside the app, where `#[app::event]` can be applied
+//! to it.
+//!
+//! With the `ts` feature every type the frontend sends or receives derives
+//! `ts_rs::TS`; `cargo run --features ts --bin generate-ts` writes them to
+//! `src/frontend/src/api/generated/getem-types.ts`, along with the
+//! shape of the app's events.
+
+pub mod args;
+pub mod catalog;
+pub mod dataset;
+mod event;
+pub mod ledger;
+pub mod message;
+pub mod order;
+pub mod proposal;
+pub mod review;
+pub mod subscription;
+pub mod terms;
+
+#[cfg(feature = "ts")]
+pub mod ts;
+
+#[cfg(test)]
+mod tests;
+
+pub use catalog::{ListingFilter, ListingKind, ListingPage, ListingSort, ListingSummary};
+pub use dataset::{ChunkMetadata, DatasetInfo, DistanceMetric, EmbeddingChunk};
+pub use ledger::{EntryId, EntryKind, LedgerEntry, Statement};
+pub use message::{Message, MessageError, MessageId, MessagePage};
+pub use order::{Order, OrderId, OrderItem, OrderStatus};
+pub use proposal::{
+    ApprovalError, CreateProposalRequest, ProposalId, ProposalRecord, ProposalRequestError,
+    ProposalStatus, U128,
+};
+pub use review::{Reply, Review};
+pub use subscription::{Plan, PlanId, Subscription};
+pub use terms::{AllowedUses, License, LicenseKind, Price, NATIVE_TOKEN};
@@ -0,0 +1,138 @@
+use std::fmt;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::proposal::ProposalId;
+
+// ---------------- Message Types ----------------
+
+/// A message's position in its thread. Deleted messages stay behind as
+/// tombstones, so ids are never reused.
+pub type MessageId = u64;
+
+/// Longest message text the app accepts, in bytes.
+pub const MAX_MESSAGE_BYTES: usize = 4096;
+
+/// Messages per page of `get_proposal_messages` unless the client asks for
+/// fewer, and the most it may ask for.
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+pub const MAX_PAGE_SIZE: u32 = 200;
+
+/// A message in a proposal's discussion thread. Everything but the text is
+/// filled in by the app.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Message {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub id: MessageId,
+    pub proposal_id: ProposalId,
+    pub author: String,
+    pub text: String,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub edited_at: Option<u64>,
+    pub deleted: bool,
+}
+
+impl Message {
+    pub fn new(
+        id: MessageId,
+        proposal_id: ProposalId,
+        author: String,
+        text: String,
+        created_at: u64,
+    ) -> Self {
+        Message {
+            id,
+            proposal_id,
+            author,
+            text,
+            created_at,
+            edited_at: None,
+            deleted: false,
+        }
+    }
+
+    /// Only the author may change a message, and not after deleting it.
+    pub fn check_author(&self, caller: &str) -> Result<(), MessageError> {
+        if self.author != caller {
+            return Err(MessageError::NotAuthor);
+        }
+        if self.deleted {
+            return Err(MessageError::Deleted);
+        }
+        Ok(())
+    }
+
+    pub fn edit(&mut self, text: String, now: u64) {
+        self.text = text;
+        self.edited_at = Some(now);
+    }
+
+    /// Clears the text but keeps the message, so later ids stay stable.
+    pub fn delete(&mut self, now: u64) {
+        self.text.clear();
+        self.edited_at = Some(now);
+        self.deleted = true;
+    }
+}
+
+/// A slice of a thread, oldest first.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct MessagePage {
+    pub messages: Vec<Message>,
+    /// Pass as `from` to fetch the next page; `None` on the last one.
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub next: Option<MessageId>,
+}
+
+impl MessagePage {
+    /// Clamps the requested page size to `1..=MAX_PAGE_SIZE`.
+    pub fn size(limit: Option<u32>) -> usize {
+        limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as usize
+    }
+}
+
+pub fn check_text(text: &str) -> Result<(), MessageError> {
+    if text.trim().is_empty() {
+        return Err(MessageError::EmptyText);
+    }
+    if text.len() > MAX_MESSAGE_BYTES {
+        return Err(MessageError::TextTooLong);
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub enum MessageError {
+    ProposalNotFound,
+    ThreadClosed,
+    MessageNotFound,
+    NotAuthor,
+    Deleted,
+    EmptyText,
+    TextTooLong,
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageError::ProposalNotFound => f.write_str("proposal not found"),
+            MessageError::ThreadClosed => f.write_str("proposal is closed to new messages"),
+            MessageError::MessageNotFound => f.write_str("message not found"),
+            MessageError::NotAuthor => f.write_str("only the author can change a message"),
+            MessageError::Deleted => f.write_str("message was deleted"),
+            MessageError::EmptyText => f.write_str("message text must not be empty"),
+            MessageError::TextTooLong => write!(
+                f,
+                "message text must be at most {} bytes",
+                MAX_MESSAGE_BYTES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MessageError {}
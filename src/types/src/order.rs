@@ -0,0 +1,71 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::proposal::{ProposalId, U128};
+use crate::subscription::PlanId;
+use crate::terms::License;
+
+// ---------------- Order Types ----------------
+
+pub type OrderId = u64;
+
+/// What an order pays for.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum OrderItem {
+    Listing(String),
+    /// One period of a subscription plan, starting or renewing it.
+    Subscription(#[cfg_attr(feature = "ts", ts(type = "number"))] PlanId),
+    /// Credit for pay-per-query searches, worth the order's amount.
+    CreditDeposit,
+    /// Credit paid out to the buyer's payout account, worth the order's
+    /// amount; the seller is the marketplace account.
+    CreditWithdrawal,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum OrderStatus {
+    Pending,
+    Paid,
+    Cancelled,
+    Expired,
+}
+
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Order {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub id: OrderId,
+    pub item: OrderItem,
+    pub buyer: String,
+    pub seller: String,
+    /// The listing's price when the order was opened; `fee` of it goes to
+    /// the marketplace, the rest to the seller.
+    pub amount: U128,
+    pub token: String,
+    pub fee: U128,
+    pub license: License,
+    pub proposal_id: ProposalId,
+    pub status: OrderStatus,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub expires_at: u64,
+}
+
+impl Order {
+    pub fn is_pending(&self) -> bool {
+        self.status == OrderStatus::Pending
+    }
+
+    pub fn is_expired_at(&self, now: u64) -> bool {
+        self.is_pending() && now >= self.expires_at
+    }
+
+    pub fn involves(&self, identity: &str) -> bool {
+        self.buyer == identity || self.seller == identity
+    }
+}
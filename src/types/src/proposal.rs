@@ -0,0 +1,411 @@
+use std::fmt;
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+// ---------------- Proposal Request Types ----------------
+
+/// A proposal as sent by clients: `{ "action_type": "...", "params": { ... } }`.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(tag = "action_type", content = "params")]
+pub enum CreateProposalRequest {
+    ExternalFunctionCall {
+        receiver_id: String,
+        method_name: String,
+        args: String,
+        deposit: U128,
+    },
+    Transfer {
+        receiver_id: String,
+        amount: U128,
+    },
+    SetContextValue {
+        key: String,
+        value: String,
+    },
+    SetNumApprovals {
+        num_approvals: u32,
+    },
+    SetActiveProposalsLimit {
+        active_proposals_limit: u32,
+    },
+    DeleteProposal {
+        /// Hex, unlike everywhere else.
+        #[serde(with = "hex_proposal_id")]
+        #[cfg_attr(feature = "ts", ts(type = "string"))]
+        proposal_id: ProposalId,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ProposalRequestError {
+    EmptyField(&'static str),
+    ZeroValue(&'static str),
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for ProposalRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProposalRequestError::EmptyField(field) => write!(f, "{} must not be empty", field),
+            ProposalRequestError::ZeroValue(field) => write!(f, "{} must be at least 1", field),
+            ProposalRequestError::InvalidValue(field) => {
+                write!(f, "{} is not valid for this key", field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProposalRequestError {}
+
+impl CreateProposalRequest {
+    pub fn action_type(&self) -> &'static str {
+        match self {
+            CreateProposalRequest::ExternalFunctionCall { .. } => "ExternalFunctionCall",
+            CreateProposalRequest::Transfer { .. } => "Transfer",
+            CreateProposalRequest::SetContextValue { .. } => "SetContextValue",
+            CreateProposalRequest::SetNumApprovals { .. } => "SetNumApprovals",
+            CreateProposalRequest::SetActiveProposalsLimit { .. } => "SetActiveProposalsLimit",
+            CreateProposalRequest::DeleteProposal { .. } => "DeleteProposal",
+        }
+    }
+
+    /// Checks what the types alone cannot express.
+    pub fn validate(&self) -> Result<(), ProposalRequestError> {
+        match self {
+            CreateProposalRequest::ExternalFunctionCall {
+                receiver_id,
+                method_name,
+                ..
+            } => {
+                non_empty("receiver_id", receiver_id)?;
+                non_empty("method_name", method_name)
+            }
+            CreateProposalRequest::Transfer { receiver_id, .. } => {
+                non_empty("receiver_id", receiver_id)
+            }
+            CreateProposalRequest::SetContextValue { key, value } => {
+                non_empty("key", key)?;
+                if key == ARCHIVE_MESSAGES_KEY && parse_flag(value).is_none() {
+                    return Err(ProposalRequestError::InvalidValue("value"));
+                }
+                Ok(())
+            }
+            CreateProposalRequest::SetNumApprovals { num_approvals } => {
+                non_zero("num_approvals", *num_approvals)
+            }
+            CreateProposalRequest::SetActiveProposalsLimit {
+                active_proposals_limit,
+            } => non_zero("active_proposals_limit", *active_proposals_limit),
+            CreateProposalRequest::DeleteProposal { .. } => Ok(()),
+        }
+    }
+
+    /// One line describing what the proposal does, for listings.
+    pub fn summary(&self) -> String {
+        match self {
+            CreateProposalRequest::ExternalFunctionCall {
+                receiver_id,
+                method_name,
+                deposit,
+                ..
+            } => format!(
+                "Call {} on {} with deposit {}",
+                method_name, receiver_id, deposit.0
+            ),
+            CreateProposalRequest::Transfer {
+                receiver_id,
+                amount,
+            } => format!("Transfer {} to {}", amount.0, receiver_id),
+            CreateProposalRequest::SetContextValue { key, .. } => {
+                format!("Set context value {}", key)
+            }
+            CreateProposalRequest::SetNumApprovals { num_approvals } => {
+                format!("Require {} approvals", num_approvals)
+            }
+            CreateProposalRequest::SetActiveProposalsLimit {
+                active_proposals_limit,
+            } => format!("Allow {} active proposals", active_proposals_limit),
+            CreateProposalRequest::DeleteProposal { proposal_id } => {
+                format!("Delete proposal {}", hex::encode(proposal_id.0))
+            }
+        }
+    }
+}
+
+/// SetContextValue key that, once executed with `"true"`, keeps the message
+/// threads of finished proposals read-only instead of deleting them.
+pub const ARCHIVE_MESSAGES_KEY: &str = "getem.archive_messages";
+
+pub fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+// ---------------- Proposal Registry ----------------
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum ProposalStatus {
+    Pending,
+    Executed,
+    Deleted,
+    Cancelled,
+}
+
+impl ProposalStatus {
+    pub fn is_closed(self) -> bool {
+        self != ProposalStatus::Pending
+    }
+}
+
+/// What the app knows about a proposal it sent to the context proxy.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct ProposalRecord {
+    pub id: ProposalId,
+    pub creator: String,
+    pub action: CreateProposalRequest,
+    pub summary: String,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+    pub approvers: Vec<String>,
+    pub status: ProposalStatus,
+}
+
+impl ProposalRecord {
+    /// The proxy counts the creator's proposal as their approval.
+    pub fn new(
+        id: ProposalId,
+        creator: String,
+        action: CreateProposalRequest,
+        created_at: u64,
+    ) -> Self {
+        ProposalRecord {
+            id,
+            summary: action.summary(),
+            approvers: vec![creator.clone()],
+            creator,
+            action,
+            created_at,
+            status: ProposalStatus::Pending,
+        }
+    }
+
+    pub fn has_enough_approvals(&self, num_approvals: u32) -> bool {
+        self.approvers.len() >= num_approvals as usize
+    }
+
+    /// An approval only counts once per member, and only while the
+    /// proposal is still pending.
+    pub fn check_approval(&self, approver: &str) -> Result<(), ApprovalError> {
+        if self.status != ProposalStatus::Pending {
+            return Err(ApprovalError::ProposalClosed(self.status));
+        }
+        if self.approvers.iter().any(|existing| existing == approver) {
+            return Err(ApprovalError::AlreadyApproved);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub enum ApprovalError {
+    ProposalNotFound,
+    AlreadyApproved,
+    ProposalClosed(ProposalStatus),
+}
+
+impl fmt::Display for ApprovalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApprovalError::ProposalNotFound => f.write_str("proposal not found"),
+            ApprovalError::AlreadyApproved => f.write_str("proposal already approved by caller"),
+            ApprovalError::ProposalClosed(status) => {
+                write!(f, "proposal is {:?} and can no longer be approved", status)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ApprovalError {}
+
+fn non_empty(field: &'static str, value: &str) -> Result<(), ProposalRequestError> {
+    if value.trim().is_empty() {
+        return Err(ProposalRequestError::EmptyField(field));
+    }
+    Ok(())
+}
+
+fn non_zero(field: &'static str, value: u32) -> Result<(), ProposalRequestError> {
+    if value == 0 {
+        return Err(ProposalRequestError::ZeroValue(field));
+    }
+    Ok(())
+}
+
+// ---------------- Amounts ----------------
+
+/// A token amount. Accepts a JSON number or a decimal string, and is always
+/// serialized as a string so JavaScript clients don't lose precision.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, BorshSerialize, BorshDeserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+pub struct U128(pub u128);
+
+impl Serialize for U128 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for U128 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct U128Visitor;
+
+        impl de::Visitor<'_> for U128Visitor {
+            type Value = U128;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a non-negative integer or a decimal string that fits in u128")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<U128, E> {
+                Ok(U128(value.into()))
+            }
+
+            fn visit_u128<E: de::Error>(self, value: u128) -> Result<U128, E> {
+                Ok(U128(value))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<U128, E> {
+                u128::try_from(value)
+                    .map(U128)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<U128, E> {
+                // Large JSON numbers arrive as floats and have already lost
+                // precision, so they have to be sent as strings instead.
+                Err(E::invalid_value(de::Unexpected::Float(value), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<U128, E> {
+                value
+                    .trim()
+                    .parse::<u128>()
+                    .map(U128)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(U128Visitor)
+    }
+}
+
+impl From<u128> for U128 {
+    fn from(value: u128) -> Self {
+        U128(value)
+    }
+}
+
+// ---------------- Proposal Ids ----------------
+
+/// The context proxy's id of a proposal, laid out in Borsh like the
+/// runtime's own id. Clients see it as the base58 string the admin API
+/// shows; the byte array older clients sent is accepted as well.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, BorshSerialize, BorshDeserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+pub struct ProposalId(pub [u8; 32]);
+
+impl AsRef<[u8]> for ProposalId {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for ProposalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(self.0).into_string())
+    }
+}
+
+impl FromStr for ProposalId {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0; 32];
+        match bs58::decode(value.trim()).onto(&mut bytes) {
+            Ok(32) => Ok(ProposalId(bytes)),
+            _ => Err(format!("{} is not a base58 proposal id", value)),
+        }
+    }
+}
+
+impl Serialize for ProposalId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProposalId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ProposalIdVisitor;
+
+        impl<'de> de::Visitor<'de> for ProposalIdVisitor {
+            type Value = ProposalId;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a base58 proposal id or an array of 32 bytes")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<ProposalId, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<ProposalId, A::Error> {
+                let mut bytes = [0; 32];
+                for (index, byte) in bytes.iter_mut().enumerate() {
+                    *byte = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+                }
+                if seq.next_element::<u8>()?.is_some() {
+                    return Err(de::Error::invalid_length(33, &self));
+                }
+                Ok(ProposalId(bytes))
+            }
+        }
+
+        deserializer.deserialize_any(ProposalIdVisitor)
+    }
+}
+
+/// `#[serde(with = "hex_proposal_id")]`: the id as hex, which is how the
+/// proxy expects it in DeleteProposal actions.
+pub mod hex_proposal_id {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(id: &ProposalId, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(id.0))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ProposalId, D::Error> {
+        let encoded = <String as Deserialize>::deserialize(deserializer)?;
+        let bytes = hex::decode(&encoded).map_err(de::Error::custom)?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| de::Error::custom("proposal_id must be 32 bytes of hex"))?;
+        Ok(ProposalId(bytes))
+    }
+}
@@ -0,0 +1,26 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+// ---------------- Review Types ----------------
+
+/// A buyer's review of a listing. Each buyer reviews a listing once, and
+/// its owner may answer once.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Review {
+    pub listing: String,
+    pub reviewer: String,
+    pub rating: u8,
+    pub text: String,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+    pub reply: Option<Reply>,
+}
+
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Reply {
+    pub text: String,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+}
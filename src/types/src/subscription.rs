@@ -0,0 +1,85 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::terms::{License, Price};
+
+// ---------------- Subscription Types ----------------
+
+pub type PlanId = u64;
+
+/// A seller's offer of access to a set of their datasets for one period
+/// (nanoseconds, matching `env::time_now`) at a time.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Plan {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub id: PlanId,
+    pub seller: String,
+    pub name: String,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub period: u64,
+    pub price: Price,
+    pub datasets: Vec<String>,
+    pub license: License,
+    /// Retired plans take no new subscribers or renewals; windows already
+    /// paid for still run out.
+    pub retired: bool,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub created_at: u64,
+}
+
+impl Plan {
+    /// Only datasets still owned by the plan's seller are covered, so a
+    /// name that is deleted and taken by someone else drops out.
+    pub fn covers(&self, name: &str, owner: &str) -> bool {
+        self.seller == owner && self.datasets.iter().any(|dataset| dataset == name)
+    }
+}
+
+/// A subscriber's paid window on a plan. It only ever grows: a renewal
+/// adds one period to it.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Subscription {
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub plan_id: PlanId,
+    pub subscriber: String,
+    /// Start of the current unbroken window.
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub started_at: u64,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub expires_at: u64,
+    pub renewals: u32,
+    /// Set once `expire_subscriptions` has reported the lapse, so it is
+    /// reported only once.
+    pub lapsed: bool,
+}
+
+impl Subscription {
+    pub fn start(plan_id: PlanId, subscriber: String, period: u64, now: u64) -> Self {
+        Subscription {
+            plan_id,
+            subscriber,
+            started_at: now,
+            expires_at: now.saturating_add(period),
+            renewals: 0,
+            lapsed: false,
+        }
+    }
+
+    pub fn is_active_at(&self, now: u64) -> bool {
+        now < self.expires_at
+    }
+
+    /// Adds a period straight after the current window while it is still
+    /// running, or starts a new window at `now` once it has run out.
+    pub fn renew(&mut self, period: u64, now: u64) {
+        if !self.is_active_at(now) {
+            self.started_at = now;
+            self.expires_at = now;
+        }
+        self.expires_at = self.expires_at.saturating_add(period);
+        self.renewals += 1;
+        self.lapsed = false;
+    }
+}
@@ -0,0 +1,103 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::proposal::U128;
+
+// ---------------- Listing Terms ----------------
+
+/// What the context proxy transfers: credit deposits, and prices from
+/// before prices named their token, are in it.
+pub const NATIVE_TOKEN: &str = "native";
+
+/// What a listing costs. The purchase is paid through the context proxy,
+/// which only transfers the chain's native token, so `token` must be
+/// `NATIVE_TOKEN`.
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Price {
+    pub amount: U128,
+    pub token: String,
+}
+
+impl Price {
+    pub fn check(&self) -> Result<(), String> {
+        if self.amount.0 == 0 {
+            return Err("price must be greater than zero".to_owned());
+        }
+        if !self.is_native() {
+            return Err(format!("price token must be \"{}\"", NATIVE_TOKEN));
+        }
+        Ok(())
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.token == NATIVE_TOKEN
+    }
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum LicenseKind {
+    #[default]
+    Personal,
+    Research,
+    Commercial,
+    OpenSource,
+}
+
+/// What a buyer may do with the content beyond using it themselves.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct AllowedUses {
+    #[serde(default)]
+    pub commercial: bool,
+    #[serde(default)]
+    pub derivatives: bool,
+    #[serde(default)]
+    pub redistribution: bool,
+    #[serde(default)]
+    pub model_training: bool,
+}
+
+/// The terms a listing is sold under. Orders keep a copy, so changing a
+/// listing's license does not change what earlier buyers agreed to.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct License {
+    pub kind: LicenseKind,
+    #[serde(default)]
+    pub allowed_uses: AllowedUses,
+}
@@ -0,0 +1,131 @@
+use serde_json::json;
+
+use super::*;
+use args::GetProposalMessagesRequest;
+
+fn proposal_id() -> ProposalId {
+    let mut bytes = [0; 32];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = index as u8;
+    }
+    ProposalId(bytes)
+}
+
+/// Borsh keeps the runtime's layout, so stored state reads back unchanged.
+#[test]
+fn proposal_id_is_raw_bytes_in_borsh_and_base58_in_json() {
+    let id = proposal_id();
+    assert_eq!(borsh::to_vec(&id).unwrap(), id.0.to_vec());
+
+    let encoded = bs58::encode(id.0).into_string();
+    assert_eq!(serde_json::to_value(id).unwrap(), json!(encoded));
+    assert_eq!(encoded.parse::<ProposalId>(), Ok(id));
+    assert_eq!(
+        serde_json::from_value::<ProposalId>(json!(encoded)).unwrap(),
+        id
+    );
+    assert_eq!(
+        serde_json::from_value::<ProposalId>(json!(id.0.to_vec())).unwrap(),
+        id
+    );
+
+    assert!("".parse::<ProposalId>().is_err());
+    assert!(bs58::encode([1; 31])
+        .into_string()
+        .parse::<ProposalId>()
+        .is_err());
+    assert!(serde_json::from_value::<ProposalId>(json!(vec![1; 33])).is_err());
+}
+
+#[test]
+fn delete_proposal_sends_the_id_as_hex() {
+    let request = CreateProposalRequest::DeleteProposal {
+        proposal_id: proposal_id(),
+    };
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "action_type": "DeleteProposal",
+            "params": { "proposal_id": hex::encode(proposal_id().0) },
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<CreateProposalRequest>(value).unwrap(),
+        request
+    );
+}
+
+#[test]
+fn optional_method_arguments_are_left_out() {
+    let request = GetProposalMessagesRequest {
+        proposal_id: proposal_id(),
+        from: None,
+        limit: Some(10),
+    };
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({ "proposal_id": proposal_id().to_string(), "limit": 10 })
+    );
+}
+
+#[test]
+fn distance_metric_parses_cli_spellings() {
+    assert_eq!("cosine".parse(), Ok(DistanceMetric::Cosine));
+    assert_eq!("dot-product".parse(), Ok(DistanceMetric::DotProduct));
+    assert_eq!("Euclidean".parse(), Ok(DistanceMetric::Euclidean));
+    assert_eq!(
+        "manhattan"
+            .parse::<DistanceMetric>()
+            .unwrap_err()
+            .to_string(),
+        "unknown metric manhattan"
+    );
+}
+
+mod events {
+    crate::define_events! {
+        #[derive(Debug, PartialEq, serde::Serialize)]
+        #[allow(dead_code)]
+    }
+
+    #[test]
+    fn define_events_applies_the_attributes() {
+        let event = Event::QueryCharged {
+            dataset: "inferno".to_owned(),
+            buyer: "bob".to_owned(),
+            amount: crate::U128(5),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "QueryCharged": { "dataset": "inferno", "buyer": "bob", "amount": "5" }
+            })
+        );
+    }
+}
+
+/// The TypeScript copy of the events takes the node's `kind`/`data` shape.
+#[cfg(feature = "ts")]
+#[test]
+fn typescript_events_are_tagged_by_kind() {
+    let event = event::Event::OrderPaid { id: 7 };
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        json!({ "kind": "OrderPaid", "data": { "id": 7 } })
+    );
+}
+
+/// The frontend imports the checked-in definitions, so they must match the
+/// Rust types.
+#[cfg(feature = "ts")]
+#[test]
+fn typescript_definitions_are_up_to_date() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(ts::OUTPUT);
+    let checked_in = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        checked_in == ts::definitions(),
+        "{} is out of date; run `cargo run --features ts --bin generate-ts` in src/types",
+        path.display()
+    );
+}
@@ -0,0 +1,86 @@
+//! The TypeScript definitions the frontend imports.
+
+use ts_rs::TS;
+
+use crate::args::{
+    ApproveProposalRequest, CancelProposalRequest, CreateNewProposalRequest,
+    DeleteProposalMessageRequest, EditProposalMessageRequest, GetProposalMessagesRequest,
+    SendProposalMessageRequest, SyncNumApprovalsRequest,
+};
+use crate::event::Event;
+use crate::{
+    AllowedUses, ChunkMetadata, CreateProposalRequest, DatasetInfo, DistanceMetric, EmbeddingChunk,
+    EntryKind, LedgerEntry, License, LicenseKind, ListingFilter, ListingKind, ListingPage,
+    ListingSort, ListingSummary, Message, MessagePage, Order, OrderItem, OrderStatus, Plan, Price,
+    ProposalId, ProposalRecord, ProposalStatus, Reply, Review, Statement, Subscription, U128,
+};
+
+/// Where `generate-ts` writes the definitions, relative to this crate.
+pub const OUTPUT: &str = "../frontend/src/api/generated/getem-types.ts";
+
+const HEADER: &str = "\
+// Generated from src/types by `cargo run --features ts --bin generate-ts`.
+// Do not edit; change the Rust types and regenerate instead.
+";
+
+/// Every exported declaration, in dependency order.
+pub fn definitions() -> String {
+    let mut out = String::from(HEADER);
+    let mut declare = |docs: Option<String>, decl: String| {
+        out.push('\n');
+        if let Some(docs) = docs {
+            out.push_str(&docs);
+        }
+        out.push_str("export ");
+        out.push_str(&decl);
+        out.push('\n');
+    };
+    macro_rules! declare {
+        ($($ty:ty),* $(,)?) => {
+            $(declare(<$ty as TS>::docs(), <$ty as TS>::decl());)*
+        };
+    }
+
+    declare!(
+        ProposalId,
+        U128,
+        Message,
+        MessagePage,
+        CreateProposalRequest,
+        ProposalStatus,
+        ProposalRecord,
+        DistanceMetric,
+        ChunkMetadata,
+        EmbeddingChunk,
+        DatasetInfo,
+        CreateNewProposalRequest,
+        ApproveProposalRequest,
+        SyncNumApprovalsRequest,
+        CancelProposalRequest,
+        GetProposalMessagesRequest,
+        SendProposalMessageRequest,
+        EditProposalMessageRequest,
+        DeleteProposalMessageRequest,
+        Price,
+        LicenseKind,
+        AllowedUses,
+        License,
+        ListingKind,
+        ListingSummary,
+        ListingFilter,
+        ListingSort,
+        ListingPage,
+        OrderItem,
+        OrderStatus,
+        Order,
+        Plan,
+        Subscription,
+        EntryKind,
+        LedgerEntry,
+        Statement,
+        Reply,
+        Review,
+        Event,
+    );
+    out
+}
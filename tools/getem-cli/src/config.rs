@@ -0,0 +1,143 @@
+//! Which node the CLI talks to and as whom.
+//!
+//! Settings come from, in order: command-line options, the environment
+//! (`NODE_URL`, `CONTEXT_ID`, `EXECUTOR_PUBLIC_KEY`, `NODE_TOKEN`, as for
+//! `getem-import`), then the config file. Like the frontend, the context
+//! and executor default to the ones named in the access token.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL;
+use base64::Engine;
+use getem_import::rpc::RpcClient;
+use serde::{Deserialize, Serialize};
+
+use crate::CliError;
+
+/// Node API address used when nothing else is configured.
+pub const DEFAULT_NODE_URL: &str = "http://localhost:2428";
+
+/// Overrides the location of the config file.
+pub const CONFIG_PATH_ENV: &str = "GETEM_CONFIG";
+
+/// The config file, `~/.config/getem/config.json` by default:
+///
+/// ```json
+/// {
+///   "node_url": "http://localhost:2428",
+///   "access_token": "<the frontend's access token>"
+/// }
+/// ```
+///
+/// Every field is optional.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub node_url: Option<String>,
+    #[serde(default)]
+    pub context_id: Option<String>,
+    #[serde(default)]
+    pub executor_public_key: Option<String>,
+    /// The JWT the frontend keeps after logging in to the node.
+    #[serde(default)]
+    pub access_token: Option<String>,
+}
+
+impl Config {
+    /// `GETEM_CONFIG`, else `getem/config.json` under `XDG_CONFIG_HOME` or
+    /// `~/.config`.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os(CONFIG_PATH_ENV) {
+            return Some(PathBuf::from(path));
+        }
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(dir.join("getem").join("config.json"))
+    }
+
+    /// Reads a config file; a missing file is an empty config.
+    pub fn load(path: &Path) -> Result<Config, CliError> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|err| CliError::Config(format!("{}: {}", path.display(), err))),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(CliError::Config(format!("{}: {}", path.display(), err))),
+        }
+    }
+
+    pub fn from_env() -> Config {
+        Config {
+            node_url: env::var("NODE_URL").ok(),
+            context_id: env::var("CONTEXT_ID").ok(),
+            executor_public_key: env::var("EXECUTOR_PUBLIC_KEY").ok(),
+            access_token: env::var("NODE_TOKEN").ok(),
+        }
+    }
+
+    /// Keeps the fields set here and takes the rest from `fallback`.
+    pub fn or(self, fallback: Config) -> Config {
+        Config {
+            node_url: self.node_url.or(fallback.node_url),
+            context_id: self.context_id.or(fallback.context_id),
+            executor_public_key: self.executor_public_key.or(fallback.executor_public_key),
+            access_token: self.access_token.or(fallback.access_token),
+        }
+    }
+
+    pub fn client(&self) -> Result<RpcClient, CliError> {
+        // The token is only read for what was not given, so one that is not
+        // a JWT still works when both are.
+        let claims = match &self.access_token {
+            Some(token) if self.context_id.is_none() || self.executor_public_key.is_none() => {
+                Some(Claims::decode(token)?)
+            }
+            _ => None,
+        };
+        let context_id = self
+            .context_id
+            .clone()
+            .or_else(|| claims.as_ref().map(|claims| claims.context_id.clone()))
+            .ok_or_else(|| {
+                CliError::Config("no context: set --context-id or an access token".to_owned())
+            })?;
+        let executor = self
+            .executor_public_key
+            .clone()
+            .or_else(|| claims.map(|claims| claims.executor_public_key))
+            .ok_or_else(|| {
+                CliError::Config("no executor: set --executor or an access token".to_owned())
+            })?;
+
+        let node_url = self.node_url.as_deref().unwrap_or(DEFAULT_NODE_URL);
+        Ok(RpcClient::new(node_url, &context_id, &executor).with_token(self.access_token.clone()))
+    }
+}
+
+/// The part of the node's access token the CLI reads; the frontend's
+/// `JsonWebToken`. The signature is left to the node.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Claims {
+    pub context_id: String,
+    pub executor_public_key: String,
+}
+
+impl Claims {
+    pub fn decode(token: &str) -> Result<Claims, CliError> {
+        let invalid = |reason: &str| CliError::Config(format!("invalid access token: {}", reason));
+
+        let mut parts = token.trim().split('.');
+        let (Some(_), Some(payload), Some(_), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid("expected three parts"));
+        };
+        let payload = BASE64_URL
+            .decode(payload.trim_end_matches('='))
+            .map_err(|err| invalid(&err.to_string()))?;
+        serde_json::from_slice(&payload).map_err(|err| invalid(&err.to_string()))
+    }
+}
@@ -0,0 +1,807 @@
+//! The `getem` command-line client: the marketplace calls the frontend
+//! makes, sent as JSON-RPC to a node (reusing
+//! `getem_import::rpc::RpcClient`) and printed as tables or JSON.
+//!
+//! [`parse_args`] turns the command line into an [`Invocation`], and
+//! [`run`] performs its [`Command`] and returns what to print, so both can
+//! be tested without a terminal. Where the node is and who calls it is
+//! resolved by [`config`].
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use getem_export::{ExportError, Format};
+use getem_import::rpc::RpcClient;
+use getem_import::ImportError;
+use getem_types::args::{
+    ApproveProposalRequest, GetProposalMessagesRequest, SendProposalMessageRequest, MAX_CHUNK_BYTES,
+};
+use getem_types::{
+    DistanceMetric, ListingFilter, ListingSort, Message, MessageId, MessagePage, ProposalId,
+    ProposalRecord,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+use crate::table::Table;
+
+pub mod config;
+pub mod table;
+
+#[cfg(test)]
+mod tests;
+
+/// Hits returned by `search` unless `-k` says otherwise.
+pub const DEFAULT_K: u32 = 10;
+
+// ---------------- Commands ----------------
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Output {
+    #[default]
+    Table,
+    /// The node's answer as it came, pretty-printed.
+    Json,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Stores a text file as a listing.
+    Upload {
+        file: PathBuf,
+        name: String,
+        encrypted: bool,
+    },
+    /// One page of the catalog.
+    List {
+        filter: ListingFilter,
+        sort: Option<ListingSort>,
+        cursor: Option<String>,
+        limit: Option<u32>,
+    },
+    Show {
+        name: String,
+    },
+    /// Opens an order for a listing; it is paid once its proposal executes.
+    Buy {
+        name: String,
+    },
+    Approve {
+        proposal_id: ProposalId,
+    },
+    /// Reads a proposal's thread, or posts to it with `send`.
+    Messages {
+        proposal_id: ProposalId,
+        from: Option<MessageId>,
+        limit: Option<u32>,
+        send: Option<String>,
+    },
+    Search {
+        dataset: String,
+        vector: Vec<f32>,
+        k: u32,
+        metric: Option<DistanceMetric>,
+    },
+    /// Saves a file to `output`, or stdout without one. With `format`, exports
+    /// a dataset into the directory `output` (default `.`) instead.
+    Download {
+        name: String,
+        output: Option<PathBuf>,
+        format: Option<Format>,
+    },
+}
+
+/// A parsed command line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invocation {
+    /// Settings given as options; they win over the environment and the
+    /// config file.
+    pub config: Config,
+    pub config_path: Option<PathBuf>,
+    pub output: Output,
+    pub command: Command,
+}
+
+// ---------------- Arguments ----------------
+
+/// Options that take no value.
+const SWITCHES: [&str; 2] = ["--json", "--encrypted"];
+
+/// Parses the arguments after the program name. `-h`/`--help` is a
+/// `Usage` error with an empty message.
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Invocation, CliError> {
+    let mut positional: Vec<String> = Vec::new();
+    let mut options: Vec<(String, Option<String>)> = Vec::new();
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Usage(String::new())),
+            flag if SWITCHES.contains(&flag) => options.push((arg, None)),
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CliError::Usage(format!("{} needs a value", arg)))?;
+                options.push((arg, Some(value)));
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut options = Options(options);
+    let config = Config {
+        node_url: options.take("--node-url"),
+        context_id: options.take("--context-id"),
+        executor_public_key: options.take("--executor"),
+        access_token: options.take("--token"),
+    };
+    let config_path = options.take("--config").map(PathBuf::from);
+    let output = if options.switch("--json") {
+        Output::Json
+    } else {
+        Output::Table
+    };
+
+    let mut positional = positional.into_iter();
+    let name = positional
+        .next()
+        .ok_or_else(|| CliError::Usage("missing command".to_owned()))?;
+    let mut operand = |what: &str| {
+        positional
+            .next()
+            .ok_or_else(|| CliError::Usage(format!("{} needs {}", name, what)))
+    };
+
+    let command = match name.as_str() {
+        "upload" => {
+            let file = PathBuf::from(operand("a file")?);
+            let name = match options.take("--name") {
+                Some(name) => name,
+                None => file
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .ok_or_else(|| CliError::Usage("upload needs --name".to_owned()))?,
+            };
+            Command::Upload {
+                file,
+                name,
+                encrypted: options.switch("--encrypted"),
+            }
+        }
+        "list" => Command::List {
+            filter: ListingFilter {
+                owner: options.take("--owner"),
+                tag: options.take("--tag"),
+                model: options.take("--model"),
+                dimension: options.parse("--dimension")?,
+            },
+            sort: options.take("--sort").as_deref().map(sort).transpose()?,
+            cursor: options.take("--cursor"),
+            limit: options.parse("--limit")?,
+        },
+        "show" => Command::Show {
+            name: operand("a listing name")?,
+        },
+        "buy" => Command::Buy {
+            name: operand("a listing name")?,
+        },
+        "approve" => Command::Approve {
+            proposal_id: proposal_id(&operand("a proposal id")?)?,
+        },
+        "messages" => Command::Messages {
+            proposal_id: proposal_id(&operand("a proposal id")?)?,
+            from: options.parse("--from")?,
+            limit: options.parse("--limit")?,
+            send: options.take("--send"),
+        },
+        "search" => {
+            let dataset = operand("a dataset name")?;
+            let vector = match (options.take("--vector"), options.take("--vector-file")) {
+                (Some(values), None) => parse_vector(&values)?,
+                (None, Some(path)) => parse_vector(
+                    &fs::read_to_string(&path)
+                        .map_err(|err| CliError::Io(format!("{}: {}", path, err)))?,
+                )?,
+                _ => {
+                    return Err(CliError::Usage(
+                        "search needs one of --vector or --vector-file".to_owned(),
+                    ))
+                }
+            };
+            Command::Search {
+                dataset,
+                vector,
+                k: options.parse("-k")?.unwrap_or(DEFAULT_K),
+                metric: options.parse("--metric")?,
+            }
+        }
+        "download" => Command::Download {
+            name: operand("a listing name")?,
+            output: options.take("--output").map(PathBuf::from),
+            format: options.parse("--format")?,
+        },
+        other => return Err(CliError::Usage(format!("unknown command {}", other))),
+    };
+
+    if let Some(extra) = positional.next() {
+        return Err(CliError::Usage(format!("unexpected argument {}", extra)));
+    }
+    if let Some((flag, _)) = options.0.first() {
+        return Err(CliError::Usage(format!(
+            "unknown option {} for {}",
+            flag, name
+        )));
+    }
+
+    Ok(Invocation {
+        config,
+        config_path,
+        output,
+        command,
+    })
+}
+
+/// Options not yet claimed by a command; whatever is left is unknown.
+struct Options(Vec<(String, Option<String>)>);
+
+impl Options {
+    fn take(&mut self, flag: &str) -> Option<String> {
+        let index = self.0.iter().rposition(|(name, _)| name == flag)?;
+        let value = self.0.remove(index).1;
+        self.0.retain(|(name, _)| name != flag);
+        value
+    }
+
+    fn switch(&mut self, flag: &str) -> bool {
+        let found = self.0.iter().any(|(name, _)| name == flag);
+        self.0.retain(|(name, _)| name != flag);
+        found
+    }
+
+    fn parse<T: FromStr>(&mut self, flag: &str) -> Result<Option<T>, CliError>
+    where
+        T::Err: fmt::Display,
+    {
+        self.take(flag)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|err| CliError::Usage(format!("{} {}: {}", flag, value, err)))
+            })
+            .transpose()
+    }
+}
+
+/// The sort orders by the names the CLI accepts.
+fn sort(value: &str) -> Result<ListingSort, CliError> {
+    match value {
+        "newest" => Ok(ListingSort::Newest),
+        "oldest" => Ok(ListingSort::Oldest),
+        "name" => Ok(ListingSort::Name),
+        "price" => Ok(ListingSort::PriceLowToHigh),
+        "price-desc" => Ok(ListingSort::PriceHighToLow),
+        _ => Err(CliError::Usage(format!("unknown sort order {}", value))),
+    }
+}
+
+fn proposal_id(value: &str) -> Result<ProposalId, CliError> {
+    value
+        .parse()
+        .map_err(|err| CliError::Usage(format!("proposal id {}: {}", value, err)))
+}
+
+/// Reads `0.1,0.2`, `[0.1, 0.2]` or the same over several lines.
+pub fn parse_vector(values: &str) -> Result<Vec<f32>, CliError> {
+    let values = values.trim();
+    let values = values
+        .strip_prefix('[')
+        .and_then(|values| values.strip_suffix(']'))
+        .unwrap_or(values);
+
+    let vector = values
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            value
+                .parse::<f32>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| CliError::Usage(format!("{} is not a vector component", value)))
+        })
+        .collect::<Result<Vec<f32>, CliError>>()?;
+    if vector.is_empty() {
+        return Err(CliError::Usage("the query vector is empty".to_owned()));
+    }
+    Ok(vector)
+}
+
+// ---------------- Running ----------------
+
+/// Performs `command` against the node and returns what to print.
+pub fn run(client: &RpcClient, command: &Command, output: Output) -> Result<String, CliError> {
+    match command {
+        Command::Upload {
+            file,
+            name,
+            encrypted,
+        } => {
+            let bytes = fs::read(file).map_err(|err| io_error(file, err))?;
+            let size = bytes.len();
+            let content = String::from_utf8(bytes).map_err(|_| {
+                CliError::Io(format!(
+                    "{}: not UTF-8 text; encode binary files first",
+                    file.display()
+                ))
+            })?;
+            let sha256 = if size <= MAX_CHUNK_BYTES {
+                client.execute(
+                    "upload_file",
+                    json!({ "name": name, "content": content, "encrypted": encrypted }),
+                )?
+            } else {
+                upload_chunks(client, name, &content, *encrypted)?
+            };
+            render(output, &sha256, |sha256| {
+                format!(
+                    "Uploaded {} as {} ({} bytes, sha256 {})\n",
+                    file.display(),
+                    name,
+                    size,
+                    table::cell(sha256)
+                )
+            })
+        }
+        Command::List {
+            filter,
+            sort,
+            cursor,
+            limit,
+        } => {
+            let page = client.query(
+                "list_listings",
+                json!({
+                    "filter": filter,
+                    "sort": sort,
+                    "cursor": cursor,
+                    "offset": null,
+                    "limit": limit,
+                }),
+            )?;
+            render(output, &page, listings)
+        }
+        Command::Show { name } => {
+            let listing = client.query("get_listing", json!({ "name": name }))?;
+            render(output, &listing, listing_fields)
+        }
+        Command::Buy { name } => {
+            let order = client.execute("open_order", json!({ "listing": name }))?;
+            render(output, &order, order_fields)
+        }
+        Command::Approve { proposal_id } => {
+            client.execute(
+                "approve_proposal",
+                args(ApproveProposalRequest {
+                    proposal_id: *proposal_id,
+                }),
+            )?;
+            // Proposals sent before the app kept a registry are approved
+            // all the same but have no record to show.
+            let Ok(record) = client.query("get_proposal", json!({ "proposal_id": proposal_id }))
+            else {
+                return Ok(match output {
+                    Output::Json => pretty(&json!({ "id": proposal_id })),
+                    Output::Table => format!("Approved {}\n", proposal_id),
+                });
+            };
+            if output == Output::Json {
+                return Ok(pretty(&record));
+            }
+            let record: ProposalRecord = from_output(record)?;
+            Ok(format!(
+                "Approved {}: {:?} with {} approvals\n",
+                record.id,
+                record.status,
+                record.approvers.len()
+            ))
+        }
+        Command::Messages {
+            proposal_id,
+            send: Some(text),
+            ..
+        } => {
+            let message = client.execute(
+                "send_proposal_messages",
+                args(SendProposalMessageRequest {
+                    proposal_id: *proposal_id,
+                    text: text.clone(),
+                }),
+            )?;
+            if output == Output::Json {
+                return Ok(pretty(&message));
+            }
+            let message: Message = from_output(message)?;
+            Ok(messages(&[message]))
+        }
+        Command::Messages {
+            proposal_id,
+            from,
+            limit,
+            send: None,
+        } => {
+            let page = client.query(
+                "get_proposal_messages",
+                args(GetProposalMessagesRequest {
+                    proposal_id: *proposal_id,
+                    from: *from,
+                    limit: *limit,
+                }),
+            )?;
+            if output == Output::Json {
+                return Ok(pretty(&page));
+            }
+            let page: MessagePage = from_output(page)?;
+            let mut out = messages(&page.messages);
+            if let Some(next) = page.next {
+                out.push_str(&format!("More: --from {}\n", next));
+            }
+            Ok(out)
+        }
+        Command::Search {
+            dataset,
+            vector,
+            k,
+            metric,
+        } => {
+            let hits = client.execute(
+                "search",
+                json!({
+                    "dataset": dataset,
+                    "query_vector": vector,
+                    "k": k,
+                    "metric": metric,
+                }),
+            )?;
+            render(output, &hits, search_hits)
+        }
+        Command::Download {
+            name,
+            output: path,
+            format: Some(format),
+        } => {
+            let dataset = getem_export::fetch(client, name, getem_export::DEFAULT_PAGE_SIZE)?;
+            let dir = path.as_deref().unwrap_or(Path::new("."));
+            fs::create_dir_all(dir).map_err(|err| io_error(dir, err))?;
+            let written = getem_export::export(&dataset, *format, dir)?;
+
+            let written: Vec<String> = written
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            render(output, &json!(written), |_| {
+                written
+                    .iter()
+                    .map(|path| format!("Wrote {}\n", path))
+                    .collect()
+            })
+        }
+        Command::Download {
+            name,
+            output: path,
+            format: None,
+        } => {
+            let (content, sha256) = download(client, name)?;
+
+            let Some(path) = path else {
+                return Ok(match output {
+                    Output::Json => pretty(&json!({ "content": content, "sha256": sha256 })),
+                    Output::Table => content,
+                });
+            };
+            fs::write(path, &content).map_err(|err| io_error(path, err))?;
+            let saved =
+                json!({ "name": name, "path": path, "size": content.len(), "sha256": sha256 });
+            render(output, &saved, |_| {
+                format!(
+                    "Saved {} to {} ({} bytes)\n",
+                    name,
+                    path.display(),
+                    content.len()
+                )
+            })
+        }
+    }
+}
+
+/// Sends a file too large for `upload_file` through a chunked upload and
+/// returns its digest. An upload a chunk was refused for is aborted.
+fn upload_chunks(
+    client: &RpcClient,
+    name: &str,
+    content: &str,
+    encrypted: bool,
+) -> Result<Value, CliError> {
+    let status = client.execute(
+        "begin_upload",
+        json!({ "name": name, "encrypted": encrypted }),
+    )?;
+    let upload_id = status["id"]
+        .as_u64()
+        .ok_or_else(|| CliError::Response(format!("unexpected begin_upload output {}", status)))?;
+
+    for (index, data) in pieces(content).into_iter().enumerate() {
+        let appended = client.execute(
+            "append_upload_chunk",
+            json!({
+                "upload_id": upload_id,
+                "index": index,
+                "data": data,
+                "sha256": sha256_hex(data),
+            }),
+        );
+        if let Err(err) = appended {
+            // The upload cannot be finished now; failing to abort it only
+            // leaves it to linger on the node.
+            let _ = client.execute("abort_upload", json!({ "upload_id": upload_id }));
+            return Err(err.into());
+        }
+    }
+
+    Ok(client.execute(
+        "finalize_upload",
+        json!({ "upload_id": upload_id, "sha256": sha256_hex(content) }),
+    )?)
+}
+
+/// Pieces of at most `MAX_CHUNK_BYTES`, never cutting a character in half.
+fn pieces(content: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = content;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(MAX_CHUNK_BYTES);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (piece, tail) = rest.split_at(end);
+        pieces.push(piece);
+        rest = tail;
+    }
+    pieces
+}
+
+/// Fetches a file `MAX_CHUNK_BYTES` at a time with `download_range` and
+/// returns its content once it matches the file's digest.
+fn download(client: &RpcClient, name: &str) -> Result<(String, String), CliError> {
+    let mut content = String::new();
+    loop {
+        let range = client.query(
+            "download_range",
+            json!({ "name": name, "offset": content.len(), "length": MAX_CHUNK_BYTES }),
+        )?;
+        let (Some(data), Some(eof), Some(sha256)) = (
+            range["data"].as_str(),
+            range["eof"].as_bool(),
+            range["sha256"].as_str(),
+        ) else {
+            return Err(CliError::Response(format!(
+                "unexpected download_range output {}",
+                range
+            )));
+        };
+        if data.is_empty() && !eof {
+            return Err(CliError::Response(format!(
+                "download_range returned nothing at offset {}",
+                content.len()
+            )));
+        }
+        content.push_str(data);
+
+        if eof {
+            if sha256_hex(&content) != sha256 {
+                return Err(CliError::Response(format!(
+                    "{} does not match its sha256 {}",
+                    name, sha256
+                )));
+            }
+            return Ok((content, sha256.to_owned()));
+        }
+    }
+}
+
+fn sha256_hex(data: &str) -> String {
+    hex::encode(Sha256::digest(data.as_bytes()))
+}
+
+fn args(request: impl Serialize) -> Value {
+    serde_json::to_value(request).expect("argument types serialize to JSON")
+}
+
+fn from_output<T: DeserializeOwned>(value: Value) -> Result<T, CliError> {
+    serde_json::from_value(value)
+        .map_err(|err| CliError::Response(format!("unexpected output: {}", err)))
+}
+
+fn pretty(value: &Value) -> String {
+    format!(
+        "{}\n",
+        serde_json::to_string_pretty(value).expect("JSON values print")
+    )
+}
+
+fn render(
+    output: Output,
+    value: &Value,
+    text: impl FnOnce(&Value) -> String,
+) -> Result<String, CliError> {
+    Ok(match output {
+        Output::Json => pretty(value),
+        Output::Table => text(value),
+    })
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> CliError {
+    CliError::Io(format!("{}: {}", path.display(), err))
+}
+
+// ---------------- Tables ----------------
+
+fn listings(page: &Value) -> String {
+    let mut table = Table::new(&[
+        "NAME", "KIND", "PRICE", "RATING", "REVIEWS", "TAGS", "OWNER",
+    ]);
+    for listing in page["listings"].as_array().into_iter().flatten() {
+        table.row(vec![
+            table::cell(&listing["name"]),
+            table::cell(&listing["kind"]),
+            table::price(&listing["price"]),
+            table::rating(&listing["rating"]),
+            table::cell(&listing["review_count"]),
+            table::cell(&listing["tags"]),
+            table::cell(&listing["owner"]),
+        ]);
+    }
+
+    let mut out = table.render();
+    if let Some(cursor) = page["next_cursor"].as_str() {
+        out.push_str(&format!("More: --cursor {}\n", cursor));
+    }
+    out
+}
+
+fn listing_fields(listing: &Value) -> String {
+    table::fields(&[
+        ("name", table::cell(&listing["name"])),
+        ("kind", table::cell(&listing["kind"])),
+        ("owner", table::cell(&listing["owner"])),
+        ("size", table::cell(&listing["size"])),
+        ("chunks", table::cell(&listing["chunk_count"])),
+        ("model", table::cell(&listing["model"])),
+        ("dimension", table::cell(&listing["dimension"])),
+        ("price", table::price(&listing["price"])),
+        ("query price", table::cell(&listing["query_price"])),
+        ("license", table::license(&listing["license"])),
+        ("rating", table::rating(&listing["rating"])),
+        ("reviews", table::cell(&listing["review_count"])),
+        ("tags", table::cell(&listing["tags"])),
+        ("encrypted", table::cell(&listing["encrypted"])),
+        (
+            "created",
+            listing["created_at"]
+                .as_u64()
+                .map_or_else(|| "-".to_owned(), table::time),
+        ),
+    ])
+}
+
+fn order_fields(order: &Value) -> String {
+    let token = table::cell(&order["token"]);
+    let mut out = table::fields(&[
+        ("order", table::cell(&order["id"])),
+        ("item", table::cell(&order["item"])),
+        ("seller", table::cell(&order["seller"])),
+        (
+            "amount",
+            format!("{} {}", table::cell(&order["amount"]), token),
+        ),
+        ("fee", format!("{} {}", table::cell(&order["fee"]), token)),
+        ("license", table::license(&order["license"])),
+        ("status", table::cell(&order["status"])),
+        ("proposal", table::cell(&order["proposal_id"])),
+        (
+            "expires",
+            order["expires_at"]
+                .as_u64()
+                .map_or_else(|| "-".to_owned(), table::time),
+        ),
+    ]);
+    if let Some(proposal_id) = order["proposal_id"].as_str() {
+        out.push_str(&format!(
+            "The order is paid once proposal {} is approved.\n",
+            proposal_id
+        ));
+    }
+    out
+}
+
+fn messages(messages: &[Message]) -> String {
+    let mut table = Table::new(&["ID", "TIME", "AUTHOR", "TEXT"]);
+    for message in messages {
+        let text = if message.deleted {
+            "(deleted)".to_owned()
+        } else if message.edited_at.is_some() {
+            format!("{} (edited)", message.text)
+        } else {
+            message.text.clone()
+        };
+        table.row(vec![
+            message.id.to_string(),
+            table::time(message.created_at),
+            message.author.clone(),
+            text,
+        ]);
+    }
+    table.render()
+}
+
+fn search_hits(hits: &Value) -> String {
+    let mut table = Table::new(&["RANK", "CHUNK", "SCORE", "EXCERPT"]);
+    for (rank, hit) in hits.as_array().into_iter().flatten().enumerate() {
+        table.row(vec![
+            (rank + 1).to_string(),
+            table::cell(&hit["chunk_id"]),
+            hit["score"]
+                .as_f64()
+                .map_or_else(|| "-".to_owned(), |score| format!("{:.4}", score)),
+            table::cell(&hit["text_excerpt"]),
+        ]);
+    }
+    table.render()
+}
+
+// ---------------- Errors ----------------
+
+#[derive(Debug)]
+pub enum CliError {
+    /// The command line is wrong; the usage is printed after it.
+    Usage(String),
+    /// The node, context or identity could not be worked out.
+    Config(String),
+    /// The node could not be reached or answered with an error.
+    Node(ImportError),
+    Export(ExportError),
+    Io(String),
+    /// The node answered, but not with what the command expects.
+    Response(String),
+}
+
+impl From<ImportError> for CliError {
+    fn from(err: ImportError) -> Self {
+        CliError::Node(err)
+    }
+}
+
+impl From<ExportError> for CliError {
+    fn from(err: ExportError) -> Self {
+        CliError::Export(err)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(reason)
+            | CliError::Config(reason)
+            | CliError::Io(reason)
+            | CliError::Response(reason) => f.write_str(reason),
+            CliError::Node(err) => err.fmt(f),
+            CliError::Export(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
@@ -0,0 +1,75 @@
+use std::env;
+use std::process::ExitCode;
+
+use getem_cli::config::Config;
+use getem_cli::{parse_args, run, CliError, Invocation};
+
+const USAGE: &str = "\
+Usage: getem [options] <COMMAND>
+
+Commands:
+  upload <FILE> [--name <NAME>] [--encrypted]
+                          Store a text file as a listing [name: the file name]
+  list [--owner <KEY>] [--tag <TAG>] [--model <MODEL>] [--dimension <N>]
+       [--sort newest|oldest|name|price|price-desc] [--limit <N>] [--cursor <C>]
+                          Browse the catalog
+  show <NAME>             Show one listing
+  buy <NAME>              Open an order for a listing
+  approve <PROPOSAL_ID>   Approve a proposal, e.g. an order's payment
+  messages <PROPOSAL_ID> [--from <ID>] [--limit <N>] [--send <TEXT>]
+                          Read a proposal's thread, or post to it
+  search <DATASET> --vector <V1,V2,..> | --vector-file <FILE> [-k <N>]
+         [--metric cosine|dot|euclidean]
+                          Top-k similarity search on the node [k: 10]
+  download <NAME> [--output <PATH>]
+                          Save a file, to stdout without --output
+  download <NAME> --format <FORMAT> [--output <DIR>]
+                          Export a dataset: jsonl, npy, safetensors, qdrant or chroma
+
+Options:
+  --json                  Print the node's answer as JSON
+  --node-url <URL>        Node API address [env: NODE_URL, default: http://localhost:2428]
+  --context-id <ID>       Context to call [env: CONTEXT_ID, default: from the token]
+  --executor <KEY>        Executor public key [env: EXECUTOR_PUBLIC_KEY, default: from the token]
+  --token <JWT>           The frontend's access token [env: NODE_TOKEN]
+  --config <FILE>         Config file [env: GETEM_CONFIG, default: ~/.config/getem/config.json]
+";
+
+fn execute(invocation: Invocation) -> Result<String, CliError> {
+    let file = match invocation.config_path.or_else(Config::default_path) {
+        Some(path) => Config::load(&path)?,
+        None => Config::default(),
+    };
+    let config = invocation.config.or(Config::from_env()).or(file);
+
+    let client = config.client()?;
+    run(&client, &invocation.command, invocation.output)
+}
+
+fn main() -> ExitCode {
+    let invocation = match parse_args(env::args().skip(1)) {
+        Ok(invocation) => invocation,
+        Err(CliError::Usage(reason)) => {
+            if !reason.is_empty() {
+                eprintln!("error: {}\n", reason);
+            }
+            eprint!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match execute(invocation) {
+        Ok(out) => {
+            print!("{}", out);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
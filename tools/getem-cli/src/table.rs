@@ -0,0 +1,145 @@
+//! Plain-text rendering of the node's answers.
+
+use serde_json::Value;
+
+/// Left-aligned columns separated by two spaces.
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&'static str]) -> Self {
+        Table {
+            headers: headers.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(&mut self, cells: Vec<String>) {
+        debug_assert_eq!(cells.len(), self.headers.len());
+        self.rows.push(cells);
+    }
+
+    pub fn render(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|header| header.len()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let headers: Vec<String> = self
+            .headers
+            .iter()
+            .map(|header| header.to_string())
+            .collect();
+        let mut out = String::new();
+        for row in std::iter::once(&headers).chain(&self.rows) {
+            let mut line = String::new();
+            for (cell, width) in row.iter().zip(&widths) {
+                line.push_str(cell);
+                line.push_str(&" ".repeat(width - cell.chars().count() + 2));
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// `label: value` lines with the values aligned, skipping empty values.
+pub fn fields(pairs: &[(&str, String)]) -> String {
+    let pairs: Vec<&(&str, String)> = pairs.iter().filter(|(_, value)| value != "-").collect();
+    let width = pairs
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    for (label, value) in pairs {
+        out.push_str(&format!(
+            "{:width$}  {}\n",
+            format!("{}:", label),
+            value,
+            width = width + 1
+        ));
+    }
+    out
+}
+
+/// A JSON value as one cell: `-` for null, lists joined by commas and
+/// objects as compact JSON.
+pub fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_owned(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) if items.is_empty() => "-".to_owned(),
+        Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// A `Price` as `<amount> <token>`.
+pub fn price(value: &Value) -> String {
+    match (value["amount"].as_str(), value["token"].as_str()) {
+        (Some(amount), Some(token)) => format!("{} {}", amount, token),
+        _ => cell(value),
+    }
+}
+
+/// A rating average to one decimal.
+pub fn rating(value: &Value) -> String {
+    value
+        .as_f64()
+        .map_or_else(|| "-".to_owned(), |rating| format!("{:.1}", rating))
+}
+
+/// A `License` as its kind and the uses it allows, e.g.
+/// `Research (derivatives, model_training)`.
+pub fn license(value: &Value) -> String {
+    let kind = cell(&value["kind"]);
+    let allowed: Vec<&str> = value["allowed_uses"]
+        .as_object()
+        .map(|uses| {
+            uses.iter()
+                .filter(|(_, allowed)| allowed.as_bool() == Some(true))
+                .map(|(name, _)| name.as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    if allowed.is_empty() {
+        kind
+    } else {
+        format!("{} ({})", kind, allowed.join(", "))
+    }
+}
+
+/// A node timestamp (nanoseconds since the epoch) in UTC, to the second.
+pub fn time(nanos: u64) -> String {
+    let secs = nanos / 1_000_000_000;
+    let (days, rest) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01, after Howard Hinnant's
+    // `civil_from_days`.
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rest / 3_600,
+        rest % 3_600 / 60,
+        rest % 60
+    )
+}
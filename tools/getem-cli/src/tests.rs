@@ -0,0 +1,640 @@
+use std::sync::Mutex;
+
+use super::*;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL;
+use base64::Engine;
+use config::Claims;
+use getem_test_support::{Answer, Log};
+use getem_types::{CreateProposalRequest, ProposalStatus};
+
+fn parse(line: &str) -> Result<Invocation, CliError> {
+    parse_args(line.split_whitespace().map(str::to_owned))
+}
+
+fn command(line: &str) -> Command {
+    parse(line).unwrap().command
+}
+
+const PROPOSAL: &str = "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi";
+
+#[test]
+fn test_arguments_parse_into_commands() {
+    assert_eq!(
+        command("upload data/notes.csv --encrypted"),
+        Command::Upload {
+            file: PathBuf::from("data/notes.csv"),
+            name: "notes.csv".to_owned(),
+            encrypted: true,
+        }
+    );
+    assert_eq!(
+        command("list --tag nlp --sort price-desc --limit 5"),
+        Command::List {
+            filter: ListingFilter {
+                tag: Some("nlp".to_owned()),
+                ..ListingFilter::default()
+            },
+            sort: Some(ListingSort::PriceHighToLow),
+            cursor: None,
+            limit: Some(5),
+        }
+    );
+    assert_eq!(
+        command(&format!("messages {} --from 20", PROPOSAL)),
+        Command::Messages {
+            proposal_id: PROPOSAL.parse().unwrap(),
+            from: Some(20),
+            limit: None,
+            send: None,
+        }
+    );
+    assert_eq!(
+        command("download inferno --format npy --output out"),
+        Command::Download {
+            name: "inferno".to_owned(),
+            output: Some(PathBuf::from("out")),
+            format: Some(Format::Npy),
+        }
+    );
+
+    let invocation = parse("--json show notes --node-url http://node:2428").unwrap();
+    assert_eq!(invocation.output, Output::Json);
+    assert_eq!(
+        invocation.config.node_url.as_deref(),
+        Some("http://node:2428")
+    );
+    assert_eq!(
+        invocation.command,
+        Command::Show {
+            name: "notes".to_owned()
+        }
+    );
+}
+
+#[test]
+fn test_bad_arguments_are_usage_errors() {
+    for line in [
+        "",
+        "--help",
+        "sell notes",
+        "show",
+        "show notes extra",
+        "show notes --sort name",
+        "list --sort cheapest",
+        "list --limit",
+        "approve not-an-id",
+        "search inferno",
+        "search inferno --vector 1,x",
+    ] {
+        assert!(
+            matches!(parse(line), Err(CliError::Usage(_))),
+            "{:?} should not parse",
+            line
+        );
+    }
+}
+
+#[test]
+fn test_vectors_are_read_from_lists() {
+    assert_eq!(parse_vector("0.5,-1,2e-1").unwrap(), vec![0.5, -1.0, 0.2]);
+    assert_eq!(
+        parse_vector("[\n  1.0,\n  2.0\n]\n").unwrap(),
+        vec![1.0, 2.0]
+    );
+    assert!(parse_vector("[]").is_err());
+    assert!(parse_vector("1,NaN").is_err());
+}
+
+fn token(claims: Value) -> String {
+    format!(
+        "{}.{}.signature",
+        BASE64_URL.encode(br#"{"alg":"HS256","typ":"JWT"}"#),
+        BASE64_URL.encode(claims.to_string())
+    )
+}
+
+#[test]
+fn test_context_and_executor_come_from_the_token() {
+    let jwt = token(json!({
+        "context_id": "context-1",
+        "executor_public_key": "executor-1",
+        "token_type": "Access",
+        "exp": 1_700_000_000,
+        "sub": "node",
+    }));
+    assert_eq!(
+        Claims::decode(&jwt).unwrap(),
+        Claims {
+            context_id: "context-1".to_owned(),
+            executor_public_key: "executor-1".to_owned(),
+        }
+    );
+    assert!(matches!(
+        Claims::decode("not-a-jwt"),
+        Err(CliError::Config(_))
+    ));
+
+    let options = Config {
+        context_id: Some("context-2".to_owned()),
+        ..Config::default()
+    };
+    let file: Config = serde_json::from_value(json!({
+        "node_url": "http://node:2428/",
+        "access_token": jwt,
+    }))
+    .unwrap();
+    let config = options.or(Config::default()).or(file);
+    assert_eq!(config.context_id.as_deref(), Some("context-2"));
+    assert_eq!(
+        config.client().unwrap().endpoint(),
+        "http://node:2428/jsonrpc"
+    );
+
+    assert!(matches!(
+        Config::default().client(),
+        Err(CliError::Config(_))
+    ));
+}
+
+#[test]
+fn test_token_is_not_decoded_when_context_and_executor_are_given() {
+    let explicit = Config {
+        context_id: Some("context-1".to_owned()),
+        executor_public_key: Some("executor-1".to_owned()),
+        access_token: Some("not-a-jwt".to_owned()),
+        ..Config::default()
+    };
+    assert!(explicit.client().is_ok());
+
+    let missing_executor = Config {
+        executor_public_key: None,
+        ..explicit
+    };
+    assert!(matches!(
+        missing_executor.client(),
+        Err(CliError::Config(message)) if message.starts_with("invalid access token")
+    ));
+}
+
+#[test]
+fn test_times_print_in_utc() {
+    assert_eq!(table::time(0), "1970-01-01 00:00:00");
+    assert_eq!(
+        table::time(1_709_164_800_000_000_000 + 3_723_000_000_000),
+        "2024-02-29 01:02:03"
+    );
+}
+
+// ---------------- Against a node ----------------
+
+/// A stand-in node (see `getem_test_support::stand_in`) and a client
+/// calling it with an access token.
+fn stand_in(answer: impl FnMut(&str, &Value) -> Answer + Send + 'static) -> (RpcClient, Log) {
+    let (url, received) = getem_test_support::stand_in(answer);
+    let client = RpcClient::new(&url, "context-1", "executor-1").with_token(Some("jwt".to_owned()));
+    (client, received)
+}
+
+fn calls(received: &Log) -> Vec<(String, String)> {
+    received
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|call| {
+            (
+                call.body["method"].as_str().unwrap().to_owned(),
+                call.method().to_owned(),
+            )
+        })
+        .collect()
+}
+
+fn listing(name: &str, price: Option<u64>, tags: &[&str]) -> Value {
+    json!({
+        "name": name,
+        "kind": "Dataset",
+        "owner": "alice",
+        "size": null,
+        "chunk_count": 120,
+        "model": "all-MiniLM-L6-v2",
+        "dimension": 384,
+        "price": price.map(|amount| json!({"amount": amount.to_string(), "token": "ICP"})),
+        "license": {"kind": "Research", "allowed_uses": {
+            "commercial": false, "derivatives": true,
+            "redistribution": false, "model_training": true,
+        }},
+        "query_price": null,
+        "rating": 4.5,
+        "review_count": 4,
+        "tags": tags,
+        "encrypted": false,
+        "created_at": 0,
+    })
+}
+
+#[test]
+fn test_list_prints_the_catalog_page() {
+    let (client, received) = stand_in(|_, _| {
+        Ok(json!({
+            "listings": [
+                listing("inferno", Some(25), &["poetry", "nlp"]),
+                listing("sentences", None, &[]),
+            ],
+            "next_cursor": "c2VudGVuY2Vz",
+        }))
+    });
+
+    let out = run(
+        &client,
+        &command("list --tag nlp --sort price"),
+        Output::Table,
+    )
+    .unwrap();
+    assert_eq!(
+        out,
+        "\
+NAME       KIND     PRICE   RATING  REVIEWS  TAGS         OWNER
+inferno    Dataset  25 ICP  4.5     4        poetry, nlp  alice
+sentences  Dataset  -       4.5     4        -            alice
+More: --cursor c2VudGVuY2Vz
+"
+    );
+
+    let received = received.lock().unwrap();
+    let call = &received[0];
+    assert_eq!(call.authorization.as_deref(), Some("Bearer jwt"));
+    assert_eq!(call.body["method"], "query");
+    assert_eq!(call.body["params"]["method"], "list_listings");
+    assert_eq!(call.body["params"]["contextId"], "context-1");
+    assert_eq!(call.body["params"]["executorPublicKey"], "executor-1");
+    assert_eq!(
+        call.body["params"]["argsJson"],
+        json!({
+            "filter": {"tag": "nlp"},
+            "sort": "PriceLowToHigh",
+            "cursor": null,
+            "offset": null,
+            "limit": null,
+        })
+    );
+}
+
+#[test]
+fn test_json_output_is_the_node_answer() {
+    let answer = listing("inferno", Some(25), &["nlp"]);
+    let expected = answer.clone();
+    let (client, _) = stand_in(move |_, _| Ok(answer.clone()));
+
+    let out = run(&client, &command("show inferno"), Output::Json).unwrap();
+    assert_eq!(serde_json::from_str::<Value>(&out).unwrap(), expected);
+
+    let out = run(&client, &command("show inferno"), Output::Table).unwrap();
+    assert!(out.starts_with("name:       inferno\n"), "{}", out);
+    assert!(out.contains("license:    Research (derivatives, model_training)\n"));
+    assert!(!out.contains("size:"), "null fields are left out");
+}
+
+#[test]
+fn test_buy_then_approve_the_payment() {
+    let (client, received) = stand_in(|method, args| match method {
+        "open_order" => Ok(json!({
+            "id": 7,
+            "item": {"Listing": args["listing"]},
+            "buyer": "bob",
+            "seller": "alice",
+            "amount": "25",
+            "token": "ICP",
+            "fee": "1",
+            "license": {"kind": "Personal", "allowed_uses": {}},
+            "proposal_id": PROPOSAL,
+            "status": "Pending",
+            "created_at": 0,
+            "expires_at": 86_400_000_000_000u64,
+        })),
+        "approve_proposal" => Ok(Value::Null),
+        "get_proposal" => {
+            let mut record = ProposalRecord::new(
+                serde_json::from_value(args["proposal_id"].clone()).unwrap(),
+                "bob".to_owned(),
+                CreateProposalRequest::SetNumApprovals { num_approvals: 2 },
+                0,
+            );
+            record.approvers.push("alice".to_owned());
+            record.status = ProposalStatus::Executed;
+            Ok(serde_json::to_value(record).unwrap())
+        }
+        other => panic!("unexpected call {}", other),
+    });
+
+    let out = run(&client, &command("buy inferno"), Output::Table).unwrap();
+    assert!(out.contains("amount:    25 ICP\n"), "{}", out);
+    assert!(out.contains("expires:   1970-01-02 00:00:00\n"), "{}", out);
+    assert!(out.contains(&format!("proposal {} is approved", PROPOSAL)));
+
+    let out = run(
+        &client,
+        &command(&format!("approve {}", PROPOSAL)),
+        Output::Table,
+    )
+    .unwrap();
+    assert_eq!(
+        out,
+        format!("Approved {}: Executed with 2 approvals\n", PROPOSAL)
+    );
+
+    assert_eq!(
+        calls(&received),
+        [
+            ("execute".to_owned(), "open_order".to_owned()),
+            ("execute".to_owned(), "approve_proposal".to_owned()),
+            ("query".to_owned(), "get_proposal".to_owned()),
+        ]
+    );
+    let received = received.lock().unwrap();
+    assert_eq!(
+        received[0].body["params"]["argsJson"],
+        json!({"listing": "inferno"})
+    );
+    assert_eq!(
+        received[1].body["params"]["argsJson"],
+        json!({ "proposal_id": PROPOSAL })
+    );
+}
+
+#[test]
+fn test_approve_a_proposal_without_a_record() {
+    let (client, _) = stand_in(|method, _| match method {
+        "approve_proposal" => Ok(Value::Null),
+        "get_proposal" => Err(json!("proposal not found")),
+        other => panic!("unexpected call {}", other),
+    });
+
+    let approve = command(&format!("approve {}", PROPOSAL));
+    assert_eq!(
+        run(&client, &approve, Output::Table).unwrap(),
+        format!("Approved {}\n", PROPOSAL)
+    );
+    let out: Value = serde_json::from_str(&run(&client, &approve, Output::Json).unwrap()).unwrap();
+    assert_eq!(out, json!({ "id": PROPOSAL }));
+}
+
+#[test]
+fn test_messages_are_read_and_sent() {
+    let (client, received) = stand_in(|method, args| {
+        let message = |id: u64, text: &str, deleted: bool| {
+            json!({
+                "id": id,
+                "proposal_id": args["proposal_id"],
+                "author": "alice",
+                "text": text,
+                "created_at": 60_000_000_000u64 * id,
+                "edited_at": null,
+                "deleted": deleted,
+            })
+        };
+        match method {
+            "get_proposal_messages" => Ok(json!({
+                "messages": [message(0, "", true), message(1, "price ok?", false)],
+                "next": 2,
+            })),
+            "send_proposal_messages" => Ok(message(2, args["text"].as_str().unwrap(), false)),
+            other => panic!("unexpected call {}", other),
+        }
+    });
+
+    let out = run(
+        &client,
+        &command(&format!("messages {} --limit 2", PROPOSAL)),
+        Output::Table,
+    )
+    .unwrap();
+    assert_eq!(
+        out,
+        "\
+ID  TIME                 AUTHOR  TEXT
+0   1970-01-01 00:00:00  alice   (deleted)
+1   1970-01-01 00:01:00  alice   price ok?
+More: --from 2
+"
+    );
+
+    let invocation = parse_args(
+        ["messages", PROPOSAL, "--send", "yes, approving"]
+            .into_iter()
+            .map(str::to_owned),
+    )
+    .unwrap();
+    let out = run(&client, &invocation.command, Output::Table).unwrap();
+    assert!(
+        out.ends_with("2   1970-01-01 00:02:00  alice   yes, approving\n"),
+        "{}",
+        out
+    );
+
+    let received = received.lock().unwrap();
+    assert_eq!(
+        received[0].body["params"]["argsJson"],
+        json!({ "proposal_id": PROPOSAL, "limit": 2 })
+    );
+    assert_eq!(received[1].body["method"], "execute");
+    assert_eq!(
+        received[1].body["params"]["argsJson"],
+        json!({ "proposal_id": PROPOSAL, "text": "yes, approving" })
+    );
+}
+
+#[test]
+fn test_search_ranks_the_hits() {
+    let (client, received) = stand_in(|_, _| {
+        Ok(json!([
+            {"chunk_id": "canto-1", "score": 0.912_34, "text_excerpt": "Nel mezzo del cammin"},
+            {"chunk_id": "canto-3", "score": 0.5, "text_excerpt": null},
+        ]))
+    });
+
+    let out = run(
+        &client,
+        &command("search inferno --vector 0.1,0.2 -k 2 --metric dot"),
+        Output::Table,
+    )
+    .unwrap();
+    assert_eq!(
+        out,
+        "\
+RANK  CHUNK    SCORE   EXCERPT
+1     canto-1  0.9123  Nel mezzo del cammin
+2     canto-3  0.5000  -
+"
+    );
+
+    let received = received.lock().unwrap();
+    assert_eq!(received[0].body["method"], "execute");
+    assert_eq!(
+        received[0].body["params"]["argsJson"],
+        json!({
+            "dataset": "inferno",
+            "query_vector": [0.1f32, 0.2f32],
+            "k": 2,
+            "metric": "DotProduct",
+        })
+    );
+}
+
+/// A stand-in node keeping one file, uploaded whole or in chunks and
+/// downloaded by range. Downloads of "tampered" get a wrong digest.
+fn file_node() -> (RpcClient, Log) {
+    let stored = Mutex::new(String::new());
+    let chunks: Mutex<Vec<String>> = Mutex::default();
+    stand_in(move |method, args| match method {
+        "upload_file" => {
+            let content = args["content"].as_str().unwrap().to_owned();
+            let digest = sha256_hex(&content);
+            *stored.lock().unwrap() = content;
+            Ok(json!(digest))
+        }
+        "begin_upload" => Ok(json!({ "id": 7, "name": args["name"], "next_index": 0 })),
+        "append_upload_chunk" => {
+            let data = args["data"].as_str().unwrap();
+            assert!(data.len() <= MAX_CHUNK_BYTES);
+            assert_eq!(args["sha256"], sha256_hex(data));
+            let mut chunks = chunks.lock().unwrap();
+            assert_eq!(args["index"], chunks.len());
+            chunks.push(data.to_owned());
+            Ok(json!({ "id": 7, "next_index": chunks.len() }))
+        }
+        "finalize_upload" => {
+            let content = chunks.lock().unwrap().concat();
+            let digest = sha256_hex(&content);
+            assert_eq!(args["sha256"], digest);
+            *stored.lock().unwrap() = content;
+            Ok(json!(digest))
+        }
+        "download_range" => {
+            let content = stored.lock().unwrap().clone();
+            let offset = args["offset"].as_u64().unwrap() as usize;
+            let mut end = content
+                .len()
+                .min(offset + args["length"].as_u64().unwrap() as usize);
+            while !content.is_char_boundary(end) {
+                end -= 1;
+            }
+            let sha256 = if args["name"] == "tampered" {
+                "00".repeat(32)
+            } else {
+                sha256_hex(&content)
+            };
+            Ok(json!({
+                "data": &content[offset..end],
+                "next_offset": end,
+                "eof": end == content.len(),
+                "sha256": sha256,
+            }))
+        }
+        other => panic!("unexpected call {}", other),
+    })
+}
+
+#[test]
+fn test_upload_and_download_a_file() {
+    let dir = std::env::temp_dir().join(format!("getem-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("notes.csv");
+    fs::write(&file, "a,b\n1,2\n").unwrap();
+
+    let (client, received) = file_node();
+
+    let upload = Command::Upload {
+        file: file.clone(),
+        name: "notes".to_owned(),
+        encrypted: false,
+    };
+    let out = run(&client, &upload, Output::Table).unwrap();
+    assert!(out.contains("as notes (8 bytes, sha256 "), "{}", out);
+
+    assert_eq!(
+        run(&client, &command("download notes"), Output::Table).unwrap(),
+        "a,b\n1,2\n"
+    );
+
+    let saved = dir.join("copy.csv");
+    let download = Command::Download {
+        name: "notes".to_owned(),
+        output: Some(saved.clone()),
+        format: None,
+    };
+    run(&client, &download, Output::Table).unwrap();
+    assert_eq!(fs::read_to_string(&saved).unwrap(), "a,b\n1,2\n");
+
+    assert!(matches!(
+        run(&client, &command("download tampered"), Output::Table),
+        Err(CliError::Response(_))
+    ));
+
+    let methods: Vec<String> = calls(&received).into_iter().map(|(_, m)| m).collect();
+    assert_eq!(
+        methods,
+        [
+            "upload_file",
+            "download_range",
+            "download_range",
+            "download_range"
+        ]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_large_files_move_in_chunks() {
+    let dir = std::env::temp_dir().join(format!("getem-cli-large-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("corpus.txt");
+    // Two-byte characters, so the one-byte prefix puts every piece's
+    // natural end in the middle of a character.
+    let content = format!("x{}", "é".repeat(MAX_CHUNK_BYTES));
+    fs::write(&file, &content).unwrap();
+
+    let (client, received) = file_node();
+
+    let upload = Command::Upload {
+        file,
+        name: "corpus".to_owned(),
+        encrypted: true,
+    };
+    run(&client, &upload, Output::Table).unwrap();
+    assert_eq!(
+        run(&client, &command("download corpus"), Output::Table).unwrap(),
+        content
+    );
+
+    let calls = calls(&received);
+    let count = |method: &str| calls.iter().filter(|(_, m)| m == method).count();
+    assert_eq!(count("upload_file"), 0);
+    assert_eq!(count("begin_upload"), 1);
+    assert_eq!(count("append_upload_chunk"), 3);
+    assert_eq!(count("finalize_upload"), 1);
+    assert_eq!(count("download_range"), 3);
+
+    let received = received.lock().unwrap();
+    assert_eq!(
+        received[0].args(),
+        &json!({ "name": "corpus", "encrypted": true })
+    );
+    assert_eq!(received[1].body["method"], "execute");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_node_errors_are_reported() {
+    let (client, _) =
+        stand_in(|_, _| Err(json!({"type": "FunctionCallError", "data": "Unauthorized"})));
+
+    let err = run(&client, &command("download private"), Output::Table).unwrap_err();
+    assert!(
+        matches!(&err, CliError::Node(ImportError::Rpc(reason)) if reason.contains("Unauthorized")),
+        "{}",
+        err
+    );
+}
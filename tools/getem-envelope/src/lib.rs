@@ -0,0 +1,264 @@
+//! Client side of getem's envelope encryption.
+//!
+//! Everything in a Calimero context is replicated to every member, so an
+//! encrypted listing is sealed before it is uploaded. The seller picks a
+//! random [`ContentKey`], seals the file or each dataset chunk with it, and
+//! then wraps the key for every reader with [`wrap_key`]. The node stores
+//! the wrapped keys next to the listing; a buyer fetches theirs with
+//! `get_wrapped_key`, recovers the content key with [`unwrap_key`] and
+//! opens the content locally.
+//!
+//! Keys are wrapped to the reader's context identity: the ed25519 public
+//! key is converted to X25519, an ephemeral key agreement is run, and the
+//! shared secret goes through HKDF-SHA256 before ChaCha20-Poly1305.
+
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+#[cfg(test)]
+mod tests;
+
+/// Matches `ENVELOPE_ALGORITHM` in the app.
+pub const ALGORITHM: &str = "x25519-hkdf-sha256-chacha20poly1305";
+
+const NONCE_BYTES: usize = 12;
+const WRAP_INFO: &[u8] = b"getem-envelope wrapped content key";
+
+// ---------------- Keys ----------------
+
+/// The symmetric key a listing's content is sealed with.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ContentKey([u8; 32]);
+
+impl ContentKey {
+    pub fn generate() -> Self {
+        ContentKey(ChaCha20Poly1305::generate_key(&mut OsRng).into())
+    }
+
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        ContentKey(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.0))
+    }
+}
+
+impl fmt::Debug for ContentKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ContentKey(..)")
+    }
+}
+
+/// The private half of a context identity: the 32-byte ed25519 seed, or
+/// the 64-byte seed-plus-public-key form some tools export.
+pub struct IdentitySecret(SigningKey);
+
+impl IdentitySecret {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let seed: [u8; 32] = bytes
+            .get(..32)
+            .filter(|_| bytes.len() == 32 || bytes.len() == 64)
+            .and_then(|seed| seed.try_into().ok())
+            .ok_or(EnvelopeError::InvalidIdentity)?;
+        Ok(IdentitySecret(SigningKey::from_bytes(&seed)))
+    }
+
+    pub fn from_bs58(encoded: &str) -> Result<Self, EnvelopeError> {
+        let bytes = bs58::decode(encoded.trim())
+            .into_vec()
+            .map_err(|_| EnvelopeError::InvalidIdentity)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// The base58 public key the node reports as this identity.
+    pub fn identity(&self) -> String {
+        bs58::encode(self.0.verifying_key().as_bytes()).into_string()
+    }
+
+    fn x25519(&self) -> StaticSecret {
+        StaticSecret::from(self.0.to_scalar_bytes())
+    }
+}
+
+/// Same shape as the app's `SealedKey`: everything hex encoded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedKey {
+    pub ephemeral_public_key: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Encrypts `key` so that only the holder of `reader`'s identity can
+/// recover it. `reader` is the base58 identity the node uses.
+pub fn wrap_key(key: &ContentKey, reader: &str) -> Result<SealedKey, EnvelopeError> {
+    let reader = reader_public_key(reader)?;
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+
+    let shared = ephemeral.diffie_hellman(&reader);
+    if !shared.was_contributory() {
+        return Err(EnvelopeError::InvalidIdentity);
+    }
+
+    let cipher = key_encryption_key(shared.as_bytes(), &ephemeral_public, &reader);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, key.as_bytes().as_slice())
+        .map_err(|_| EnvelopeError::Encryption)?;
+
+    Ok(SealedKey {
+        ephemeral_public_key: hex::encode(ephemeral_public.as_bytes()),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    })
+}
+
+/// Recovers a content key wrapped for `identity`.
+pub fn unwrap_key(
+    sealed: &SealedKey,
+    identity: &IdentitySecret,
+) -> Result<ContentKey, EnvelopeError> {
+    let ephemeral_public = PublicKey::from(decode_hex::<32>(&sealed.ephemeral_public_key)?);
+    let nonce = decode_hex::<NONCE_BYTES>(&sealed.nonce)?;
+    let ciphertext = hex::decode(&sealed.ciphertext).map_err(|_| EnvelopeError::Malformed)?;
+
+    let secret = identity.x25519();
+    let own_public = PublicKey::from(&secret);
+    let shared = secret.diffie_hellman(&ephemeral_public);
+    if !shared.was_contributory() {
+        return Err(EnvelopeError::Decryption);
+    }
+
+    let cipher = key_encryption_key(shared.as_bytes(), &ephemeral_public, &own_public);
+    let key = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| EnvelopeError::Decryption)?;
+
+    key.try_into()
+        .map(ContentKey)
+        .map_err(|_| EnvelopeError::Malformed)
+}
+
+fn reader_public_key(reader: &str) -> Result<PublicKey, EnvelopeError> {
+    let bytes: [u8; 32] = bs58::decode(reader.trim())
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(EnvelopeError::InvalidIdentity)?;
+    let verifying = VerifyingKey::from_bytes(&bytes).map_err(|_| EnvelopeError::InvalidIdentity)?;
+    Ok(PublicKey::from(verifying.to_montgomery().to_bytes()))
+}
+
+fn key_encryption_key(
+    shared: &[u8],
+    ephemeral: &PublicKey,
+    reader: &PublicKey,
+) -> ChaCha20Poly1305 {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral.as_bytes());
+    salt[32..].copy_from_slice(reader.as_bytes());
+
+    let mut kek = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(WRAP_INFO, &mut kek)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    ChaCha20Poly1305::new(Key::from_slice(&kek))
+}
+
+// ---------------- Content ----------------
+
+/// Seals `plaintext` as base64 of `nonce || ciphertext`, the form stored in
+/// an encrypted file's content or a chunk's `sealed` field.
+pub fn seal(key: &ContentKey, plaintext: &[u8]) -> Result<String, EnvelopeError> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = key
+        .cipher()
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| EnvelopeError::Encryption)?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(BASE64.encode(sealed))
+}
+
+pub fn open(key: &ContentKey, sealed: &str) -> Result<Vec<u8>, EnvelopeError> {
+    let bytes = BASE64
+        .decode(sealed.trim())
+        .map_err(|_| EnvelopeError::Malformed)?;
+    if bytes.len() < NONCE_BYTES {
+        return Err(EnvelopeError::Malformed);
+    }
+
+    let (nonce, ciphertext) = bytes.split_at(NONCE_BYTES);
+    key.cipher()
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| EnvelopeError::Decryption)
+}
+
+/// What an encrypted dataset chunk's `sealed` field holds once opened: the
+/// metadata and vector a plaintext chunk would carry in the clear.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChunkContent {
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+    pub vector: Vec<f32>,
+}
+
+pub fn seal_chunk(key: &ContentKey, chunk: &ChunkContent) -> Result<String, EnvelopeError> {
+    let plaintext = serde_json::to_vec(chunk).map_err(|_| EnvelopeError::Encryption)?;
+    seal(key, &plaintext)
+}
+
+pub fn open_chunk(key: &ContentKey, sealed: &str) -> Result<ChunkContent, EnvelopeError> {
+    let plaintext = open(key, sealed)?;
+    serde_json::from_slice(&plaintext).map_err(|_| EnvelopeError::Malformed)
+}
+
+fn decode_hex<const N: usize>(value: &str) -> Result<[u8; N], EnvelopeError> {
+    hex::decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(EnvelopeError::Malformed)
+}
+
+// ---------------- Errors ----------------
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// Not a valid ed25519 identity.
+    InvalidIdentity,
+    /// The envelope or sealed content is not in the expected encoding.
+    Malformed,
+    /// Wrong key, or the data was tampered with.
+    Decryption,
+    Encryption,
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::InvalidIdentity => f.write_str("not a valid ed25519 identity"),
+            EnvelopeError::Malformed => f.write_str("malformed envelope"),
+            EnvelopeError::Decryption => {
+                f.write_str("decryption failed: wrong key or tampered data")
+            }
+            EnvelopeError::Encryption => f.write_str("encryption failed"),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
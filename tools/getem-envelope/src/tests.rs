@@ -0,0 +1,98 @@
+use super::*;
+
+fn identity(seed: u8) -> IdentitySecret {
+    IdentitySecret::from_bytes(&[seed; 32]).expect("32-byte seed")
+}
+
+#[test]
+fn test_wrapped_key_round_trip() {
+    let buyer = identity(7);
+    let key = ContentKey::generate();
+
+    let sealed = wrap_key(&key, &buyer.identity()).unwrap();
+    let unwrapped = unwrap_key(&sealed, &buyer).unwrap();
+
+    assert_eq!(unwrapped, key);
+}
+
+#[test]
+fn test_other_identity_cannot_unwrap() {
+    let buyer = identity(7);
+    let outsider = identity(8);
+    let key = ContentKey::generate();
+
+    let sealed = wrap_key(&key, &buyer.identity()).unwrap();
+
+    assert_eq!(
+        unwrap_key(&sealed, &outsider),
+        Err(EnvelopeError::Decryption)
+    );
+}
+
+#[test]
+fn test_sealed_key_has_the_shape_the_app_checks() {
+    let key = ContentKey::generate();
+    let sealed = wrap_key(&key, &identity(1).identity()).unwrap();
+
+    assert_eq!(hex::decode(&sealed.ephemeral_public_key).unwrap().len(), 32);
+    assert_eq!(hex::decode(&sealed.nonce).unwrap().len(), 12);
+    assert_eq!(hex::decode(&sealed.ciphertext).unwrap().len(), 48);
+}
+
+#[test]
+fn test_keypair_form_matches_seed() {
+    let seed = [3u8; 32];
+    let from_seed = IdentitySecret::from_bytes(&seed).unwrap();
+
+    let mut keypair = seed.to_vec();
+    keypair.extend_from_slice(&bs58::decode(from_seed.identity()).into_vec().unwrap());
+    let encoded = bs58::encode(&keypair).into_string();
+
+    assert_eq!(
+        IdentitySecret::from_bs58(&encoded).unwrap().identity(),
+        from_seed.identity()
+    );
+    assert!(IdentitySecret::from_bytes(&[0u8; 16]).is_err());
+}
+
+#[test]
+fn test_invalid_reader_is_rejected() {
+    let key = ContentKey::generate();
+
+    assert_eq!(
+        wrap_key(&key, "not-base58!").unwrap_err(),
+        EnvelopeError::InvalidIdentity
+    );
+}
+
+#[test]
+fn test_content_round_trip_and_tampering() {
+    let key = ContentKey::generate();
+    let sealed = seal(&key, b"private dataset").unwrap();
+
+    assert_eq!(open(&key, &sealed).unwrap(), b"private dataset");
+    assert_eq!(
+        open(&ContentKey::generate(), &sealed),
+        Err(EnvelopeError::Decryption)
+    );
+
+    let mut bytes = BASE64.decode(&sealed).unwrap();
+    *bytes.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        open(&key, &BASE64.encode(bytes)),
+        Err(EnvelopeError::Decryption)
+    );
+}
+
+#[test]
+fn test_chunk_round_trip() {
+    let key = ContentKey::generate();
+    let chunk = ChunkContent {
+        metadata: serde_json::json!({ "title": "Intro", "text_excerpt": "Hello" }),
+        vector: vec![0.25, -1.0, 3.5],
+    };
+
+    let sealed = seal_chunk(&key, &chunk).unwrap();
+
+    assert_eq!(open_chunk(&key, &sealed).unwrap(), chunk);
+}
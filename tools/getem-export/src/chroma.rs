@@ -0,0 +1,120 @@
+//! The body of Chroma's `POST /api/v1/collections/{id}/upsert`: parallel
+//! `ids`, `embeddings`, `metadatas` and `documents` lists.
+//!
+//! Chroma metadata is a flat, non-empty map without nulls, so only the
+//! fields a chunk has are written, attributes are prefixed with `attr:`,
+//! and a chunk without any is given `null` metadata. The excerpt
+//! is also the document, for Chroma's full-text filters.
+
+use std::collections::BTreeMap;
+
+use getem_import::{ChunkMetadata, Dataset, DistanceMetric, EmbeddingChunk};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{check, from_json, to_json, ExportError};
+
+const ATTRIBUTE_PREFIX: &str = "attr:";
+
+#[derive(Serialize)]
+struct Upsert<'a> {
+    ids: Vec<&'a str>,
+    embeddings: Vec<&'a [f32]>,
+    metadatas: Vec<Option<BTreeMap<String, String>>>,
+    documents: Vec<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct OwnedUpsert {
+    ids: Vec<String>,
+    embeddings: Vec<Vec<f32>>,
+    metadatas: Vec<Option<BTreeMap<String, String>>>,
+}
+
+/// The body of `POST /api/v1/collections`, creating a collection with the
+/// dataset's metric.
+pub fn collection(dataset: &Dataset) -> Value {
+    let space = match dataset.metric {
+        DistanceMetric::Cosine => "cosine",
+        DistanceMetric::DotProduct => "ip",
+        DistanceMetric::Euclidean => "l2",
+    };
+    json!({ "name": dataset.name, "metadata": { "hnsw:space": space } })
+}
+
+pub fn write(dataset: &Dataset) -> Result<String, ExportError> {
+    check(dataset)?;
+
+    let chunks = &dataset.chunks;
+    Ok(to_json(&Upsert {
+        ids: chunks.iter().map(|chunk| chunk.chunk_id.as_str()).collect(),
+        embeddings: chunks.iter().map(|chunk| chunk.vector.as_slice()).collect(),
+        metadatas: chunks
+            .iter()
+            .map(|chunk| flatten(&chunk.metadata))
+            .collect(),
+        documents: chunks
+            .iter()
+            .map(|chunk| chunk.metadata.text_excerpt.as_deref().unwrap_or_default())
+            .collect(),
+    }))
+}
+
+/// Reads the chunks back in list order.
+pub fn read(json: &str) -> Result<Vec<EmbeddingChunk>, ExportError> {
+    let upsert: OwnedUpsert = from_json("chroma upsert", json)?;
+    if upsert.embeddings.len() != upsert.ids.len() || upsert.metadatas.len() != upsert.ids.len() {
+        return Err(ExportError::Format(format!(
+            "chroma upsert: {} ids, {} embeddings and {} metadatas",
+            upsert.ids.len(),
+            upsert.embeddings.len(),
+            upsert.metadatas.len()
+        )));
+    }
+
+    Ok(upsert
+        .ids
+        .into_iter()
+        .zip(upsert.embeddings)
+        .zip(upsert.metadatas)
+        .map(|((chunk_id, vector), metadata)| EmbeddingChunk {
+            chunk_id,
+            metadata: unflatten(metadata.unwrap_or_default()),
+            vector,
+            sealed: None,
+        })
+        .collect())
+}
+
+fn flatten(metadata: &ChunkMetadata) -> Option<BTreeMap<String, String>> {
+    let fields = [
+        ("title", &metadata.title),
+        ("label", &metadata.label),
+        ("text_excerpt", &metadata.text_excerpt),
+    ];
+    let flat: BTreeMap<String, String> = fields
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_owned(), value.clone()?)))
+        .chain(
+            metadata
+                .attributes
+                .iter()
+                .map(|(key, value)| (format!("{}{}", ATTRIBUTE_PREFIX, key), value.clone())),
+        )
+        .collect();
+    (!flat.is_empty()).then_some(flat)
+}
+
+fn unflatten(mut flat: BTreeMap<String, String>) -> ChunkMetadata {
+    ChunkMetadata {
+        title: flat.remove("title"),
+        label: flat.remove("label"),
+        text_excerpt: flat.remove("text_excerpt"),
+        attributes: flat
+            .into_iter()
+            .filter_map(|(key, value)| {
+                Some((key.strip_prefix(ATTRIBUTE_PREFIX)?.to_owned(), value))
+            })
+            .collect(),
+    }
+}
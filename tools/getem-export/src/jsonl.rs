@@ -0,0 +1,57 @@
+//! One JSON object per chunk and line:
+//!
+//! ```json
+//! {"id":"1","vector":[0.1,0.2],"metadata":{"title":null,"label":null,"text_excerpt":"...","attributes":{}}}
+//! ```
+
+use getem_import::{ChunkMetadata, Dataset, EmbeddingChunk};
+use serde::{Deserialize, Serialize};
+
+use crate::{check, from_json, to_json, ExportError};
+
+#[derive(Serialize)]
+struct Line<'a> {
+    id: &'a str,
+    vector: &'a [f32],
+    metadata: &'a ChunkMetadata,
+}
+
+#[derive(Deserialize)]
+struct OwnedLine {
+    id: String,
+    vector: Vec<f32>,
+    #[serde(default)]
+    metadata: ChunkMetadata,
+}
+
+pub fn write(dataset: &Dataset) -> Result<String, ExportError> {
+    check(dataset)?;
+
+    let mut out = String::new();
+    for chunk in &dataset.chunks {
+        out.push_str(&to_json(&Line {
+            id: &chunk.chunk_id,
+            vector: &chunk.vector,
+            metadata: &chunk.metadata,
+        }));
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Reads the chunks back in file order. Blank lines are skipped.
+pub fn read(text: &str) -> Result<Vec<EmbeddingChunk>, ExportError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let line: OwnedLine = from_json(&format!("line {}", index + 1), line)?;
+            Ok(EmbeddingChunk {
+                chunk_id: line.id,
+                metadata: line.metadata,
+                vector: line.vector,
+                sealed: None,
+            })
+        })
+        .collect()
+}
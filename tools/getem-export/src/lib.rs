@@ -0,0 +1,261 @@
+//! Exports getem datasets to the formats RAG stacks and vector stores read
+//! directly, instead of the app's own `EmbeddingChunk` JSON:
+//!
+//! - [`jsonl`]: one `{id, vector, metadata}` object per line.
+//! - [`npy`]: the vectors as an `(n, dimension)` `float32` matrix, with ids
+//!   and metadata in a sidecar JSON file, row for row.
+//! - [`safetensors`]: the same matrix as the tensor `embeddings`, with ids
+//!   and metadata in the header.
+//! - [`qdrant`] and [`chroma`]: the JSON bodies of their upsert calls.
+//!
+//! Every format reads back to the same ids, metadata and bit-identical
+//! floats. JSON formats write each float as the shortest decimal that
+//! parses back to it; NaN and infinities have no JSON form and are
+//! rejected by all formats alike.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use getem_import::rpc::RpcClient;
+use getem_import::{Dataset, DistanceMetric, EmbeddingChunk, ImportError};
+use getem_types::DatasetInfo;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+pub mod chroma;
+pub mod jsonl;
+pub mod npy;
+pub mod qdrant;
+pub mod safetensors;
+
+#[cfg(test)]
+mod tests;
+
+/// Chunks fetched per `get_dataset_chunks` call.
+pub const DEFAULT_PAGE_SIZE: u64 = 256;
+
+// ---------------- Formats ----------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Jsonl,
+    Npy,
+    Safetensors,
+    Qdrant,
+    Chroma,
+}
+
+impl FromStr for Format {
+    type Err = ExportError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "jsonl" => Ok(Format::Jsonl),
+            "npy" | "numpy" => Ok(Format::Npy),
+            "safetensors" => Ok(Format::Safetensors),
+            "qdrant" => Ok(Format::Qdrant),
+            "chroma" => Ok(Format::Chroma),
+            _ => Err(ExportError::Format(format!(
+                "unknown export format {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Writes `dataset` into `dir` and returns the files written:
+///
+/// | format        | files                                   |
+/// |---------------|-----------------------------------------|
+/// | `Jsonl`       | `<name>.jsonl`                          |
+/// | `Npy`         | `<name>.npy`, `<name>.json` (sidecar)   |
+/// | `Safetensors` | `<name>.safetensors`                    |
+/// | `Qdrant`      | `<name>.qdrant.json`                    |
+/// | `Chroma`      | `<name>.chroma.json`                    |
+///
+/// Characters other than letters, digits, `.`, `-` and `_` in the dataset
+/// name become `_`.
+pub fn export(dataset: &Dataset, format: Format, dir: &Path) -> Result<Vec<PathBuf>, ExportError> {
+    let stem = file_stem(&dataset.name);
+    let files = match format {
+        Format::Jsonl => vec![(
+            format!("{}.jsonl", stem),
+            jsonl::write(dataset)?.into_bytes(),
+        )],
+        Format::Npy => {
+            let (matrix, sidecar) = npy::write(dataset)?;
+            vec![
+                (format!("{}.npy", stem), matrix),
+                (format!("{}.json", stem), sidecar.into_bytes()),
+            ]
+        }
+        Format::Safetensors => vec![(
+            format!("{}.safetensors", stem),
+            safetensors::write(dataset)?,
+        )],
+        Format::Qdrant => vec![(
+            format!("{}.qdrant.json", stem),
+            qdrant::write(dataset)?.into_bytes(),
+        )],
+        Format::Chroma => vec![(
+            format!("{}.chroma.json", stem),
+            chroma::write(dataset)?.into_bytes(),
+        )],
+    };
+
+    fs::create_dir_all(dir)
+        .map_err(|err| ExportError::Io(format!("{}: {}", dir.display(), err)))?;
+    files
+        .into_iter()
+        .map(|(file, bytes)| {
+            let path = dir.join(file);
+            fs::write(&path, bytes)
+                .map_err(|err| ExportError::Io(format!("{}: {}", path.display(), err)))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Checks what every format relies on: one dimension and finite values.
+pub fn check(dataset: &Dataset) -> Result<(), ExportError> {
+    let expected = dataset.dimension as usize;
+    for chunk in &dataset.chunks {
+        if chunk.vector.len() != expected {
+            return Err(ExportError::DimensionMismatch {
+                chunk_id: chunk.chunk_id.clone(),
+                expected,
+                found: chunk.vector.len(),
+            });
+        }
+        if !chunk.vector.iter().all(|value| value.is_finite()) {
+            return Err(ExportError::NonFinite(chunk.chunk_id.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// The metric as the app spells it, e.g. `DotProduct`.
+fn metric_name(metric: DistanceMetric) -> String {
+    match serde_json::to_value(metric) {
+        Ok(Value::String(name)) => name,
+        _ => unreachable!("DistanceMetric serializes to its variant name"),
+    }
+}
+
+fn metric_from_name(name: &str) -> Result<DistanceMetric, ExportError> {
+    serde_json::from_value(Value::String(name.to_owned()))
+        .map_err(|_| ExportError::Format(format!("unknown metric {}", name)))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("export records serialize to JSON")
+}
+
+fn from_json<T: DeserializeOwned>(what: &str, json: &str) -> Result<T, ExportError> {
+    serde_json::from_str(json).map_err(|err| ExportError::Format(format!("{}: {}", what, err)))
+}
+
+// ---------------- Fetch ----------------
+
+/// Reads a whole dataset from the node, `page_size` chunks per call. The
+/// client's identity must own the dataset or have bought access to it.
+pub fn fetch(client: &RpcClient, name: &str, page_size: u64) -> Result<Dataset, ExportError> {
+    let info: DatasetInfo = output(client.query("get_dataset", json!({ "name": name }))?)?;
+    if info.encrypted {
+        return Err(ExportError::Encrypted(info.name));
+    }
+
+    let page_size = page_size.max(1);
+    let mut chunks: Vec<EmbeddingChunk> = Vec::new();
+    while (chunks.len() as u64) < info.chunk_count {
+        let page: Vec<EmbeddingChunk> = output(client.query(
+            "get_dataset_chunks",
+            json!({ "name": name, "offset": chunks.len(), "limit": page_size }),
+        )?)?;
+        if page.is_empty() {
+            break;
+        }
+        chunks.extend(page);
+    }
+
+    let dataset = Dataset {
+        name: info.name,
+        model: info.model,
+        metric: info.metric,
+        dimension: info.dimension,
+        chunks,
+    };
+    check(&dataset)?;
+    Ok(dataset)
+}
+
+fn output<T: DeserializeOwned>(value: Value) -> Result<T, ExportError> {
+    serde_json::from_value(value)
+        .map_err(|err| ExportError::Node(ImportError::Rpc(format!("unexpected output: {}", err))))
+}
+
+// ---------------- Errors ----------------
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExportError {
+    /// The node could not be reached or answered with an error.
+    Node(ImportError),
+    /// Encrypted datasets must be opened with `getem-envelope` first.
+    Encrypted(String),
+    DimensionMismatch {
+        chunk_id: String,
+        expected: usize,
+        found: usize,
+    },
+    /// The chunk holds NaN or an infinity.
+    NonFinite(String),
+    /// An export being read back is malformed, or an option is unknown.
+    Format(String),
+    Io(String),
+}
+
+impl From<ImportError> for ExportError {
+    fn from(err: ImportError) -> Self {
+        ExportError::Node(err)
+    }
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Node(err) => err.fmt(f),
+            ExportError::Encrypted(name) => write!(
+                f,
+                "dataset {} is encrypted; decrypt its chunks before exporting",
+                name
+            ),
+            ExportError::DimensionMismatch {
+                chunk_id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "chunk {} has dimension {}, expected {}",
+                chunk_id, found, expected
+            ),
+            ExportError::NonFinite(chunk_id) => {
+                write!(f, "chunk {} holds a NaN or infinite value", chunk_id)
+            }
+            ExportError::Format(reason) => write!(f, "invalid export: {}", reason),
+            ExportError::Io(reason) => write!(f, "could not write export: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
@@ -0,0 +1,192 @@
+//! NumPy `.npy` (format 1.0) holding the vectors as an `(n, dimension)`
+//! little-endian `float32` matrix, plus a JSON sidecar with the dataset
+//! fields and each row's id and metadata:
+//!
+//! ```python
+//! vectors = numpy.load("inferno.npy")
+//! sidecar = json.load(open("inferno.json"))
+//! ```
+
+use getem_import::{ChunkMetadata, Dataset, DistanceMetric, EmbeddingChunk};
+use serde::{Deserialize, Serialize};
+
+use crate::{check, from_json, to_json, ExportError};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// NumPy aligns the data to 64 bytes so it can be memory-mapped.
+const ALIGNMENT: usize = 64;
+
+#[derive(Serialize, Deserialize)]
+pub struct Sidecar {
+    pub name: String,
+    pub model: String,
+    pub metric: DistanceMetric,
+    pub dimension: u32,
+    /// Chunk id of each row.
+    pub ids: Vec<String>,
+    /// Metadata of each row.
+    pub metadata: Vec<ChunkMetadata>,
+}
+
+/// Returns the `.npy` bytes and the sidecar JSON.
+pub fn write(dataset: &Dataset) -> Result<(Vec<u8>, String), ExportError> {
+    check(dataset)?;
+
+    let rows = dataset.chunks.len();
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        rows, dataset.dimension
+    );
+    // Magic, version and length come first; the header ends in a newline.
+    let unpadded = MAGIC.len() + 2 + 2 + header.len() + 1;
+    header.push_str(&" ".repeat((ALIGNMENT - unpadded % ALIGNMENT) % ALIGNMENT));
+    header.push('\n');
+
+    let length = u16::try_from(header.len())
+        .map_err(|_| ExportError::Format("npy header too long".to_owned()))?;
+    let mut npy = Vec::with_capacity(unpadded + rows * dataset.dimension as usize * 4);
+    npy.extend_from_slice(MAGIC);
+    npy.extend_from_slice(&[1, 0]);
+    npy.extend_from_slice(&length.to_le_bytes());
+    npy.extend_from_slice(header.as_bytes());
+    for chunk in &dataset.chunks {
+        for value in &chunk.vector {
+            npy.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    let sidecar = Sidecar {
+        name: dataset.name.clone(),
+        model: dataset.model.clone(),
+        metric: dataset.metric,
+        dimension: dataset.dimension,
+        ids: dataset
+            .chunks
+            .iter()
+            .map(|chunk| chunk.chunk_id.clone())
+            .collect(),
+        metadata: dataset
+            .chunks
+            .iter()
+            .map(|chunk| chunk.metadata.clone())
+            .collect(),
+    };
+    Ok((npy, to_json(&sidecar)))
+}
+
+/// Reads an export back. The matrix may be any `.npy` version, but must be
+/// a C-ordered little-endian `float32` matrix with a row per sidecar id.
+pub fn read(npy: &[u8], sidecar: &str) -> Result<Dataset, ExportError> {
+    let sidecar: Sidecar = from_json("sidecar", sidecar)?;
+    let (shape, values) = read_matrix(npy)?;
+
+    let (rows, columns) = match shape[..] {
+        [rows, columns] => (rows, columns),
+        _ => {
+            return Err(invalid(format!(
+                "expected a matrix, found shape {:?}",
+                shape
+            )))
+        }
+    };
+    if rows != sidecar.ids.len() || rows != sidecar.metadata.len() {
+        return Err(invalid(format!(
+            "{} rows but {} ids and {} metadata entries",
+            rows,
+            sidecar.ids.len(),
+            sidecar.metadata.len()
+        )));
+    }
+    if columns != sidecar.dimension as usize {
+        return Err(invalid(format!(
+            "{} columns but dimension {}",
+            columns, sidecar.dimension
+        )));
+    }
+
+    let chunks = sidecar
+        .ids
+        .into_iter()
+        .zip(sidecar.metadata)
+        .zip(values.chunks(columns.max(1)))
+        .map(|((chunk_id, metadata), vector)| EmbeddingChunk {
+            chunk_id,
+            metadata,
+            vector: vector.to_vec(),
+            sealed: None,
+        })
+        .collect();
+
+    Ok(Dataset {
+        name: sidecar.name,
+        model: sidecar.model,
+        metric: sidecar.metric,
+        dimension: sidecar.dimension,
+        chunks,
+    })
+}
+
+/// Parses a `.npy` file of `float32`s into its shape and values.
+pub fn read_matrix(npy: &[u8]) -> Result<(Vec<usize>, Vec<f32>), ExportError> {
+    let rest = npy
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| invalid("not an npy file".to_owned()))?;
+    let (length, rest) = match rest {
+        [1, _, a, b, rest @ ..] => (u16::from_le_bytes([*a, *b]) as usize, rest),
+        [2 | 3, _, a, b, c, d, rest @ ..] => (u32::from_le_bytes([*a, *b, *c, *d]) as usize, rest),
+        _ => return Err(invalid("unsupported npy version".to_owned())),
+    };
+    if rest.len() < length {
+        return Err(invalid("truncated header".to_owned()));
+    }
+    let (header, data) = rest.split_at(length);
+    let header =
+        std::str::from_utf8(header).map_err(|_| invalid("header is not text".to_owned()))?;
+
+    let descr = after(header, "descr")
+        .and_then(|value| value.strip_prefix('\''))
+        .and_then(|value| value.split('\'').next());
+    if descr != Some("<f4") {
+        return Err(invalid(format!("expected '<f4' values, found {:?}", descr)));
+    }
+    if !after(header, "fortran_order").is_some_and(|value| value.starts_with("False")) {
+        return Err(invalid("expected C-ordered values".to_owned()));
+    }
+    let shape = after(header, "shape")
+        .and_then(|value| value.strip_prefix('('))
+        .and_then(|value| value.split(')').next())
+        .ok_or_else(|| invalid("missing shape".to_owned()))?
+        .split(',')
+        .map(str::trim)
+        .filter(|size| !size.is_empty())
+        .map(|size| size.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid("malformed shape".to_owned()))?;
+
+    let count: usize = shape.iter().product();
+    if data.len() != count * 4 {
+        return Err(invalid(format!(
+            "shape {:?} needs {} bytes of data, found {}",
+            shape,
+            count * 4,
+            data.len()
+        )));
+    }
+    let values = data
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect();
+    Ok((shape, values))
+}
+
+/// The text following `'key':` in a header dictionary.
+fn after<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let pattern = format!("'{}':", key);
+    let start = header.find(&pattern)? + pattern.len();
+    Some(header[start..].trim_start())
+}
+
+fn invalid(reason: String) -> ExportError {
+    ExportError::Format(format!("npy: {}", reason))
+}
@@ -0,0 +1,85 @@
+//! The body of Qdrant's `PUT /collections/{name}/points`. Qdrant point ids
+//! must be integers or UUIDs, so points are numbered by row and the chunk
+//! id travels in the payload next to the metadata fields:
+//!
+//! ```json
+//! {"points":[{"id":0,"vector":[0.1,0.2],"payload":{"chunk_id":"1","title":null,...}}]}
+//! ```
+
+use getem_import::{ChunkMetadata, Dataset, DistanceMetric, EmbeddingChunk};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{check, from_json, to_json, ExportError};
+
+#[derive(Serialize, Deserialize)]
+struct Upsert<P> {
+    points: Vec<P>,
+}
+
+#[derive(Serialize)]
+struct Point<'a> {
+    id: usize,
+    vector: &'a [f32],
+    payload: Payload<&'a str, &'a ChunkMetadata>,
+}
+
+#[derive(Deserialize)]
+struct OwnedPoint {
+    id: u64,
+    vector: Vec<f32>,
+    payload: Payload<String, ChunkMetadata>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Payload<I, M> {
+    chunk_id: I,
+    #[serde(flatten)]
+    metadata: M,
+}
+
+/// The body of `PUT /collections/{name}`, creating a collection the
+/// points fit.
+pub fn collection(dataset: &Dataset) -> Value {
+    let distance = match dataset.metric {
+        DistanceMetric::Cosine => "Cosine",
+        DistanceMetric::DotProduct => "Dot",
+        DistanceMetric::Euclidean => "Euclid",
+    };
+    json!({ "vectors": { "size": dataset.dimension, "distance": distance } })
+}
+
+pub fn write(dataset: &Dataset) -> Result<String, ExportError> {
+    check(dataset)?;
+
+    let points = dataset
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(id, chunk)| Point {
+            id,
+            vector: &chunk.vector,
+            payload: Payload {
+                chunk_id: &chunk.chunk_id,
+                metadata: &chunk.metadata,
+            },
+        })
+        .collect();
+    Ok(to_json(&Upsert { points }))
+}
+
+/// Reads the chunks back in point id order.
+pub fn read(json: &str) -> Result<Vec<EmbeddingChunk>, ExportError> {
+    let mut upsert: Upsert<OwnedPoint> = from_json("qdrant points", json)?;
+    upsert.points.sort_by_key(|point| point.id);
+    Ok(upsert
+        .points
+        .into_iter()
+        .map(|point| EmbeddingChunk {
+            chunk_id: point.payload.chunk_id,
+            metadata: point.payload.metadata,
+            vector: point.vector,
+            sealed: None,
+        })
+        .collect())
+}
@@ -0,0 +1,150 @@
+//! safetensors with a single `F32` tensor, `embeddings`, of shape
+//! `[n, dimension]`. Header metadata is string to string, so it holds the
+//! dataset fields as text and the row ids and metadata as JSON arrays:
+//!
+//! ```python
+//! with safe_open("inferno.safetensors", "np") as f:
+//!     vectors = f.get_tensor("embeddings")
+//!     ids = json.loads(f.metadata()["ids"])
+//! ```
+
+use std::collections::BTreeMap;
+
+use getem_import::{ChunkMetadata, Dataset, EmbeddingChunk};
+use serde_json::{json, Value};
+
+use crate::{check, from_json, metric_from_name, metric_name, to_json, ExportError};
+
+/// Name of the tensor holding the vectors.
+pub const TENSOR: &str = "embeddings";
+
+/// Readers expect the data to start on an 8-byte boundary.
+const ALIGNMENT: usize = 8;
+
+pub fn write(dataset: &Dataset) -> Result<Vec<u8>, ExportError> {
+    check(dataset)?;
+
+    let rows = dataset.chunks.len();
+    let size = rows * dataset.dimension as usize * 4;
+    let ids: Vec<&str> = dataset
+        .chunks
+        .iter()
+        .map(|chunk| chunk.chunk_id.as_str())
+        .collect();
+    let metadata: Vec<&ChunkMetadata> =
+        dataset.chunks.iter().map(|chunk| &chunk.metadata).collect();
+
+    let mut header = to_json(&json!({
+        "__metadata__": {
+            "name": dataset.name,
+            "model": dataset.model,
+            "metric": metric_name(dataset.metric),
+            "dimension": dataset.dimension.to_string(),
+            "ids": to_json(&ids),
+            "metadata": to_json(&metadata),
+        },
+        TENSOR: {
+            "dtype": "F32",
+            "shape": [rows, dataset.dimension],
+            "data_offsets": [0, size],
+        },
+    }));
+    header.push_str(&" ".repeat((ALIGNMENT - header.len() % ALIGNMENT) % ALIGNMENT));
+
+    let mut bytes = Vec::with_capacity(8 + header.len() + size);
+    bytes.extend_from_slice(&(header.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    for chunk in &dataset.chunks {
+        for value in &chunk.vector {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    Ok(bytes)
+}
+
+pub fn read(bytes: &[u8]) -> Result<Dataset, ExportError> {
+    let length = bytes
+        .get(..8)
+        .map(|prefix| u64::from_le_bytes(prefix.try_into().expect("8 bytes")) as usize)
+        .ok_or_else(|| invalid("missing header length".to_owned()))?;
+    let header = bytes
+        .get(8..8usize.saturating_add(length))
+        .ok_or_else(|| invalid("truncated header".to_owned()))?;
+    let data = &bytes[8 + length..];
+    let header =
+        std::str::from_utf8(header).map_err(|_| invalid("header is not text".to_owned()))?;
+    let header: Value = from_json("safetensors header", header)?;
+
+    let metadata: BTreeMap<String, String> = header
+        .get("__metadata__")
+        .cloned()
+        .and_then(|metadata| serde_json::from_value(metadata).ok())
+        .ok_or_else(|| invalid("missing __metadata__".to_owned()))?;
+    let field = |key: &str| {
+        metadata
+            .get(key)
+            .map(String::as_str)
+            .ok_or_else(|| invalid(format!("missing metadata {}", key)))
+    };
+    let dimension: u32 = field("dimension")?
+        .parse()
+        .map_err(|_| invalid("malformed dimension".to_owned()))?;
+    let ids: Vec<String> = from_json("ids", field("ids")?)?;
+    let chunk_metadata: Vec<ChunkMetadata> = from_json("metadata", field("metadata")?)?;
+
+    let tensor = header
+        .get(TENSOR)
+        .ok_or_else(|| invalid(format!("missing tensor {}", TENSOR)))?;
+    if tensor["dtype"] != "F32" {
+        return Err(invalid(format!("expected F32, found {}", tensor["dtype"])));
+    }
+    let sizes = |key: &str| -> Result<Vec<usize>, ExportError> {
+        serde_json::from_value(tensor[key].clone())
+            .map_err(|_| invalid(format!("malformed {}", key)))
+    };
+    let shape = sizes("shape")?;
+    if shape != [ids.len(), dimension as usize] || chunk_metadata.len() != ids.len() {
+        return Err(invalid(format!(
+            "shape {:?} does not match {} ids of dimension {}",
+            shape,
+            ids.len(),
+            dimension
+        )));
+    }
+    let values = match sizes("data_offsets")?[..] {
+        [start, end]
+            if end.checked_sub(start) == Some(ids.len() * dimension as usize * 4)
+                && end <= data.len() =>
+        {
+            &data[start..end]
+        }
+        _ => return Err(invalid("data offsets do not match the shape".to_owned())),
+    };
+
+    let chunks = ids
+        .into_iter()
+        .zip(chunk_metadata)
+        .zip(values.chunks(dimension.max(1) as usize * 4))
+        .map(|((chunk_id, metadata), row)| EmbeddingChunk {
+            chunk_id,
+            metadata,
+            vector: row
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect(),
+            sealed: None,
+        })
+        .collect();
+
+    Ok(Dataset {
+        name: field("name")?.to_owned(),
+        model: field("model")?.to_owned(),
+        metric: metric_from_name(field("metric")?)?,
+        dimension,
+        chunks,
+    })
+}
+
+fn invalid(reason: String) -> ExportError {
+    ExportError::Format(format!("safetensors: {}", reason))
+}
@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+
+use super::*;
+
+use getem_import::ChunkMetadata;
+use getem_test_support::{stand_in, Log};
+use serde_json::json;
+
+/// Values that only survive a format if it keeps every bit.
+const AWKWARD: [f32; 8] = [
+    0.1,
+    -0.0,
+    1.0e-45,
+    f32::MIN_POSITIVE,
+    f32::MAX,
+    -f32::MAX,
+    std::f32::consts::PI,
+    0.333_333_34,
+];
+
+fn chunk(chunk_id: &str, vector: Vec<f32>) -> EmbeddingChunk {
+    EmbeddingChunk {
+        chunk_id: chunk_id.to_owned(),
+        metadata: ChunkMetadata::default(),
+        vector,
+        sealed: None,
+    }
+}
+
+fn sample() -> Dataset {
+    let mut described = chunk("canto-1", AWKWARD.to_vec());
+    described.metadata = ChunkMetadata {
+        title: Some("Divina Commedia: Inferno".to_owned()),
+        label: Some("Canto I".to_owned()),
+        text_excerpt: Some("Nel mezzo del cammin di nostra vita \"…\"\nmi ritrovai".to_owned()),
+        attributes: BTreeMap::from([
+            ("canto".to_owned(), "1".to_owned()),
+            ("title".to_owned(), "shadowed by the field".to_owned()),
+            ("empty".to_owned(), String::new()),
+        ]),
+    };
+
+    let mut excerpt_only = chunk("2", AWKWARD.iter().rev().copied().collect());
+    excerpt_only.metadata.text_excerpt = Some(String::new());
+
+    Dataset {
+        name: "inferno/cantos".to_owned(),
+        model: "all-MiniLM-L6-v2".to_owned(),
+        metric: DistanceMetric::DotProduct,
+        dimension: AWKWARD.len() as u32,
+        chunks: vec![
+            described,
+            excerpt_only,
+            chunk("bare", AWKWARD.iter().map(|value| value / 3.0).collect()),
+        ],
+    }
+}
+
+/// Compares floats by bits, so `-0.0` and `0.0` differ.
+fn assert_same_chunks(found: &[EmbeddingChunk], expected: &[EmbeddingChunk]) {
+    assert_eq!(found, expected);
+    for (found, expected) in found.iter().zip(expected) {
+        let bits = |chunk: &EmbeddingChunk| -> Vec<u32> {
+            chunk.vector.iter().map(|value| value.to_bits()).collect()
+        };
+        assert_eq!(bits(found), bits(expected), "{}", expected.chunk_id);
+    }
+}
+
+fn assert_same_dataset(found: &Dataset, expected: &Dataset) {
+    assert_eq!(
+        (&found.name, &found.model, found.metric, found.dimension),
+        (
+            &expected.name,
+            &expected.model,
+            expected.metric,
+            expected.dimension
+        )
+    );
+    assert_same_chunks(&found.chunks, &expected.chunks);
+}
+
+// ---------------- Round Trips ----------------
+
+#[test]
+fn test_jsonl_round_trips() {
+    let dataset = sample();
+    let text = jsonl::write(&dataset).unwrap();
+
+    assert_eq!(text.lines().count(), 3);
+    let first: Value = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+    assert_eq!(first["id"], "canto-1");
+    assert_eq!(first["metadata"]["attributes"]["canto"], "1");
+
+    assert_same_chunks(&jsonl::read(&text).unwrap(), &dataset.chunks);
+}
+
+#[test]
+fn test_npy_round_trips() {
+    let dataset = sample();
+    let (matrix, sidecar) = npy::write(&dataset).unwrap();
+
+    // Data starts on a 64-byte boundary, after a header NumPy can parse.
+    let header_length = u16::from_le_bytes([matrix[8], matrix[9]]) as usize;
+    assert_eq!((10 + header_length) % 64, 0);
+    assert_eq!(matrix.len(), 10 + header_length + 3 * AWKWARD.len() * 4);
+    let header = std::str::from_utf8(&matrix[10..10 + header_length]).unwrap();
+    assert!(header.starts_with("{'descr': '<f4', 'fortran_order': False, 'shape': (3, 8), }"));
+    assert!(header.ends_with('\n'));
+
+    let (shape, values) = npy::read_matrix(&matrix).unwrap();
+    assert_eq!(shape, [3, 8]);
+    assert_eq!(values[..8], AWKWARD);
+
+    assert_same_dataset(&npy::read(&matrix, &sidecar).unwrap(), &dataset);
+}
+
+#[test]
+fn test_safetensors_round_trips() {
+    let dataset = sample();
+    let bytes = safetensors::write(&dataset).unwrap();
+
+    // The reference implementation accepts the file and sees the same data.
+    let tensors = ::safetensors::SafeTensors::deserialize(&bytes).unwrap();
+    let tensor = tensors.tensor(safetensors::TENSOR).unwrap();
+    assert_eq!(tensor.dtype(), ::safetensors::Dtype::F32);
+    assert_eq!(tensor.shape(), [3, 8]);
+    let first_row: Vec<f32> = tensor.data()[..32]
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+    assert_eq!(first_row, AWKWARD);
+
+    let (_, metadata) = ::safetensors::SafeTensors::read_metadata(&bytes).unwrap();
+    let fields = metadata.metadata().as_ref().unwrap();
+    assert_eq!(fields["metric"], "DotProduct");
+    assert_eq!(fields["ids"], r#"["canto-1","2","bare"]"#);
+
+    assert_same_dataset(&safetensors::read(&bytes).unwrap(), &dataset);
+}
+
+#[test]
+fn test_qdrant_points_round_trip() {
+    let dataset = sample();
+    let json = qdrant::write(&dataset).unwrap();
+
+    let body: Value = serde_json::from_str(&json).unwrap();
+    let point = &body["points"][0];
+    assert_eq!(point["id"], 0);
+    assert_eq!(point["payload"]["chunk_id"], "canto-1");
+    assert_eq!(point["payload"]["label"], "Canto I");
+    assert_eq!(
+        qdrant::collection(&dataset),
+        json!({"vectors": {"size": 8, "distance": "Dot"}})
+    );
+
+    assert_same_chunks(&qdrant::read(&json).unwrap(), &dataset.chunks);
+}
+
+#[test]
+fn test_chroma_upsert_round_trips() {
+    let dataset = sample();
+    let json = chroma::write(&dataset).unwrap();
+
+    let body: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(body["ids"], json!(["canto-1", "2", "bare"]));
+    assert_eq!(body["metadatas"][0]["attr:title"], "shadowed by the field");
+    assert_eq!(body["metadatas"][0]["title"], "Divina Commedia: Inferno");
+    assert_eq!(body["metadatas"][1], json!({"text_excerpt": ""}));
+    assert_eq!(body["metadatas"][2], Value::Null);
+    assert_eq!(body["documents"][2], "");
+    assert_eq!(chroma::collection(&dataset)["metadata"]["hnsw:space"], "ip");
+
+    assert_same_chunks(&chroma::read(&json).unwrap(), &dataset.chunks);
+}
+
+#[test]
+fn test_export_writes_files_per_format() {
+    let dataset = sample();
+    let dir = std::env::temp_dir().join(format!("getem-export-{}", std::process::id()));
+
+    let files = export(&dataset, "npy".parse().unwrap(), &dir).unwrap();
+    assert_eq!(
+        files,
+        [
+            dir.join("inferno_cantos.npy"),
+            dir.join("inferno_cantos.json")
+        ]
+    );
+    let matrix = fs::read(&files[0]).unwrap();
+    let sidecar = fs::read_to_string(&files[1]).unwrap();
+    assert_same_dataset(&npy::read(&matrix, &sidecar).unwrap(), &dataset);
+
+    let files = export(&dataset, Format::Safetensors, &dir).unwrap();
+    assert_eq!(files, [dir.join("inferno_cantos.safetensors")]);
+    assert_same_dataset(
+        &safetensors::read(&fs::read(&files[0]).unwrap()).unwrap(),
+        &dataset,
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+    assert!("parquet".parse::<Format>().is_err());
+}
+
+#[test]
+fn test_unexportable_vectors_are_rejected() {
+    let mut dataset = sample();
+    dataset.chunks[1].vector[3] = f32::NAN;
+    assert_eq!(
+        jsonl::write(&dataset),
+        Err(ExportError::NonFinite("2".to_owned()))
+    );
+
+    dataset.chunks[1].vector.pop();
+    assert_eq!(
+        safetensors::write(&dataset),
+        Err(ExportError::DimensionMismatch {
+            chunk_id: "2".to_owned(),
+            expected: 8,
+            found: 7,
+        })
+    );
+}
+
+#[test]
+fn test_mismatched_sidecar_is_rejected() {
+    let dataset = sample();
+    let (matrix, _) = npy::write(&dataset).unwrap();
+    let mut short = dataset.clone();
+    short.chunks.pop();
+    let (_, sidecar) = npy::write(&short).unwrap();
+
+    assert!(matches!(
+        npy::read(&matrix, &sidecar),
+        Err(ExportError::Format(_))
+    ));
+    assert!(matches!(
+        safetensors::read(&matrix),
+        Err(ExportError::Format(_))
+    ));
+}
+
+// ---------------- Fetch ----------------
+
+/// Stands in for a node serving `get_dataset` and `get_dataset_chunks`
+/// for one dataset.
+fn mock_node(dataset: Dataset, encrypted: bool) -> (String, Log) {
+    stand_in(move |method, args| match method {
+        "get_dataset" => Ok(json!({
+            "name": dataset.name,
+            "owner": "seller",
+            "model": dataset.model,
+            "dimension": dataset.dimension,
+            "metric": dataset.metric,
+            "chunk_count": dataset.chunks.len(),
+            "encrypted": encrypted,
+        })),
+        "get_dataset_chunks" => {
+            let offset = args["offset"].as_u64().unwrap() as usize;
+            let limit = args["limit"].as_u64().unwrap() as usize;
+            Ok(json!(dataset
+                .chunks
+                .iter()
+                .skip(offset)
+                .take(limit)
+                .collect::<Vec<_>>()))
+        }
+        method => panic!("unexpected call {}", method),
+    })
+}
+
+/// Each call's method, arguments and JSON-RPC method.
+fn calls(log: &Log) -> Vec<Value> {
+    log.lock()
+        .unwrap()
+        .iter()
+        .map(|call| json!([call.method(), call.args(), call.body["method"]]))
+        .collect()
+}
+
+#[test]
+fn test_fetch_reads_every_page() {
+    let dataset = sample();
+    let (url, log) = mock_node(dataset.clone(), false);
+    let client = RpcClient::new(&url, "context-1", "buyer");
+
+    let fetched = fetch(&client, &dataset.name, 2).unwrap();
+    assert_same_dataset(&fetched, &dataset);
+
+    assert_eq!(
+        calls(&log),
+        [
+            json!(["get_dataset", {"name": dataset.name}, "query"]),
+            json!(["get_dataset_chunks", {"name": dataset.name, "offset": 0, "limit": 2}, "query"]),
+            json!(["get_dataset_chunks", {"name": dataset.name, "offset": 2, "limit": 2}, "query"]),
+        ]
+    );
+}
+
+#[test]
+fn test_encrypted_datasets_are_not_fetched() {
+    let dataset = sample();
+    let (url, log) = mock_node(dataset.clone(), true);
+    let client = RpcClient::new(&url, "context-1", "buyer");
+
+    assert_eq!(
+        fetch(&client, &dataset.name, 2),
+        Err(ExportError::Encrypted(dataset.name.clone()))
+    );
+    assert_eq!(calls(&log).len(), 1);
+}
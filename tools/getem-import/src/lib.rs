@@ -0,0 +1,298 @@
+//! Imports the output of `tools/embeddings_generator` into getem datasets.
+//!
+//! The generator writes two shapes of JSON:
+//!
+//! - `embeddings.json`: `{ "sentences": [...], "embeddings": [[...], ...] }`,
+//!   one sentence per embedding.
+//! - `dante_inferno_embeddings.json`: a list of records with `chunk_id`,
+//!   `title`, `canto`, `label`, `text_excerpt`, `full_text` and `embedding`.
+//!
+//! Both are mapped onto the app's `EmbeddingChunk`, checked for a single
+//! dimension, and uploaded with `upload_dataset` followed by as many
+//! `append_dataset_chunks` calls as the batch size requires.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub use getem_types::{ChunkMetadata, DistanceMetric, EmbeddingChunk};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+pub mod rpc;
+
+#[cfg(test)]
+mod tests;
+
+use rpc::RpcClient;
+
+/// The model `embeddings_generator` encodes with.
+pub const DEFAULT_MODEL: &str = "all-MiniLM-L6-v2";
+
+/// Chunks sent per JSON-RPC call. Each call is one transaction on the
+/// node, so very large batches are slow to replicate.
+pub const DEFAULT_BATCH_SIZE: usize = 64;
+
+// ---------------- App Model ----------------
+
+/// A dataset ready to upload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dataset {
+    pub name: String,
+    pub model: String,
+    pub metric: DistanceMetric,
+    pub dimension: u32,
+    pub chunks: Vec<EmbeddingChunk>,
+}
+
+impl Dataset {
+    /// Reads either generator format and checks that every vector has the
+    /// same dimension.
+    pub fn from_json(
+        name: &str,
+        model: &str,
+        metric: DistanceMetric,
+        json: &str,
+    ) -> Result<Self, ImportError> {
+        let chunks = parse(json)?;
+        let dimension = dimension(&chunks)?;
+        Ok(Dataset {
+            name: name.to_owned(),
+            model: model.to_owned(),
+            metric,
+            dimension,
+            chunks,
+        })
+    }
+}
+
+// ---------------- Generator Formats ----------------
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Input {
+    Sentences {
+        sentences: Vec<String>,
+        embeddings: Vec<Vec<f32>>,
+    },
+    Records(Vec<Record>),
+}
+
+/// One entry of `dante_inferno_embeddings.json`. Ids and cantos are
+/// numbers or strings depending on the script version.
+#[derive(Deserialize)]
+struct Record {
+    chunk_id: Value,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    canto: Option<Value>,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    text_excerpt: Option<String>,
+    #[serde(default)]
+    full_text: Option<String>,
+    embedding: Vec<f32>,
+}
+
+/// Maps either format onto app chunks, in file order.
+pub fn parse(json: &str) -> Result<Vec<EmbeddingChunk>, ImportError> {
+    let input: Input = serde_json::from_str(json).map_err(|_| {
+        ImportError::Format(
+            "expected {sentences, embeddings} or a list of chunk records".to_owned(),
+        )
+    })?;
+
+    let chunks = match input {
+        Input::Sentences {
+            sentences,
+            embeddings,
+        } => {
+            if sentences.len() != embeddings.len() {
+                return Err(ImportError::Format(format!(
+                    "{} sentences but {} embeddings",
+                    sentences.len(),
+                    embeddings.len()
+                )));
+            }
+            sentences
+                .into_iter()
+                .zip(embeddings)
+                .enumerate()
+                .map(|(index, (sentence, vector))| EmbeddingChunk {
+                    chunk_id: format!("sentence-{}", index),
+                    metadata: ChunkMetadata {
+                        text_excerpt: Some(sentence),
+                        ..ChunkMetadata::default()
+                    },
+                    vector,
+                    sealed: None,
+                })
+                .collect()
+        }
+        Input::Records(records) => records
+            .into_iter()
+            .map(Record::into_chunk)
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    for chunk in &chunks {
+        if !seen.insert(chunk.chunk_id.as_str()) {
+            return Err(ImportError::DuplicateChunkId(chunk.chunk_id.clone()));
+        }
+    }
+    Ok(chunks)
+}
+
+impl Record {
+    fn into_chunk(self) -> Result<EmbeddingChunk, ImportError> {
+        let chunk_id = scalar(&self.chunk_id)
+            .ok_or_else(|| ImportError::Format("chunk_id must be a string or number".to_owned()))?;
+
+        let mut attributes = BTreeMap::new();
+        if let Some(canto) = self.canto.as_ref().and_then(scalar) {
+            attributes.insert("canto".to_owned(), canto);
+        }
+        if let Some(full_text) = self.full_text {
+            attributes.insert("full_text".to_owned(), full_text);
+        }
+
+        Ok(EmbeddingChunk {
+            chunk_id,
+            metadata: ChunkMetadata {
+                title: self.title,
+                label: self.label,
+                text_excerpt: self.text_excerpt,
+                attributes,
+            },
+            vector: self.embedding,
+            sealed: None,
+        })
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) if !value.is_empty() => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// The dimension shared by every vector. The app rejects a dataset with
+/// mixed dimensions, so this catches it before anything is uploaded.
+pub fn dimension(chunks: &[EmbeddingChunk]) -> Result<u32, ImportError> {
+    let first = chunks.first().ok_or(ImportError::Empty)?;
+    let expected = first.vector.len();
+    if expected == 0 {
+        return Err(ImportError::Format(format!(
+            "chunk {} has an empty embedding",
+            first.chunk_id
+        )));
+    }
+
+    for chunk in chunks {
+        if chunk.vector.len() != expected {
+            return Err(ImportError::DimensionMismatch {
+                chunk_id: chunk.chunk_id.clone(),
+                expected,
+                found: chunk.vector.len(),
+            });
+        }
+    }
+    u32::try_from(expected).map_err(|_| ImportError::Format("dimension too large".to_owned()))
+}
+
+// ---------------- Upload ----------------
+
+/// What `upload` did.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UploadReport {
+    pub chunk_count: u64,
+    pub calls: usize,
+}
+
+/// Creates the dataset with its first batch of chunks and appends the
+/// rest. Stops at the first call the node rejects; chunks sent before it
+/// stay in the dataset.
+pub fn upload(
+    client: &RpcClient,
+    dataset: &Dataset,
+    batch_size: usize,
+) -> Result<UploadReport, ImportError> {
+    let mut batches = dataset.chunks.chunks(batch_size.max(1));
+    let first = batches.next().ok_or(ImportError::Empty)?;
+
+    let mut info = client.execute(
+        "upload_dataset",
+        json!({
+            "name": dataset.name,
+            "model": dataset.model,
+            "dimension": dataset.dimension,
+            "metric": dataset.metric,
+            "chunks": first,
+        }),
+    )?;
+    let mut calls = 1;
+
+    for batch in batches {
+        info = client.execute(
+            "append_dataset_chunks",
+            json!({
+                "name": dataset.name,
+                "chunks": batch,
+            }),
+        )?;
+        calls += 1;
+    }
+
+    let chunk_count = info
+        .get("chunk_count")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| ImportError::Rpc(format!("unexpected dataset info: {}", info)))?;
+    Ok(UploadReport { chunk_count, calls })
+}
+
+// ---------------- Errors ----------------
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ImportError {
+    /// The input holds no chunks.
+    Empty,
+    /// The input is not in either generator format.
+    Format(String),
+    DuplicateChunkId(String),
+    DimensionMismatch {
+        chunk_id: String,
+        expected: usize,
+        found: usize,
+    },
+    /// The node could not be reached or did not answer JSON-RPC.
+    Http(String),
+    /// The node answered with an error.
+    Rpc(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Empty => f.write_str("no chunks to import"),
+            ImportError::Format(reason) => write!(f, "unsupported input: {}", reason),
+            ImportError::DuplicateChunkId(id) => write!(f, "chunk id {} appears twice", id),
+            ImportError::DimensionMismatch {
+                chunk_id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "chunk {} has dimension {}, expected {}",
+                chunk_id, found, expected
+            ),
+            ImportError::Http(reason) => write!(f, "request failed: {}", reason),
+            ImportError::Rpc(reason) => write!(f, "node returned an error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
@@ -0,0 +1,133 @@
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+use getem_import::rpc::RpcClient;
+use getem_import::{upload, Dataset, DistanceMetric, DEFAULT_BATCH_SIZE, DEFAULT_MODEL};
+
+const USAGE: &str = "\
+Usage: getem-import <FILE> --name <DATASET> [options]
+
+Reads embeddings.json ({sentences, embeddings}) or a list of chunk records
+(dante_inferno_embeddings.json) and uploads it as a getem dataset.
+
+Options:
+  --name <DATASET>        Dataset name in the catalog (required)
+  --model <MODEL>         Embedding model [default: all-MiniLM-L6-v2]
+  --metric <METRIC>       cosine, dot or euclidean [default: cosine]
+  --batch-size <N>        Chunks per JSON-RPC call [default: 64]
+  --node-url <URL>        Node API address [env: NODE_URL, default: http://localhost:2428]
+  --context-id <ID>       Context to upload into [env: CONTEXT_ID]
+  --executor <KEY>        Executor public key [env: EXECUTOR_PUBLIC_KEY]
+  --token <JWT>           Bearer token for the node [env: NODE_TOKEN]
+  --dry-run               Check the file without uploading
+";
+
+struct Args {
+    file: String,
+    name: String,
+    model: String,
+    metric: DistanceMetric,
+    batch_size: usize,
+    node_url: String,
+    context_id: Option<String>,
+    executor: Option<String>,
+    token: Option<String>,
+    dry_run: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut file = None;
+    let mut name = None;
+    let mut args = Args {
+        file: String::new(),
+        name: String::new(),
+        model: DEFAULT_MODEL.to_owned(),
+        metric: DistanceMetric::default(),
+        batch_size: DEFAULT_BATCH_SIZE,
+        node_url: env::var("NODE_URL").unwrap_or_else(|_| "http://localhost:2428".to_owned()),
+        context_id: env::var("CONTEXT_ID").ok(),
+        executor: env::var("EXECUTOR_PUBLIC_KEY").ok(),
+        token: env::var("NODE_TOKEN").ok(),
+        dry_run: false,
+    };
+
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--name" => name = Some(value()?),
+            "--model" => args.model = value()?,
+            "--metric" => args.metric = value()?.parse().map_err(|err| format!("{}", err))?,
+            "--batch-size" => {
+                args.batch_size = value()?
+                    .parse()
+                    .map_err(|_| "--batch-size must be a number".to_owned())?
+            }
+            "--node-url" => args.node_url = value()?,
+            "--context-id" => args.context_id = Some(value()?),
+            "--executor" => args.executor = Some(value()?),
+            "--token" => args.token = Some(value()?),
+            "--dry-run" => args.dry_run = true,
+            "-h" | "--help" => return Err(String::new()),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+            path if file.is_none() => file = Some(path.to_owned()),
+            extra => return Err(format!("unexpected argument {}", extra)),
+        }
+    }
+
+    args.file = file.ok_or("missing input file")?;
+    args.name = name.ok_or("--name is required")?;
+    Ok(args)
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let json = fs::read_to_string(&args.file).map_err(|err| format!("{}: {}", args.file, err))?;
+    let dataset = Dataset::from_json(&args.name, &args.model, args.metric, &json)
+        .map_err(|err| format!("{}: {}", args.file, err))?;
+    println!(
+        "{}: {} chunks of dimension {}",
+        args.file,
+        dataset.chunks.len(),
+        dataset.dimension
+    );
+    if args.dry_run {
+        return Ok(());
+    }
+
+    let context_id = args
+        .context_id
+        .ok_or("--context-id or CONTEXT_ID is required")?;
+    let executor = args
+        .executor
+        .ok_or("--executor or EXECUTOR_PUBLIC_KEY is required")?;
+    let client = RpcClient::new(&args.node_url, &context_id, &executor).with_token(args.token);
+
+    let report = upload(&client, &dataset, args.batch_size).map_err(|err| err.to_string())?;
+    println!(
+        "Uploaded {} as {} ({} chunks in {} calls)",
+        args.file, dataset.name, report.chunk_count, report.calls
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            if !err.is_empty() {
+                eprintln!("error: {}\n", err);
+            }
+            eprint!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! A minimal client for a Calimero node's JSON-RPC endpoint, enough to
+//! call the app's methods in one context as one identity.
+
+use std::cell::Cell;
+
+use serde_json::{json, Value};
+
+use crate::ImportError;
+
+/// Where the node serves JSON-RPC, as in the frontend's `VITE_RPC_PATH`.
+pub const DEFAULT_RPC_PATH: &str = "/jsonrpc";
+
+pub struct RpcClient {
+    endpoint: String,
+    context_id: String,
+    executor_public_key: String,
+    token: Option<String>,
+    next_id: Cell<u64>,
+}
+
+impl RpcClient {
+    /// `node_url` is the node's API address, e.g. `http://localhost:2428`.
+    pub fn new(node_url: &str, context_id: &str, executor_public_key: &str) -> Self {
+        RpcClient {
+            endpoint: format!("{}{}", node_url.trim_end_matches('/'), DEFAULT_RPC_PATH),
+            context_id: context_id.to_owned(),
+            executor_public_key: executor_public_key.to_owned(),
+            token: None,
+            next_id: Cell::new(1),
+        }
+    }
+
+    /// Sends `token` as a bearer token, for nodes that require a JWT.
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Calls a method that changes the app state.
+    pub fn execute(&self, method: &str, args: Value) -> Result<Value, ImportError> {
+        self.call("execute", method, args)
+    }
+
+    /// Calls a method that only reads the app state.
+    pub fn query(&self, method: &str, args: Value) -> Result<Value, ImportError> {
+        self.call("query", method, args)
+    }
+
+    fn call(&self, rpc_method: &str, method: &str, args: Value) -> Result<Value, ImportError> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": rpc_method,
+            "params": {
+                "contextId": self.context_id,
+                "method": method,
+                "argsJson": args,
+                "executorPublicKey": self.executor_public_key,
+            },
+        });
+
+        let mut request = ureq::post(&self.endpoint);
+        if let Some(token) = &self.token {
+            request = request.set("Authorization", &format!("Bearer {}", token));
+        }
+        let response: Value = match request.send_json(body) {
+            Ok(response) => response
+                .into_json()
+                .map_err(|err| ImportError::Http(err.to_string()))?,
+            Err(ureq::Error::Status(status, response)) => {
+                let text = response.into_string().unwrap_or_default();
+                return Err(ImportError::Http(format!("{} {}", status, text)));
+            }
+            Err(err) => return Err(ImportError::Http(err.to_string())),
+        };
+
+        if let Some(error) = response.get("error").filter(|error| !error.is_null()) {
+            return Err(ImportError::Rpc(error.to_string()));
+        }
+        response
+            .get("result")
+            .and_then(|result| result.get("output"))
+            .cloned()
+            .ok_or_else(|| ImportError::Rpc(format!("no output in {}", response)))
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+use std::net::TcpListener;
+
+use super::*;
+
+use getem_test_support::{stand_in, Log, Received};
+
+const SENTENCES: &str = r#"{
+    "sentences": ["first", "second", "third"],
+    "embeddings": [[1.0, 0.0], [0.0, 1.0], [0.5, 0.5]]
+}"#;
+
+const RECORDS: &str = r#"[
+    {
+        "chunk_id": 1,
+        "title": "Divina Commedia: Inferno",
+        "canto": "1",
+        "label": "Canto 1",
+        "text_excerpt": "Midway upon the journey",
+        "full_text": "Midway upon the journey of our life",
+        "embedding": [0.1, 0.2, 0.3]
+    },
+    {
+        "chunk_id": "2",
+        "canto": 2,
+        "embedding": [0.4, 0.5, 0.6]
+    }
+]"#;
+
+fn chunks(count: usize) -> Vec<EmbeddingChunk> {
+    (0..count)
+        .map(|index| EmbeddingChunk {
+            chunk_id: index.to_string(),
+            metadata: ChunkMetadata::default(),
+            vector: vec![index as f32, 1.0],
+            sealed: None,
+        })
+        .collect()
+}
+
+fn dataset(name: &str, count: usize) -> Dataset {
+    Dataset {
+        name: name.to_owned(),
+        model: DEFAULT_MODEL.to_owned(),
+        metric: DistanceMetric::Cosine,
+        dimension: 2,
+        chunks: chunks(count),
+    }
+}
+
+// ---------------- Formats ----------------
+
+#[test]
+fn test_sentences_become_numbered_chunks() {
+    let chunks = parse(SENTENCES).unwrap();
+
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[1].chunk_id, "sentence-1");
+    assert_eq!(chunks[1].metadata.text_excerpt.as_deref(), Some("second"));
+    assert_eq!(chunks[1].vector, [0.0, 1.0]);
+    assert_eq!(dimension(&chunks), Ok(2));
+}
+
+#[test]
+fn test_records_keep_their_metadata() {
+    let chunks = parse(RECORDS).unwrap();
+
+    assert_eq!(chunks.len(), 2);
+    let first = &chunks[0];
+    assert_eq!(first.chunk_id, "1");
+    assert_eq!(
+        first.metadata.title.as_deref(),
+        Some("Divina Commedia: Inferno")
+    );
+    assert_eq!(first.metadata.label.as_deref(), Some("Canto 1"));
+    assert_eq!(
+        first.metadata.text_excerpt.as_deref(),
+        Some("Midway upon the journey")
+    );
+    assert_eq!(first.metadata.attributes["canto"], "1");
+    assert_eq!(
+        first.metadata.attributes["full_text"],
+        "Midway upon the journey of our life"
+    );
+
+    // Numbers and strings are both accepted for ids and cantos.
+    assert_eq!(chunks[1].chunk_id, "2");
+    assert_eq!(chunks[1].metadata.attributes["canto"], "2");
+    assert_eq!(chunks[1].metadata.title, None);
+}
+
+#[test]
+fn test_generator_output_in_the_repo_parses() {
+    let sentences = include_str!("../../embeddings_generator/embeddings.json");
+    let dataset = Dataset::from_json(
+        "sentences",
+        DEFAULT_MODEL,
+        DistanceMetric::Cosine,
+        sentences,
+    )
+    .unwrap();
+    assert_eq!((dataset.chunks.len(), dataset.dimension), (3, 384));
+
+    let inferno = include_str!("../../embeddings_generator/scripts/dante_inferno_embeddings.json");
+    let dataset =
+        Dataset::from_json("inferno", DEFAULT_MODEL, DistanceMetric::Cosine, inferno).unwrap();
+    assert_eq!(dataset.dimension, 384);
+    assert!(dataset
+        .chunks
+        .iter()
+        .all(|chunk| chunk.metadata.attributes.contains_key("canto")));
+}
+
+#[test]
+fn test_mixed_dimensions_are_rejected() {
+    let json = r#"[
+        {"chunk_id": "a", "embedding": [1.0, 2.0]},
+        {"chunk_id": "b", "embedding": [1.0, 2.0, 3.0]}
+    ]"#;
+
+    assert_eq!(
+        Dataset::from_json("mixed", DEFAULT_MODEL, DistanceMetric::Cosine, json),
+        Err(ImportError::DimensionMismatch {
+            chunk_id: "b".to_owned(),
+            expected: 2,
+            found: 3,
+        })
+    );
+}
+
+#[test]
+fn test_malformed_input_is_rejected() {
+    let uneven = r#"{"sentences": ["one", "two"], "embeddings": [[1.0]]}"#;
+    assert!(matches!(parse(uneven), Err(ImportError::Format(_))));
+    assert!(matches!(
+        parse(r#"{"vectors": []}"#),
+        Err(ImportError::Format(_))
+    ));
+    assert_eq!(dimension(&parse("[]").unwrap()), Err(ImportError::Empty));
+
+    let duplicated = r#"[
+        {"chunk_id": 7, "embedding": [1.0]},
+        {"chunk_id": "7", "embedding": [2.0]}
+    ]"#;
+    assert_eq!(
+        parse(duplicated),
+        Err(ImportError::DuplicateChunkId("7".to_owned()))
+    );
+}
+
+#[test]
+fn test_metric_names() {
+    assert_eq!("cosine".parse(), Ok(DistanceMetric::Cosine));
+    assert_eq!("dot".parse(), Ok(DistanceMetric::DotProduct));
+    assert_eq!("Euclidean".parse(), Ok(DistanceMetric::Euclidean));
+    assert!("manhattan".parse::<DistanceMetric>().is_err());
+}
+
+// ---------------- Upload ----------------
+
+/// Stands in for a node running the app: it keeps a chunk count per
+/// dataset and answers like `upload_dataset` / `append_dataset_chunks`.
+/// Appending to a dataset named "reject" fails.
+fn mock_node() -> (String, Log) {
+    let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+    stand_in(move |method, args| {
+        let name = args["name"].as_str().unwrap().to_owned();
+        let added = args["chunks"].as_array().unwrap().len() as u64;
+        if method == "append_dataset_chunks" && name == "reject" {
+            return Err(json!({"type": "FunctionCallError", "data": "InvalidDataset"}));
+        }
+
+        let count = counts.entry(name.clone()).or_default();
+        if method == "upload_dataset" {
+            *count = 0;
+        }
+        *count += added;
+        Ok(json!({"name": name, "chunk_count": *count}))
+    })
+}
+
+#[test]
+fn test_upload_creates_then_appends_in_batches() {
+    let (url, received) = mock_node();
+    let client = RpcClient::new(&url, "context-1", "executor-1").with_token(Some("jwt".to_owned()));
+
+    let report = upload(&client, &dataset("vectors", 5), 2).unwrap();
+    assert_eq!(
+        report,
+        UploadReport {
+            chunk_count: 5,
+            calls: 3
+        }
+    );
+
+    let received = received.lock().unwrap();
+    let methods: Vec<&str> = received.iter().map(Received::method).collect();
+    assert_eq!(
+        methods,
+        [
+            "upload_dataset",
+            "append_dataset_chunks",
+            "append_dataset_chunks"
+        ]
+    );
+
+    let first = &received[0];
+    assert_eq!(first.authorization.as_deref(), Some("Bearer jwt"));
+    assert_eq!(first.body["jsonrpc"], "2.0");
+    assert_eq!(first.body["method"], "execute");
+    assert_eq!(first.body["params"]["contextId"], "context-1");
+    assert_eq!(first.body["params"]["executorPublicKey"], "executor-1");
+    let args = first.args();
+    assert_eq!(args["model"], DEFAULT_MODEL);
+    assert_eq!(args["dimension"], 2);
+    assert_eq!(args["metric"], "Cosine");
+    assert_eq!(args["chunks"][1]["chunk_id"], "1");
+    assert_eq!(args["chunks"][1]["vector"], json!([1.0, 1.0]));
+    assert_eq!(received[2].args()["chunks"][0]["chunk_id"], "4");
+}
+
+#[test]
+fn test_upload_stops_at_the_first_rejected_call() {
+    let (url, received) = mock_node();
+    let client = RpcClient::new(&url, "context-1", "executor-1");
+
+    let err = upload(&client, &dataset("reject", 5), 2).unwrap_err();
+    assert!(matches!(&err, ImportError::Rpc(reason) if reason.contains("InvalidDataset")));
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].authorization, None);
+}
+
+#[test]
+fn test_unreachable_node_is_an_http_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let client = RpcClient::new(&url, "context-1", "executor-1");
+    assert!(matches!(
+        upload(&client, &dataset("vectors", 1), 2),
+        Err(ImportError::Http(_))
+    ));
+}
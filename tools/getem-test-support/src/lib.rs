@@ -0,0 +1,107 @@
+//! Helpers shared by the tests of the tools in this workspace.
+//!
+//! [`stand_in`] plays a node running the app, so the JSON-RPC clients can
+//! be tested without one.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde_json::{json, Value};
+
+/// A call the stand-in node received: its auth header and JSON-RPC body.
+pub struct Received {
+    pub authorization: Option<String>,
+    pub body: Value,
+}
+
+impl Received {
+    /// The app method called, e.g. `upload_dataset`.
+    pub fn method(&self) -> &str {
+        self.body["params"]["method"].as_str().unwrap_or_default()
+    }
+
+    pub fn args(&self) -> &Value {
+        &self.body["params"]["argsJson"]
+    }
+}
+
+/// The output of a call, or the JSON-RPC error to send back instead.
+pub type Answer = Result<Value, Value>;
+
+/// Every call the stand-in received, oldest first.
+pub type Log = Arc<Mutex<Vec<Received>>>;
+
+/// Stands in for a node running the app and returns its URL: every call
+/// is answered by `answer(method, argsJson)`, with an `Err` sent back as a
+/// JSON-RPC error, and logged.
+pub fn stand_in(mut answer: impl FnMut(&str, &Value) -> Answer + Send + 'static) -> (String, Log) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let log = Arc::clone(&received);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            assert!(
+                request_line.starts_with("POST /jsonrpc "),
+                "{}",
+                request_line
+            );
+
+            let mut length = 0;
+            let mut authorization = None;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                let (header, value) = line.split_once(':').unwrap();
+                match header.to_ascii_lowercase().as_str() {
+                    "content-length" => length = value.trim().parse().unwrap(),
+                    "authorization" => authorization = Some(value.trim().to_owned()),
+                    _ => {}
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let call = Received {
+                authorization,
+                body: serde_json::from_slice(&body).unwrap(),
+            };
+
+            let reply = match answer(call.method(), call.args()) {
+                Ok(output) => json!({
+                    "jsonrpc": "2.0",
+                    "id": call.body["id"],
+                    "result": {"output": output},
+                }),
+                Err(error) => json!({
+                    "jsonrpc": "2.0",
+                    "id": call.body["id"],
+                    "error": error,
+                }),
+            };
+            log.lock().unwrap().push(call);
+
+            let reply = reply.to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                reply.len(),
+                reply
+            )
+            .unwrap();
+        }
+    });
+
+    (url, received)
+}
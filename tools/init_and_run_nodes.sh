@@ -2,10 +2,12 @@
 set -e
 
 # Configuration
-APPLICATION_WASM_PATH="./src/backend/res/hello_app.wasm"
+APPLICATION_WASM_PATH="./src/logic/res/exchange_app.wasm"
 SESSION_NAME="calimero_nodes"
 base_dir="$HOME/.calimero"
 USE_TMUX=1
+# Approvals the context proxy requires; the app is told at init
+NUM_APPROVALS="${NUM_APPROVALS:-3}"
 
 # Global variables for context and keys
 member_public_key=""
@@ -138,7 +140,8 @@ install_application() {
 create_context() {
     local input_app_id=$1
     echo "Creating context..." >&2
-    context_output=$(meroctl --node-name ${nodes[0]} context create --application-id "$input_app_id" --protocol "icp")
+    context_output=$(meroctl --node-name ${nodes[0]} context create --application-id "$input_app_id" --protocol "icp" \
+        --params "{\"num_approvals\": $NUM_APPROVALS}")
     echo "Context creation output:" >&2
     echo "$context_output" >&2
 
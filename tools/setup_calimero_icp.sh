@@ -6,9 +6,11 @@ set -e
 # Simple protocol definition
 PROTOCOL="icp"
 OUTPUT_FILE=".env"
+# Approvals the context proxy requires; the app is told at init
+NUM_APPROVALS="${NUM_APPROVALS:-3}"
 
 echo "Installing application into the node ..."
-full_output=$(meroctl --node-name node1 app install -p ../src/backend/src/res/hello_app.wasm)
+full_output=$(meroctl --node-name node1 app install -p ../src/logic/res/exchange_app.wasm)
 echo "Full command output:"
 echo "$full_output"
 
@@ -21,7 +23,8 @@ echo "Application ID: $app_id"
 
 # Create context and save output
 echo "Creating context..."
-context_output=$(meroctl --node-name node1 context create --application-id "$app_id" --protocol "$PROTOCOL")
+context_output=$(meroctl --node-name node1 context create --application-id "$app_id" --protocol "$PROTOCOL" \
+    --params "{\"num_approvals\": $NUM_APPROVALS}")
 echo "Context creation output:"
 echo "$context_output"
 